@@ -0,0 +1,30 @@
+// This is free and unencumbered software released into the public domain.
+
+/// A target size for record batches, in rows or in (approximate) bytes of
+/// array memory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BatchSize {
+    Rows(usize),
+    Bytes(usize),
+}
+
+impl BatchSize {
+    /// Returns the size of a single row of the given batch in terms of this
+    /// target, i.e., 1 for row counts or the average row size for bytes.
+    pub(crate) fn row_weight(&self, batch: &arrow_array::RecordBatch) -> usize {
+        match self {
+            BatchSize::Rows(_) => 1,
+            BatchSize::Bytes(_) => batch
+                .get_array_memory_size()
+                .div_ceil(batch.num_rows().max(1))
+                .max(1),
+        }
+    }
+
+    pub(crate) fn target(&self) -> usize {
+        match self {
+            BatchSize::Rows(rows) => *rows,
+            BatchSize::Bytes(bytes) => *bytes,
+        }
+    }
+}
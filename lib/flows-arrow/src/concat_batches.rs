@@ -1,31 +1,60 @@
 // This is free and unencumbered software released into the public domain.
 
+use super::{SchemaMode, SchemaUnifier};
 use alloc::vec::Vec;
 use arrow_array::RecordBatch;
-use async_flow::{Inputs, Output, Port, Result};
+use async_flow::{Inputs, Output, Outputs, Port, Result};
 use flows_derive::block;
+use flows_error::BlockError;
 
 /// A block that concatenates input batches into a single output batch.
+///
+/// See `rebatch` for a block that instead outputs batches of a target size
+/// as the input streams in.
+///
+/// Batches with a schema that is incompatible with the preceding batches, as
+/// determined by the given schema mode, are reported on the `errors` port and
+/// left out of the output batch. Given `SchemaMode::Unify`, the output batch
+/// has the unified schema of the remaining batches.
 #[block]
 pub async fn concat_batches(
+    mode: SchemaMode,
     mut inputs: Inputs<RecordBatch>,
     output: Output<RecordBatch>,
+    errors: Outputs<BlockError>,
 ) -> Result {
+    let mut unifier = SchemaUnifier::new(mode);
     let mut batches: Vec<RecordBatch> = Vec::new();
 
     while let Some(batch) = inputs.recv().await? {
         if batch.num_rows() == 0 && !batches.is_empty() {
             continue; // skip empty batches after the first one
         }
-        batches.push(batch);
+        match unifier.merge(batch.schema_ref()) {
+            Ok(_) => batches.push(batch),
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("concat_batches", error))
+                    .await?
+            },
+            Err(_) => continue,
+        }
     }
 
-    if !batches.is_empty() {
-        let schema = batches[0].schema();
-        let batch = arrow_select::concat::concat_batches(&schema, &batches).unwrap();
-
-        if !output.is_closed() {
-            output.send(batch).await?;
+    if let Some(schema) = unifier.schema() {
+        let result = batches
+            .iter()
+            .map(|batch| unifier.align(batch))
+            .collect::<core::result::Result<Vec<_>, _>>()
+            .and_then(|batches| arrow_select::concat::concat_batches(schema, &batches));
+        match result {
+            Ok(batch) if !output.is_closed() => output.send(batch).await?,
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("concat_batches", error))
+                    .await?
+            },
+            _ => {},
         }
     }
 
@@ -43,7 +72,8 @@ mod tests {
     #[tokio::test]
     async fn test_concat_batches() -> Result<(), Box<dyn Error>> {
         let (mut in_, mut out) = (Channel::bounded(1), Channel::oneshot());
-        let concatter = tokio::spawn(concat_batches(in_.rx, out.tx));
+        let mut err = Channel::bounded(1);
+        let concatter = tokio::spawn(concat_batches(SchemaMode::Strict, in_.rx, out.tx, err.tx));
 
         let batch = record_batch!(("n", Int32, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]))?;
         in_.tx.send(batch.clone()).await?;
@@ -60,6 +90,33 @@ mod tests {
             assert_eq!(output.num_columns(), 1);
         }
 
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_concat_batches_unified() -> Result<(), Box<dyn Error>> {
+        let (mut in_, mut out) = (Channel::bounded(10), Channel::oneshot());
+        let mut err = Channel::bounded(10);
+        let concatter = tokio::spawn(concat_batches(SchemaMode::Unify, in_.rx, out.tx, err.tx));
+
+        in_.tx.send(record_batch!(("n", Int32, [1, 2]))?).await?;
+        in_.tx
+            .send(record_batch!(("s", Utf8, ["x"]), ("n", Float64, [3.5]))?)
+            .await?;
+        in_.tx.send(record_batch!(("s", Int32, [4]))?).await?; // incompatible
+        in_.tx.close();
+
+        let _ = tokio::join!(concatter);
+
+        let expected = record_batch!(
+            ("n", Float64, [Some(1.0), Some(2.0), Some(3.5)]),
+            ("s", Utf8, [None, None, Some("x")])
+        )?;
+        assert_eq!(out.rx.recv_all().await?, vec![expected]);
+        assert_eq!(err.rx.recv_all().await?.len(), 1);
+
         Ok(())
     }
 }
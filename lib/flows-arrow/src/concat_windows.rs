@@ -0,0 +1,73 @@
+// This is free and unencumbered software released into the public domain.
+
+use alloc::vec::Vec;
+use arrow_array::RecordBatch;
+use async_flow::{Inputs, Outputs, Port, Result};
+use flows_derive::block;
+use flows_error::BlockError;
+
+/// A block that concatenates each input window of batches into a single
+/// output batch.
+///
+/// This is intended to follow one of the `flows-window` blocks, turning the
+/// windows it outputs back into a stream of record batches.
+///
+/// Windows that fail to concatenate (e.g., due to mismatched schemas) are
+/// reported on the `errors` port.
+#[block]
+pub async fn concat_windows(
+    mut inputs: Inputs<Vec<RecordBatch>>,
+    outputs: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    while let Some(window) = inputs.recv().await? {
+        let Some(first) = window.first() else {
+            continue; // skip empty windows
+        };
+
+        let schema = first.schema();
+        match arrow_select::concat::concat_batches(&schema, &window) {
+            Ok(batch) if !outputs.is_closed() => outputs.send(batch).await?,
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("concat_windows", error))
+                    .await?
+            },
+            _ => continue,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use arrow_array::record_batch;
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_concat_windows() -> Result<(), Box<dyn Error>> {
+        let (mut in_, mut out) = (Channel::bounded(10), Channel::bounded(10));
+        let mut err = Channel::bounded(1);
+        let concatter = tokio::spawn(concat_windows(in_.rx, out.tx, err.tx));
+
+        let batch = record_batch!(("n", Int32, [1, 2, 3]))?;
+        in_.tx.send(vec![batch.clone(), batch.clone()]).await?;
+        in_.tx.send(vec![batch.clone()]).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(concatter);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[0].num_rows(), 6);
+        assert_eq!(outputs[1].num_rows(), 3);
+
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+// This is free and unencumbered software released into the public domain.
+
+/// The dialect of CSV data that the `decode_csv` and `encode_csv` blocks
+/// read and write.
+///
+/// The default is comma-delimited records with a header row, using double
+/// quotes for quoting and doubled quotes for escaping.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CsvOptions {
+    pub has_header: bool,
+    pub delimiter: u8,
+    pub quote: u8,
+    pub escape: Option<u8>,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CsvOptions {
+    pub const fn new() -> Self {
+        Self {
+            has_header: true,
+            delimiter: b',',
+            quote: b'"',
+            escape: None,
+        }
+    }
+
+    /// Sets whether the first record is a header row of column names.
+    pub const fn with_header(self, has_header: bool) -> Self {
+        Self { has_header, ..self }
+    }
+
+    /// Sets the field delimiter.
+    pub const fn with_delimiter(self, delimiter: u8) -> Self {
+        Self { delimiter, ..self }
+    }
+
+    /// Sets the quote character.
+    pub const fn with_quote(self, quote: u8) -> Self {
+        Self { quote, ..self }
+    }
+
+    /// Sets the escape character used for quotes within quoted fields,
+    /// instead of doubling them.
+    pub const fn with_escape(self, escape: u8) -> Self {
+        Self {
+            escape: Some(escape),
+            ..self
+        }
+    }
+
+    pub(crate) fn format(&self) -> arrow_csv::reader::Format {
+        let format = arrow_csv::reader::Format::default()
+            .with_header(self.has_header)
+            .with_delimiter(self.delimiter)
+            .with_quote(self.quote);
+        match self.escape {
+            Some(escape) => format.with_escape(escape),
+            None => format,
+        }
+    }
+
+    pub(crate) fn reader(&self) -> csv_core::Reader {
+        csv_core::ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .escape(self.escape)
+            .double_quote(self.escape.is_none())
+            .build()
+    }
+
+    pub(crate) fn writer(&self) -> arrow_csv::WriterBuilder {
+        let builder = arrow_csv::WriterBuilder::new()
+            .with_delimiter(self.delimiter)
+            .with_quote(self.quote);
+        match self.escape {
+            Some(escape) => builder.with_escape(escape).with_double_quote(false),
+            None => builder,
+        }
+    }
+}
@@ -0,0 +1,251 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{
+    CsvOptions, SchemaSource,
+    row_batcher::{Row, RowBatcher, RowFormat},
+};
+use alloc::{format, vec::Vec};
+use arrow_array::RecordBatch;
+use arrow_csv::ReaderBuilder;
+use arrow_schema::{ArrowError, Schema, SchemaRef};
+use async_flow::{Inputs, Outputs, Port, Result};
+use csv_core::ReadRecordResult;
+use flows_derive::block;
+use flows_error::BlockError;
+
+/// A block that decodes CSV data from bytes inputs, outputting record
+/// batches of (at most) `batch_size` rows.
+///
+/// The inputs may be arbitrarily chunked, even in the middle of a quoted
+/// field. The schema is either given, or inferred from the initial rows (in
+/// which case the header row, if any, provides the column names). Rows that
+/// fail to decode against the schema are reported on the `errors` port,
+/// numbered from 1 excluding the header row, while the remaining rows of
+/// their batch are output as usual. A zero `batch_size`, or a failure to
+/// infer the schema, is reported on the `errors` port, after which the block
+/// exits.
+#[block]
+pub async fn decode_csv(
+    options: CsvOptions,
+    schema: SchemaSource,
+    batch_size: usize,
+    mut inputs: Inputs<Vec<u8>>,
+    outputs: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    let mut splitter = CsvSplitter::new(&options);
+    let mut batcher = match RowBatcher::new(CsvFormat::new(options), schema, batch_size) {
+        Ok(batcher) => batcher,
+        Err(error) => {
+            if errors.is_connected() {
+                errors
+                    .send(BlockError::from_error("decode_csv", error))
+                    .await?;
+            }
+            return Ok(());
+        },
+    };
+
+    let mut finished = false;
+    while !finished {
+        match inputs.recv().await? {
+            Some(input) => splitter.split(&input),
+            None => {
+                splitter.finish();
+                finished = true;
+            },
+        }
+        for record in splitter.records.drain(..) {
+            match record {
+                Row { number: 0, bytes } => batcher.format_mut().header = Some(bytes),
+                row => batcher.push(row),
+            }
+        }
+
+        loop {
+            let decoded = match batcher.next_batch(finished) {
+                Ok(Some(decoded)) => decoded,
+                Ok(None) => break,
+                Err(error) => {
+                    if errors.is_connected() {
+                        errors
+                            .send(BlockError::from_error("decode_csv", error))
+                            .await?;
+                    }
+                    return Ok(());
+                },
+            };
+            if let Some(batch) = decoded.batch
+                && !outputs.is_closed()
+            {
+                outputs.send(batch).await?;
+            }
+            if errors.is_connected() {
+                for (row, error) in decoded.errors {
+                    let message = format!("row {row}: {error}");
+                    errors
+                        .send(BlockError::new("decode_csv", message).with_source(error))
+                        .await?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Splits CSV data into its raw records, respecting quoted fields.
+///
+/// The header row, if any, is numbered 0.
+struct CsvSplitter {
+    reader: csv_core::Reader,
+    record: Vec<u8>,
+    records: Vec<Row>,
+    next_number: usize,
+}
+
+impl CsvSplitter {
+    fn new(options: &CsvOptions) -> Self {
+        Self {
+            reader: options.reader(),
+            record: Vec::new(),
+            records: Vec::new(),
+            next_number: if options.has_header { 0 } else { 1 },
+        }
+    }
+
+    fn split(&mut self, mut input: &[u8]) {
+        // Note that an empty input would signal the end of the data:
+        while !input.is_empty() {
+            let (_, consumed) = self.read(input);
+            input = &input[consumed..];
+        }
+    }
+
+    fn finish(&mut self) {
+        while self.read(&[]).0 != ReadRecordResult::End {}
+    }
+
+    /// Reads (part of) a record from the given input, returning the number
+    /// of bytes consumed along with the reader's state.
+    fn read(&mut self, input: &[u8]) -> (ReadRecordResult, usize) {
+        // The parsed fields are discarded, as only the raw records are needed:
+        let (mut output, mut ends) = ([0; 1024], [0; 128]);
+        let (result, consumed, _, _) = self.reader.read_record(input, &mut output, &mut ends);
+        self.record.extend_from_slice(&input[..consumed]);
+        match result {
+            ReadRecordResult::Record => {
+                self.records.push(Row {
+                    number: self.next_number,
+                    bytes: core::mem::take(&mut self.record),
+                });
+                self.next_number += 1;
+            },
+            ReadRecordResult::End => self.record.clear(), // only blank lines remain
+            _ => {},
+        }
+        (result, consumed)
+    }
+}
+
+struct CsvFormat {
+    options: CsvOptions,
+    header: Option<Vec<u8>>,
+}
+
+impl CsvFormat {
+    fn new(options: CsvOptions) -> Self {
+        Self {
+            options,
+            header: None,
+        }
+    }
+}
+
+impl RowFormat for CsvFormat {
+    fn infer_schema(
+        &self,
+        rows: &[u8],
+        max_rows: usize,
+    ) -> core::result::Result<Schema, ArrowError> {
+        let format = self.options.format().with_header(self.header.is_some());
+        let mut sample = self.header.clone().unwrap_or_default();
+        sample.extend_from_slice(rows);
+        let (schema, _) = format.infer_schema(sample.as_slice(), Some(max_rows))?;
+        Ok(schema)
+    }
+
+    fn decode(
+        &self,
+        schema: &SchemaRef,
+        mut rows: &[u8],
+        num_rows: usize,
+    ) -> core::result::Result<Option<RecordBatch>, ArrowError> {
+        let mut decoder = ReaderBuilder::new(schema.clone())
+            .with_format(self.options.format().with_header(false))
+            .with_batch_size(num_rows)
+            .build_decoder();
+        while !rows.is_empty() {
+            match decoder.decode(rows)? {
+                0 => break,
+                consumed => rows = &rows[consumed..],
+            }
+        }
+        decoder.decode(&[])?; // end of data
+        decoder.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, sync::Arc, vec};
+    use arrow_array::record_batch;
+    use arrow_schema::{DataType, Field};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_decode_csv() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(100);
+        let (mut out, mut err) = (Channel::bounded(10), Channel::bounded(10));
+        let decoder = tokio::spawn(decode_csv(
+            CsvOptions::new().with_delimiter(b';'),
+            SchemaSource::Infer(2),
+            2,
+            in_.rx,
+            out.tx,
+            err.tx,
+        ));
+
+        let csv = b"id;name\n1;\"a;b\"\n2;c\nx;d\n3;\"e\nf\"";
+        for chunk in csv.chunks(3) {
+            in_.tx.send(chunk.to_vec()).await?;
+        }
+        in_.tx.close();
+
+        let _ = tokio::join!(decoder);
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, true),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let expected = record_batch!(
+            ("id", Int64, [1, 2, 3]),
+            ("name", Utf8, ["a;b", "c", "e\nf"])
+        )?;
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs.len(), 2);
+        assert!(outputs.iter().all(|batch| batch.schema() == schema));
+        assert_eq!(
+            arrow_select::concat::concat_batches(&schema, &outputs)?,
+            expected
+        );
+
+        let errors = err.rx.recv_all().await?;
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message().starts_with("row 3:"));
+
+        Ok(())
+    }
+}
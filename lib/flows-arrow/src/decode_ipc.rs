@@ -0,0 +1,240 @@
+// This is free and unencumbered software released into the public domain.
+
+use alloc::vec::Vec;
+use arrow_array::RecordBatch;
+use arrow_buffer::Buffer;
+use arrow_ipc::reader::StreamDecoder;
+use async_flow::{Inputs, Outputs, Port, Result};
+use flows_derive::block;
+use flows_error::BlockError;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// A block that decodes Arrow IPC streaming format from bytes inputs,
+/// outputting the record batches it contains.
+///
+/// The inputs may be arbitrarily chunked. The stream's schema and dictionary
+/// messages are tracked across inputs, so that dictionary-encoded columns
+/// decode correctly in every batch. Malformed or truncated streams are
+/// reported on the `errors` port, after which the block exits.
+#[block]
+pub async fn decode_ipc_stream(
+    mut inputs: Inputs<Vec<u8>>,
+    outputs: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    let mut decoder = StreamDecoder::new();
+
+    while let Some(input) = inputs.recv().await? {
+        let mut buffer = Buffer::from(input);
+        while !buffer.is_empty() {
+            match decoder.decode(&mut buffer) {
+                Ok(Some(batch)) if !outputs.is_closed() => outputs.send(batch).await?,
+                Ok(_) => continue,
+                Err(error) => {
+                    if errors.is_connected() {
+                        errors
+                            .send(BlockError::from_error("decode_ipc_stream", error))
+                            .await?;
+                    }
+                    return Ok(());
+                },
+            }
+        }
+    }
+
+    if let Err(error) = decoder.finish()
+        && errors.is_connected()
+    {
+        errors
+            .send(BlockError::from_error("decode_ipc_stream", error))
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// A block that decodes Arrow IPC file format from bytes inputs, outputting
+/// the record batches it contains.
+///
+/// Since the file footer is located at the end of the file, the inputs are
+/// buffered until the input is closed. Malformed files are reported on the
+/// `errors` port.
+#[cfg(feature = "std")]
+#[block]
+pub async fn decode_ipc_file(
+    mut inputs: Inputs<Vec<u8>>,
+    outputs: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    use arrow_ipc::reader::FileReader;
+    use std::io::Cursor;
+
+    let mut bytes: Vec<u8> = Vec::new();
+    while let Some(input) = inputs.recv().await? {
+        bytes.extend(input);
+    }
+
+    if bytes.is_empty() {
+        return Ok(()); // no input
+    }
+
+    let reader = match FileReader::try_new(Cursor::new(bytes), None) {
+        Ok(reader) => reader,
+        Err(error) => {
+            if errors.is_connected() {
+                errors
+                    .send(BlockError::from_error("decode_ipc_file", error))
+                    .await?;
+            }
+            return Ok(());
+        },
+    };
+
+    for batch in reader {
+        match batch {
+            Ok(batch) if !outputs.is_closed() => outputs.send(batch).await?,
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("decode_ipc_file", error))
+                    .await?
+            },
+            _ => continue,
+        }
+    }
+
+    Ok(())
+}
+
+/// A block that reads Arrow IPC streaming format from an async reader,
+/// outputting the record batches it contains.
+///
+/// I/O failures as well as malformed or truncated streams are reported on
+/// the `errors` port, after which the block exits.
+#[block]
+pub async fn read_ipc_stream<R>(
+    mut reader: R,
+    outputs: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result
+where
+    R: AsyncRead + Unpin,
+{
+    const CHUNK_SIZE: usize = 64 * 1024;
+
+    let mut decoder = StreamDecoder::new();
+
+    loop {
+        let mut chunk = Vec::with_capacity(CHUNK_SIZE);
+        match reader.read_buf(&mut chunk).await {
+            Ok(0) => break, // end of stream
+            Ok(_) => {},
+            Err(error) => {
+                if errors.is_connected() {
+                    errors
+                        .send(BlockError::from_error("read_ipc_stream", error))
+                        .await?;
+                }
+                return Ok(());
+            },
+        }
+
+        let mut buffer = Buffer::from(chunk);
+        while !buffer.is_empty() {
+            match decoder.decode(&mut buffer) {
+                Ok(Some(batch)) if !outputs.is_closed() => outputs.send(batch).await?,
+                Ok(_) => continue,
+                Err(error) => {
+                    if errors.is_connected() {
+                        errors
+                            .send(BlockError::from_error("read_ipc_stream", error))
+                            .await?;
+                    }
+                    return Ok(());
+                },
+            }
+        }
+    }
+
+    if let Err(error) = decoder.finish()
+        && errors.is_connected()
+    {
+        errors
+            .send(BlockError::from_error("read_ipc_stream", error))
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, sync::Arc, vec};
+    use arrow_array::{DictionaryArray, types::Int32Type};
+    use arrow_ipc::writer::StreamWriter;
+    use arrow_schema::{DataType, Field, Schema};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_decode_ipc_stream() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(100);
+        let (mut out, mut err) = (Channel::bounded(10), Channel::bounded(10));
+        let decoder = tokio::spawn(decode_ipc_stream(in_.rx, out.tx, err.tx));
+
+        let batches = sample_batches()?;
+        let bytes = encode(&batches)?;
+        for chunk in bytes.chunks(7) {
+            in_.tx.send(chunk.to_vec()).await?;
+        }
+        in_.tx.close();
+
+        let _ = tokio::join!(decoder);
+
+        assert_eq!(out.rx.recv_all().await?, batches);
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[tokio::test]
+    async fn test_read_ipc_stream() -> Result<(), Box<dyn Error>> {
+        let (mut out, mut err) = (Channel::bounded(10), Channel::bounded(10));
+
+        let batches = sample_batches()?;
+        let mut bytes = encode(&batches)?;
+        bytes.truncate(bytes.len() - 3);
+
+        let reader = tokio::spawn(read_ipc_stream(std::io::Cursor::new(bytes), out.tx, err.tx));
+        let _ = tokio::join!(reader);
+
+        assert_eq!(out.rx.recv_all().await?, batches);
+        assert_eq!(err.rx.recv_all().await?.len(), 1); // truncated stream
+
+        Ok(())
+    }
+
+    fn sample_batches() -> Result<Vec<RecordBatch>, Box<dyn Error>> {
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "tag",
+            DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+            false,
+        )]));
+        let tags1: DictionaryArray<Int32Type> = vec!["a", "b", "a"].into_iter().collect();
+        let tags2: DictionaryArray<Int32Type> = vec!["c", "c"].into_iter().collect();
+        Ok(vec![
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(tags1)])?,
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(tags2)])?,
+        ])
+    }
+
+    fn encode(batches: &[RecordBatch]) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut writer = StreamWriter::try_new(Vec::new(), &batches[0].schema())?;
+        for batch in batches {
+            writer.write(batch)?;
+        }
+        writer.finish()?;
+        Ok(writer.into_inner()?)
+    }
+}
@@ -0,0 +1,196 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{
+    SchemaSource,
+    row_batcher::{Row, RowBatcher, RowFormat},
+};
+use alloc::{format, vec::Vec};
+use arrow_array::RecordBatch;
+use arrow_json::ReaderBuilder;
+use arrow_schema::{ArrowError, Schema, SchemaRef};
+use async_flow::{Inputs, Outputs, Port, Result};
+use flows_derive::block;
+use flows_error::BlockError;
+
+/// A block that decodes newline-delimited JSON (NDJSON) from bytes inputs,
+/// outputting record batches of (at most) `batch_size` rows.
+///
+/// The inputs may be arbitrarily chunked, and blank lines are skipped. The
+/// schema is either given, or inferred from the initial lines. Lines that
+/// fail to decode against the schema are reported on the `errors` port,
+/// numbered from 1, while the remaining lines of their batch are output as
+/// usual. A zero `batch_size`, or a failure to infer the schema, is reported
+/// on the `errors` port, after which the block exits.
+#[block]
+pub async fn decode_ndjson(
+    schema: SchemaSource,
+    batch_size: usize,
+    mut inputs: Inputs<Vec<u8>>,
+    outputs: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    let mut batcher = match RowBatcher::new(NdjsonFormat, schema, batch_size) {
+        Ok(batcher) => batcher,
+        Err(error) => {
+            if errors.is_connected() {
+                errors
+                    .send(BlockError::from_error("decode_ndjson", error))
+                    .await?;
+            }
+            return Ok(());
+        },
+    };
+    let (mut line, mut number) = (Vec::new(), 0);
+
+    let mut finished = false;
+    while !finished {
+        let input = match inputs.recv().await? {
+            Some(input) => input,
+            None => {
+                finished = true;
+                Vec::new()
+            },
+        };
+
+        for bytes in input.split_inclusive(|&byte| byte == b'\n') {
+            line.extend_from_slice(bytes);
+            if line.ends_with(b"\n") {
+                number += 1;
+                push_line(&mut batcher, number, &mut line);
+            }
+        }
+        if finished && !line.is_empty() {
+            number += 1;
+            push_line(&mut batcher, number, &mut line); // unterminated last line
+        }
+
+        loop {
+            let decoded = match batcher.next_batch(finished) {
+                Ok(Some(decoded)) => decoded,
+                Ok(None) => break,
+                Err(error) => {
+                    if errors.is_connected() {
+                        errors
+                            .send(BlockError::from_error("decode_ndjson", error))
+                            .await?;
+                    }
+                    return Ok(());
+                },
+            };
+            if let Some(batch) = decoded.batch
+                && !outputs.is_closed()
+            {
+                outputs.send(batch).await?;
+            }
+            if errors.is_connected() {
+                for (line, error) in decoded.errors {
+                    let message = format!("line {line}: {error}");
+                    errors
+                        .send(BlockError::new("decode_ndjson", message).with_source(error))
+                        .await?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn push_line(batcher: &mut RowBatcher<NdjsonFormat>, number: usize, line: &mut Vec<u8>) {
+    if line.iter().all(u8::is_ascii_whitespace) {
+        line.clear(); // skip blank lines
+    } else {
+        batcher.push(Row {
+            number,
+            bytes: core::mem::take(line),
+        });
+    }
+}
+
+struct NdjsonFormat;
+
+impl RowFormat for NdjsonFormat {
+    fn infer_schema(
+        &self,
+        rows: &[u8],
+        max_rows: usize,
+    ) -> core::result::Result<Schema, ArrowError> {
+        let (schema, _) = arrow_json::reader::infer_json_schema(rows, Some(max_rows))?;
+        Ok(schema)
+    }
+
+    fn decode(
+        &self,
+        schema: &SchemaRef,
+        rows: &[u8],
+        num_rows: usize,
+    ) -> core::result::Result<Option<RecordBatch>, ArrowError> {
+        let mut decoder = ReaderBuilder::new(schema.clone())
+            .with_batch_size(num_rows)
+            .build_decoder()?;
+        decoder.decode(rows)?;
+        decoder.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use arrow_array::record_batch;
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_decode_ndjson() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(100);
+        let (mut out, mut err) = (Channel::bounded(10), Channel::bounded(10));
+        let batch = record_batch!(("a", Int64, [1, 2, 3]), ("b", Utf8, ["x", "y", "z"]))?;
+        let decoder = tokio::spawn(decode_ndjson(
+            SchemaSource::Fixed(batch.schema()),
+            10,
+            in_.rx,
+            out.tx,
+            err.tx,
+        ));
+
+        let ndjson = b"{\"a\":1,\"b\":\"x\"}\n\n{\"a\":\"oops\"}\n{\"a\":2,\"b\":\"y\"}\n{\"a\":3,\"b\":\"z\"}";
+        for chunk in ndjson.chunks(5) {
+            in_.tx.send(chunk.to_vec()).await?;
+        }
+        in_.tx.close();
+
+        let _ = tokio::join!(decoder);
+
+        assert_eq!(out.rx.recv_all().await?, vec![batch]);
+
+        let errors = err.rx.recv_all().await?;
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message().starts_with("line 3:"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_decode_ndjson_zero_batch_size() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let (mut out, mut err) = (Channel::bounded(10), Channel::bounded(10));
+        let decoder = tokio::spawn(decode_ndjson(
+            SchemaSource::Infer(10),
+            0,
+            in_.rx,
+            out.tx,
+            err.tx,
+        ));
+
+        in_.tx.send(b"{\"a\":1}\n".to_vec()).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(decoder);
+
+        assert!(out.rx.recv_all().await?.is_empty());
+        assert_eq!(err.rx.recv_all().await?.len(), 1);
+
+        Ok(())
+    }
+}
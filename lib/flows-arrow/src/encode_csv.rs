@@ -0,0 +1,91 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::CsvOptions;
+use alloc::vec::Vec;
+use arrow_array::RecordBatch;
+use arrow_schema::{ArrowError, SchemaRef};
+use async_flow::{Inputs, Outputs, Port, Result};
+use flows_derive::block;
+use flows_error::BlockError;
+
+/// A block that encodes input batches to CSV, outputting the encoded bytes
+/// of each batch.
+///
+/// If the options call for a header row, it is output ahead of the first
+/// batch, with the column names taken from the first batch's schema. Batches
+/// with a different schema, or with columns of a type that CSV can't
+/// represent, are reported on the `errors` port.
+#[block]
+pub async fn encode_csv(
+    options: CsvOptions,
+    mut inputs: Inputs<RecordBatch>,
+    outputs: Outputs<Vec<u8>>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    let mut schema: Option<SchemaRef> = None;
+
+    while let Some(batch) = inputs.recv().await? {
+        match encode(&options, &mut schema, &batch) {
+            Ok(bytes) if !outputs.is_closed() => outputs.send(bytes).await?,
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("encode_csv", error))
+                    .await?
+            },
+            _ => continue,
+        }
+    }
+
+    Ok(())
+}
+
+fn encode(
+    options: &CsvOptions,
+    schema: &mut Option<SchemaRef>,
+    batch: &RecordBatch,
+) -> core::result::Result<Vec<u8>, ArrowError> {
+    let has_header = match schema {
+        Some(schema) if *schema != batch.schema() => {
+            return Err(ArrowError::SchemaError(
+                "batch schema differs from the first batch".into(),
+            ));
+        },
+        Some(_) => false,
+        None => options.has_header,
+    };
+
+    let mut writer = options.writer().with_header(has_header).build(Vec::new());
+    writer.write(batch)?;
+    schema.get_or_insert_with(|| batch.schema());
+    Ok(writer.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use arrow_array::record_batch;
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_encode_csv() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let (mut out, mut err) = (Channel::bounded(10), Channel::bounded(10));
+        let encoder = tokio::spawn(encode_csv(CsvOptions::new(), in_.rx, out.tx, err.tx));
+
+        let batch = record_batch!(("a", Int32, [1, 2]), ("b", Utf8, ["x,y", "z"]))?;
+        in_.tx.send(batch.clone()).await?;
+        in_.tx.send(batch.project(&[0])?).await?; // mismatched schema
+        in_.tx.send(batch.slice(1, 1)).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(encoder);
+
+        let bytes: Vec<u8> = out.rx.recv_all().await?.concat();
+        assert_eq!(bytes, b"a,b\n1,\"x,y\"\n2,z\n2,z\n");
+        assert_eq!(err.rx.recv_all().await?.len(), 1);
+
+        Ok(())
+    }
+}
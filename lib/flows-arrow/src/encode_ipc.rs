@@ -0,0 +1,204 @@
+// This is free and unencumbered software released into the public domain.
+
+use alloc::vec::Vec;
+use arrow_array::RecordBatch;
+use arrow_ipc::writer::{FileWriter, StreamWriter};
+use arrow_schema::{ArrowError, SchemaRef};
+use async_flow::{Inputs, Outputs, Port, Result};
+use flows_derive::block;
+use flows_error::BlockError;
+
+/// A block that encodes input batches to Arrow IPC streaming format,
+/// outputting the encoded bytes as they are produced.
+///
+/// The stream's schema is taken from the first batch and is written ahead of
+/// it, along with any dictionaries that the batches reference. Batches with
+/// a different schema are reported on the `errors` port.
+#[block]
+pub async fn encode_ipc_stream(
+    mut inputs: Inputs<RecordBatch>,
+    outputs: Outputs<Vec<u8>>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    let mut writer: Option<(SchemaRef, StreamWriter<Vec<u8>>)> = None;
+
+    while let Some(batch) = inputs.recv().await? {
+        let (schema, writer) = match &mut writer {
+            Some(writer) => writer,
+            None => match StreamWriter::try_new(Vec::new(), &batch.schema()) {
+                Ok(stream_writer) => writer.insert((batch.schema(), stream_writer)),
+                Err(error) => {
+                    if errors.is_connected() {
+                        errors
+                            .send(BlockError::from_error("encode_ipc_stream", error))
+                            .await?;
+                    }
+                    continue;
+                },
+            },
+        };
+
+        match check_schema(schema, &batch).and_then(|_| writer.write(&batch)) {
+            Ok(_) if !outputs.is_closed() => {
+                outputs.send(core::mem::take(writer.get_mut())).await?
+            },
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("encode_ipc_stream", error))
+                    .await?
+            },
+            _ => continue,
+        }
+    }
+
+    if let Some((_, mut writer)) = writer {
+        match writer.finish() {
+            Ok(_) if !outputs.is_closed() => {
+                outputs.send(core::mem::take(writer.get_mut())).await?
+            },
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("encode_ipc_stream", error))
+                    .await?
+            },
+            _ => {},
+        }
+    }
+
+    Ok(())
+}
+
+/// A block that encodes input batches to Arrow IPC file format, outputting
+/// the encoded bytes as they are produced.
+///
+/// The file's schema is taken from the first batch, and its footer is output
+/// once the input is closed. Batches with a different schema, as well as
+/// batches that would replace a previously written dictionary (which the
+/// file format doesn't support), are reported on the `errors` port.
+#[block]
+pub async fn encode_ipc_file(
+    mut inputs: Inputs<RecordBatch>,
+    outputs: Outputs<Vec<u8>>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    let mut writer: Option<(SchemaRef, FileWriter<Vec<u8>>)> = None;
+
+    while let Some(batch) = inputs.recv().await? {
+        let (schema, writer) = match &mut writer {
+            Some(writer) => writer,
+            None => match FileWriter::try_new(Vec::new(), &batch.schema()) {
+                Ok(file_writer) => writer.insert((batch.schema(), file_writer)),
+                Err(error) => {
+                    if errors.is_connected() {
+                        errors
+                            .send(BlockError::from_error("encode_ipc_file", error))
+                            .await?;
+                    }
+                    continue;
+                },
+            },
+        };
+
+        match check_schema(schema, &batch).and_then(|_| writer.write(&batch)) {
+            Ok(_) if !outputs.is_closed() => {
+                outputs.send(core::mem::take(writer.get_mut())).await?
+            },
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("encode_ipc_file", error))
+                    .await?
+            },
+            _ => continue,
+        }
+    }
+
+    if let Some((_, mut writer)) = writer {
+        match writer.finish() {
+            Ok(_) if !outputs.is_closed() => {
+                outputs.send(core::mem::take(writer.get_mut())).await?
+            },
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("encode_ipc_file", error))
+                    .await?
+            },
+            _ => {},
+        }
+    }
+
+    Ok(())
+}
+
+fn check_schema(schema: &SchemaRef, batch: &RecordBatch) -> core::result::Result<(), ArrowError> {
+    if *schema == batch.schema() {
+        Ok(())
+    } else {
+        Err(ArrowError::SchemaError(
+            "batch schema differs from the first batch".into(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use arrow_array::record_batch;
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_encode_ipc_stream() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let (mut out, mut err) = (Channel::bounded(10), Channel::bounded(10));
+        let encoder = tokio::spawn(encode_ipc_stream(in_.rx, out.tx, err.tx));
+
+        let batch = record_batch!(("a", Int32, [1, 2, 3]), ("b", Utf8, ["x", "y", "z"]))?;
+        in_.tx.send(batch.clone()).await?;
+        in_.tx.send(batch.project(&[0])?).await?; // mismatched schema
+        in_.tx.send(batch.slice(1, 2)).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(encoder);
+
+        let bytes: Vec<u8> = out.rx.recv_all().await?.concat();
+        let reader = arrow_ipc::reader::StreamReader::try_new(bytes.as_slice(), None)?;
+        let batches = reader.collect::<core::result::Result<Vec<_>, _>>()?;
+        assert_eq!(batches, vec![batch.clone(), batch.slice(1, 2)]);
+
+        assert_eq!(err.rx.recv_all().await?.len(), 1);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[tokio::test]
+    async fn test_encode_ipc_file() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let (mut out, mut err) = (Channel::bounded(10), Channel::bounded(10));
+        let encoder = tokio::spawn(encode_ipc_file(in_.rx, out.tx, err.tx));
+
+        let batch = record_batch!(("a", Int32, [1, 2, 3]))?;
+        in_.tx.send(batch.clone()).await?;
+        in_.tx.send(batch.clone()).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(encoder);
+
+        let bytes: Vec<u8> = out.rx.recv_all().await?.concat();
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        let mut in_ = Channel::bounded(1);
+        let (mut out, mut err) = (Channel::bounded(10), Channel::bounded(10));
+        let decoder = tokio::spawn(crate::decode_ipc_file(in_.rx, out.tx, err.tx));
+        in_.tx.send(bytes).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(decoder);
+
+        assert_eq!(out.rx.recv_all().await?, vec![batch.clone(), batch]);
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+}
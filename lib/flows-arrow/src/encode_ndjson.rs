@@ -0,0 +1,73 @@
+// This is free and unencumbered software released into the public domain.
+
+use alloc::vec::Vec;
+use arrow_array::RecordBatch;
+use arrow_json::LineDelimitedWriter;
+use async_flow::{Inputs, Outputs, Port, Result};
+use flows_derive::block;
+use flows_error::BlockError;
+
+/// A block that encodes input batches to newline-delimited JSON (NDJSON),
+/// outputting the encoded bytes of each batch.
+///
+/// Each row is encoded as a JSON object on a line of its own, omitting null
+/// values. Batches with columns of a type that JSON can't represent are
+/// reported on the `errors` port.
+#[block]
+pub async fn encode_ndjson(
+    mut inputs: Inputs<RecordBatch>,
+    outputs: Outputs<Vec<u8>>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    let mut writer = LineDelimitedWriter::new(Vec::new());
+
+    while let Some(batch) = inputs.recv().await? {
+        let result = writer.write(&batch);
+        let bytes = core::mem::take(writer.get_mut());
+        match result {
+            Ok(_) if !outputs.is_closed() => outputs.send(bytes).await?,
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("encode_ndjson", error))
+                    .await?
+            },
+            _ => continue,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use arrow_array::record_batch;
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_encode_ndjson() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let (mut out, mut err) = (Channel::bounded(10), Channel::bounded(10));
+        let encoder = tokio::spawn(encode_ndjson(in_.rx, out.tx, err.tx));
+
+        in_.tx
+            .send(record_batch!(
+                ("a", Int32, [Some(1), None]),
+                ("b", Utf8, ["x", "y"])
+            )?)
+            .await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(encoder);
+
+        assert_eq!(
+            out.rx.recv_all().await?,
+            vec![b"{\"a\":1,\"b\":\"x\"}\n{\"b\":\"y\"}\n".to_vec()]
+        );
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+}
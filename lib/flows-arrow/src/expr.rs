@@ -0,0 +1,381 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{Literal, schema_unifier::supertype};
+use alloc::{boxed::Box, format, string::String, sync::Arc, vec::Vec};
+use arrow_arith::{boolean::is_not_null, numeric, temporal};
+use arrow_array::{
+    Array, ArrayRef, GenericStringArray, OffsetSizeTrait, RecordBatch, cast::AsArray,
+    new_null_array,
+};
+use arrow_cast::{CastOptions, cast_with_options};
+use arrow_schema::{ArrowError, DataType};
+use arrow_select::zip::zip;
+use arrow_string::substring::{substring, substring_by_char};
+
+/// An expression over the columns of a record batch, evaluated with Arrow's
+/// compute kernels.
+///
+/// Columns are referenced by name. The operands of arithmetic operators are
+/// converted to a common numeric datatype, with literal operands taking on
+/// the datatype of the other operand unless that would change their value
+/// (see [`Literal::coerce`]), and operations that overflow or divide by zero
+/// are an error. The arguments of `Coalesce` are likewise converted to a
+/// common datatype.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Expr {
+    Column(String),
+    Literal(Literal),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Div(Box<Expr>, Box<Expr>),
+    Rem(Box<Expr>, Box<Expr>),
+    Cast(Box<Expr>, DataType),
+    Upper(Box<Expr>),
+    Lower(Box<Expr>),
+    Substring {
+        expr: Box<Expr>,
+        /// The one-based index of the first character, or, if negative, the
+        /// index counting from the end of the string.
+        start: i64,
+        length: Option<u64>,
+    },
+    DatePart(Box<Expr>, DatePart),
+    Coalesce(Vec<Expr>),
+}
+
+/// A part of a date or timestamp extracted by [`Expr::DatePart`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DatePart {
+    Year,
+    Quarter,
+    Month,
+    Week,
+    Day,
+    /// The day of the week, where Monday is 0.
+    DayOfWeek,
+    DayOfYear,
+    Hour,
+    Minute,
+    Second,
+    Millisecond,
+    Microsecond,
+    Nanosecond,
+}
+
+impl Expr {
+    pub fn column(name: impl Into<String>) -> Self {
+        Self::Column(name.into())
+    }
+
+    pub fn literal(value: impl Into<Literal>) -> Self {
+        Self::Literal(value.into())
+    }
+
+    pub fn cast(self, data_type: DataType) -> Self {
+        Self::Cast(Box::new(self), data_type)
+    }
+
+    pub fn upper(self) -> Self {
+        Self::Upper(Box::new(self))
+    }
+
+    pub fn lower(self) -> Self {
+        Self::Lower(Box::new(self))
+    }
+
+    pub fn substring(self, start: i64, length: Option<u64>) -> Self {
+        Self::Substring {
+            expr: Box::new(self),
+            start,
+            length,
+        }
+    }
+
+    pub fn date_part(self, part: DatePart) -> Self {
+        Self::DatePart(Box::new(self), part)
+    }
+
+    pub fn coalesce(exprs: impl IntoIterator<Item = Expr>) -> Self {
+        Self::Coalesce(exprs.into_iter().collect())
+    }
+
+    /// Evaluates the expression against the given batch, returning an array
+    /// with a value for each of its rows.
+    pub fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef, ArrowError> {
+        use Expr::*;
+        match self {
+            Column(name) => batch
+                .column_by_name(name)
+                .cloned()
+                .ok_or_else(|| ArrowError::SchemaError(format!("column '{name}' not found"))),
+            Literal(value) => Ok(value.to_array_of_len(batch.num_rows())),
+            Add(lhs, rhs) => arithmetic(batch, lhs, rhs, numeric::add),
+            Sub(lhs, rhs) => arithmetic(batch, lhs, rhs, numeric::sub),
+            Mul(lhs, rhs) => arithmetic(batch, lhs, rhs, numeric::mul),
+            Div(lhs, rhs) => arithmetic(batch, lhs, rhs, numeric::div),
+            Rem(lhs, rhs) => arithmetic(batch, lhs, rhs, numeric::rem),
+            Cast(expr, data_type) => cast(&expr.evaluate(batch)?, data_type),
+            Upper(expr) => map_strings(&expr.evaluate(batch)?, str::to_uppercase),
+            Lower(expr) => map_strings(&expr.evaluate(batch)?, str::to_lowercase),
+            Substring {
+                expr,
+                start,
+                length,
+            } => {
+                let array = expr.evaluate(batch)?;
+                // Unlike SQL, Arrow's kernels count from zero:
+                let start = if *start > 0 { start - 1 } else { *start };
+                match array.data_type() {
+                    DataType::Utf8 => Ok(Arc::new(substring_by_char(
+                        array.as_string::<i32>(),
+                        start,
+                        *length,
+                    )?)),
+                    DataType::LargeUtf8 => Ok(Arc::new(substring_by_char(
+                        array.as_string::<i64>(),
+                        start,
+                        *length,
+                    )?)),
+                    _ => substring(&array, start, *length),
+                }
+            },
+            DatePart(expr, part) => temporal::date_part(&expr.evaluate(batch)?, (*part).into()),
+            Coalesce(exprs) => {
+                let arrays = exprs
+                    .iter()
+                    .map(|expr| expr.evaluate(batch))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let data_type = arrays.iter().try_fold(DataType::Null, |result, array| {
+                    common_type(&result, array.data_type())
+                })?;
+                let mut arrays = arrays.into_iter().rev();
+                let last = match arrays.next() {
+                    Some(array) => cast(&array, &data_type)?,
+                    None => new_null_array(&DataType::Null, batch.num_rows()),
+                };
+                arrays.try_fold(last, |result, array| {
+                    let array = cast(&array, &data_type)?;
+                    zip(&is_not_null(&array)?, &array, &result)
+                })
+            },
+        }
+    }
+}
+
+impl core::ops::Add for Expr {
+    type Output = Expr;
+
+    fn add(self, other: Expr) -> Expr {
+        Expr::Add(Box::new(self), Box::new(other))
+    }
+}
+
+impl core::ops::Sub for Expr {
+    type Output = Expr;
+
+    fn sub(self, other: Expr) -> Expr {
+        Expr::Sub(Box::new(self), Box::new(other))
+    }
+}
+
+impl core::ops::Mul for Expr {
+    type Output = Expr;
+
+    fn mul(self, other: Expr) -> Expr {
+        Expr::Mul(Box::new(self), Box::new(other))
+    }
+}
+
+impl core::ops::Div for Expr {
+    type Output = Expr;
+
+    fn div(self, other: Expr) -> Expr {
+        Expr::Div(Box::new(self), Box::new(other))
+    }
+}
+
+impl core::ops::Rem for Expr {
+    type Output = Expr;
+
+    fn rem(self, other: Expr) -> Expr {
+        Expr::Rem(Box::new(self), Box::new(other))
+    }
+}
+
+impl From<DatePart> for temporal::DatePart {
+    fn from(part: DatePart) -> Self {
+        match part {
+            DatePart::Year => Self::Year,
+            DatePart::Quarter => Self::Quarter,
+            DatePart::Month => Self::Month,
+            DatePart::Week => Self::Week,
+            DatePart::Day => Self::Day,
+            DatePart::DayOfWeek => Self::DayOfWeekMonday0,
+            DatePart::DayOfYear => Self::DayOfYear,
+            DatePart::Hour => Self::Hour,
+            DatePart::Minute => Self::Minute,
+            DatePart::Second => Self::Second,
+            DatePart::Millisecond => Self::Millisecond,
+            DatePart::Microsecond => Self::Microsecond,
+            DatePart::Nanosecond => Self::Nanosecond,
+        }
+    }
+}
+
+fn arithmetic(
+    batch: &RecordBatch,
+    lhs: &Expr,
+    rhs: &Expr,
+    kernel: fn(&dyn arrow_array::Datum, &dyn arrow_array::Datum) -> Result<ArrayRef, ArrowError>,
+) -> Result<ArrayRef, ArrowError> {
+    match (lhs, rhs) {
+        (Expr::Literal(lhs), rhs) => {
+            let (rhs, lhs) = lhs.coerce(&rhs.evaluate(batch)?)?;
+            kernel(&lhs, &rhs)
+        },
+        (lhs, Expr::Literal(rhs)) => {
+            let (lhs, rhs) = rhs.coerce(&lhs.evaluate(batch)?)?;
+            kernel(&lhs, &rhs)
+        },
+        (lhs, rhs) => {
+            let (lhs, rhs) = (lhs.evaluate(batch)?, rhs.evaluate(batch)?);
+            // Temporal and decimal operands are left to the kernel to check:
+            if !lhs.data_type().is_numeric() || !rhs.data_type().is_numeric() {
+                return kernel(&lhs, &rhs);
+            }
+            let data_type = common_type(lhs.data_type(), rhs.data_type())?;
+            kernel(&cast(&lhs, &data_type)?, &cast(&rhs, &data_type)?)
+        },
+    }
+}
+
+fn common_type(lhs: &DataType, rhs: &DataType) -> Result<DataType, ArrowError> {
+    supertype(lhs, rhs).ok_or_else(|| {
+        ArrowError::InvalidArgumentError(format!("incompatible datatypes {lhs} and {rhs}"))
+    })
+}
+
+/// Casts the given array to the given datatype, with values that don't
+/// convert being an error rather than null.
+pub(crate) fn cast(array: &ArrayRef, data_type: &DataType) -> Result<ArrayRef, ArrowError> {
+    if array.data_type() == data_type {
+        return Ok(array.clone());
+    }
+    let options = CastOptions {
+        safe: false,
+        ..Default::default()
+    };
+    cast_with_options(array, data_type, &options)
+}
+
+fn map_strings(array: &ArrayRef, f: fn(&str) -> String) -> Result<ArrayRef, ArrowError> {
+    fn map<O: OffsetSizeTrait>(
+        array: &GenericStringArray<O>,
+        f: fn(&str) -> String,
+    ) -> GenericStringArray<O> {
+        array.iter().map(|value| value.map(f)).collect()
+    }
+    match array.data_type() {
+        DataType::Utf8 => Ok(Arc::new(map(array.as_string::<i32>(), f))),
+        DataType::LargeUtf8 => Ok(Arc::new(map(array.as_string::<i64>(), f))),
+        DataType::Utf8View => map_strings(&cast(array, &DataType::Utf8)?, f),
+        data_type => Err(ArrowError::InvalidArgumentError(format!(
+            "expected a string column, got {data_type}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use arrow_array::{
+        Date32Array, Int8Array, Int32Array, Int64Array, StringArray,
+        types::{Float64Type, Int32Type, Int64Type},
+    };
+    use core::error::Error;
+
+    #[test]
+    fn test_expr() -> Result<(), Box<dyn Error>> {
+        let batch = RecordBatch::try_from_iter([
+            ("a", Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrayRef),
+            (
+                "b",
+                Arc::new(Int64Array::from(vec![Some(10), None, Some(30)])),
+            ),
+            (
+                "s",
+                Arc::new(StringArray::from(vec!["Hello", "wörld", "x"])),
+            ),
+            ("d", Arc::new(Date32Array::from(vec![0, 31, 365]))),
+        ])?;
+
+        let sum = (Expr::column("a") + Expr::column("b")).evaluate(&batch)?;
+        assert_eq!(sum.data_type(), &DataType::Int64);
+        assert_eq!(sum.as_primitive::<Int64Type>().value(0), 11);
+        assert!(sum.is_null(1));
+
+        let scaled = (Expr::column("a") * Expr::literal(2)).evaluate(&batch)?;
+        assert_eq!(scaled.as_primitive::<Int32Type>().values(), &[2, 4, 6]);
+        let halved = (Expr::column("a") / Expr::literal(2.0)).evaluate(&batch)?;
+        assert_eq!(
+            halved.as_primitive::<Float64Type>().values(),
+            &[0.5, 1.0, 1.5]
+        );
+        let bytes = RecordBatch::try_from_iter([(
+            "a",
+            Arc::new(Int8Array::from(vec![1, 2, 3])) as ArrayRef,
+        )])?;
+        let sum = (Expr::column("a") + Expr::literal(1000)).evaluate(&bytes)?;
+        assert_eq!(
+            sum.as_primitive::<Int64Type>().values(),
+            &[1001, 1002, 1003]
+        );
+        let sum = (Expr::literal(1) + Expr::column("a")).evaluate(&bytes)?;
+        assert_eq!(sum.data_type(), &DataType::Int8);
+        let zero = Expr::column("a") / Expr::literal(0);
+        assert!(zero.evaluate(&batch).is_err());
+        assert!(Expr::column("z").evaluate(&batch).is_err());
+
+        let upper = Expr::column("s").upper().evaluate(&batch)?;
+        assert_eq!(upper.as_string::<i32>().value(1), "WÖRLD");
+        let sub = Expr::column("s").substring(2, Some(3)).evaluate(&batch)?;
+        let sub = sub.as_string::<i32>();
+        assert_eq!(
+            (sub.value(0), sub.value(1), sub.value(2)),
+            ("ell", "örl", "")
+        );
+
+        let months = Expr::column("d")
+            .date_part(DatePart::Month)
+            .evaluate(&batch)?;
+        assert_eq!(months.as_primitive::<Int32Type>().values(), &[1, 2, 1]);
+
+        let coalesced = Expr::coalesce([Expr::column("b"), Expr::column("a")]).evaluate(&batch)?;
+        assert_eq!(coalesced.as_primitive::<Int64Type>().values(), &[10, 2, 30]);
+
+        let cast = Expr::column("s").cast(DataType::Int32).evaluate(&batch);
+        assert!(cast.is_err());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_expr_serde() -> Result<(), Box<dyn Error>> {
+        let expr = Expr::coalesce([
+            Expr::column("a").cast(DataType::Float64) / Expr::literal(2.0),
+            Expr::literal(Literal::Null),
+        ]);
+        let json = serde_json::to_string(&expr)?;
+        assert_eq!(serde_json::from_str::<Expr>(&json)?, expr);
+        let expr: Expr = serde_json::from_str(r#"{"upper": {"column": "s"}}"#)?;
+        assert_eq!(expr, Expr::column("s").upper());
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::Predicate;
+use alloc::format;
+use arrow_array::{BooleanArray, RecordBatch};
+use arrow_schema::ArrowError;
+use async_flow::{Inputs, Outputs, Port, Result};
+use flows_derive::block;
+use flows_error::BlockError;
+
+/// A block that filters the rows of input batches by a predicate, outputting
+/// only the rows for which the predicate evaluates to `true`.
+///
+/// Batches that the predicate fails to evaluate on (e.g., due to an
+/// out-of-bounds column index) are reported on the `errors` port.
+#[block]
+pub async fn filter_rows(
+    predicate: Predicate,
+    mut inputs: Inputs<RecordBatch>,
+    outputs: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    while let Some(input) = inputs.recv().await? {
+        if input.num_rows() == 0 {
+            continue; // skip empty batches
+        }
+
+        let output = predicate
+            .evaluate(&input)
+            .and_then(|mask| arrow_select::filter::filter_record_batch(&input, &mask));
+
+        match output {
+            Ok(output) if !outputs.is_closed() => outputs.send(output).await?,
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("filter_rows", error))
+                    .await?
+            },
+            _ => continue,
+        }
+    }
+
+    Ok(())
+}
+
+/// A block that filters the rows of input batches by externally computed
+/// masks, outputting only the rows for which the corresponding mask entry is
+/// `true`.
+///
+/// Each input batch is paired with the next input mask; the block exits as
+/// soon as either input is closed. Batches whose mask has a different length
+/// are reported on the `errors` port.
+#[block]
+pub async fn filter_rows_by_mask(
+    mut inputs: Inputs<RecordBatch>,
+    mut masks: Inputs<BooleanArray>,
+    outputs: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    while let Some(input) = inputs.recv().await? {
+        let Some(mask) = masks.recv().await? else {
+            break;
+        };
+
+        let output = if mask.len() == input.num_rows() {
+            arrow_select::filter::filter_record_batch(&input, &mask)
+        } else {
+            Err(ArrowError::InvalidArgumentError(format!(
+                "mask length {} does not match batch length {}",
+                mask.len(),
+                input.num_rows()
+            )))
+        };
+
+        match output {
+            Ok(output) if !outputs.is_closed() => outputs.send(output).await?,
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("filter_rows_by_mask", error))
+                    .await?
+            },
+            _ => continue,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use arrow_array::{cast::AsArray, record_batch, types::Int32Type};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_filter_rows() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let (mut out, mut err) = (Channel::bounded(10), Channel::bounded(10));
+        let filterer = tokio::spawn(filter_rows(
+            Predicate::gt_eq(0, 3).and(Predicate::is_not_null(1)),
+            in_.rx,
+            out.tx,
+            err.tx,
+        ));
+
+        let batch = record_batch!(
+            ("a", Int32, [1, 2, 3, 4, 5]),
+            ("b", Float64, [Some(4.0), None, Some(5.0), None, Some(6.0)])
+        )?;
+        in_.tx.send(batch.clone()).await?;
+        in_.tx.send(batch.project(&[1])?).await?; // missing column
+        in_.tx.close();
+
+        let _ = tokio::join!(filterer);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(
+            outputs[0].column(0).as_primitive::<Int32Type>().values(),
+            &[3, 5]
+        );
+
+        let errors = err.rx.recv_all().await?;
+        assert_eq!(errors.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_filter_rows_by_mask() -> Result<(), Box<dyn Error>> {
+        let (mut in_, mut masks) = (Channel::bounded(10), Channel::bounded(10));
+        let (mut out, mut err) = (Channel::bounded(10), Channel::bounded(10));
+        let filterer = tokio::spawn(filter_rows_by_mask(in_.rx, masks.rx, out.tx, err.tx));
+
+        let batch = record_batch!(("a", Int32, [1, 2, 3]))?;
+        in_.tx.send(batch.clone()).await?;
+        masks
+            .tx
+            .send(BooleanArray::from(vec![true, false, true]))
+            .await?;
+        in_.tx.send(batch.clone()).await?;
+        masks.tx.send(BooleanArray::from(vec![true])).await?;
+        in_.tx.close();
+        masks.tx.close();
+
+        let _ = tokio::join!(filterer);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(
+            outputs[0].column(0).as_primitive::<Int32Type>().values(),
+            &[1, 3]
+        );
+
+        let errors = err.rx.recv_all().await?;
+        assert_eq!(errors.len(), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{JoinKey, JoinType, hash_table::HashTable};
+use alloc::vec::Vec;
+use arrow_array::RecordBatch;
+use arrow_schema::{ArrowError, SchemaRef};
+use async_flow::{Inputs, Outputs, Port, Result};
+use flows_derive::block;
+use flows_error::BlockError;
+
+/// A block that joins the batches of its probe input against those of its
+/// build input on the given key columns.
+///
+/// The build input is consumed in full into an in-memory hash table before
+/// any probe batch is received, so it should be the smaller of the two
+/// sides. Each probe batch is then joined as it arrives. The output consists
+/// of the probe columns followed, for inner and left joins, by the build
+/// side's non-key columns, which mustn't share a name with a probe column.
+/// Rows whose keys are null match nothing. Numeric keys are compared exactly,
+/// so a probe key that has no equal in the build keys' datatype (e.g., a
+/// fraction against integer keys) matches nothing, whereas comparing integer
+/// or decimal keys with floating-point keys fails the probe batch. Other
+/// probe keys are cast to the build keys' datatypes, with keys that don't
+/// convert failing their batch.
+///
+/// If the build input closes without any batches, probe batches are output
+/// unchanged for left and anti joins and dropped otherwise. Build batches
+/// with a schema that differs from the first build batch, and probe batches
+/// that fail to join (e.g., due to a missing key column), are reported on
+/// the `errors` port; a build side lacking a key column is reported there as
+/// well, after which the block exits.
+#[block]
+pub async fn hash_join(
+    join_type: JoinType,
+    keys: Vec<JoinKey>,
+    mut build: Inputs<RecordBatch>,
+    mut probe: Inputs<RecordBatch>,
+    outputs: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    let mut schema: Option<SchemaRef> = None;
+    let mut batches = Vec::new();
+    while let Some(batch) = build.recv().await? {
+        match &schema {
+            None => schema = Some(batch.schema()),
+            Some(schema) if *schema != batch.schema() => {
+                if errors.is_connected() {
+                    let error =
+                        ArrowError::SchemaError("batch schema differs from the first batch".into());
+                    errors
+                        .send(BlockError::from_error("hash_join", error))
+                        .await?;
+                }
+                continue;
+            },
+            Some(_) => {},
+        }
+        batches.push(batch);
+    }
+
+    let table = match schema {
+        None => None,
+        Some(schema) => match arrow_select::concat::concat_batches(&schema, &batches)
+            .and_then(|batch| HashTable::new(batch, &keys))
+        {
+            Ok(table) => Some(table),
+            Err(error) => {
+                if errors.is_connected() {
+                    errors
+                        .send(BlockError::from_error("hash_join", error))
+                        .await?;
+                }
+                return Ok(());
+            },
+        },
+    };
+    drop(batches);
+
+    while let Some(input) = probe.recv().await? {
+        let output = match &table {
+            Some(table) => table.probe(&input, &keys, join_type),
+            None if matches!(join_type, JoinType::Left | JoinType::Anti) => Ok(input),
+            None => continue,
+        };
+
+        match output {
+            Ok(output) if output.num_rows() == 0 => continue,
+            Ok(output) if !outputs.is_closed() => outputs.send(output).await?,
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("hash_join", error))
+                    .await?
+            },
+            _ => continue,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::sync::Arc;
+    use alloc::{boxed::Box, vec};
+    use arrow_array::{ArrayRef, Decimal128Array, cast::AsArray, record_batch, types::Int32Type};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_hash_join() -> Result<(), Box<dyn Error>> {
+        let users = record_batch!(
+            ("id", Int32, [Some(1), Some(2), None]),
+            ("name", Utf8, ["alice", "bob", "nobody"])
+        )?;
+        let events = record_batch!(
+            ("user", Int64, [Some(2), Some(3), Some(1), None, Some(2)]),
+            ("event", Int32, [10, 20, 30, 40, 50])
+        )?;
+
+        let join = async |join_type| -> Result<Vec<RecordBatch>, Box<dyn Error>> {
+            let (mut build, mut probe) = (Channel::bounded(10), Channel::bounded(10));
+            let (mut out, mut err) = (Channel::bounded(10), Channel::bounded(10));
+            let keys = vec![JoinKey::new("id", "user")];
+            let joiner = tokio::spawn(hash_join(
+                join_type, keys, build.rx, probe.rx, out.tx, err.tx,
+            ));
+            build.tx.send(users.clone()).await?;
+            build.tx.close();
+            probe.tx.send(events.clone()).await?;
+            probe.tx.send(record_batch!(("x", Int32, [1]))?).await?; // lacks `user`
+            probe.tx.close();
+            let _ = tokio::join!(joiner);
+            assert_eq!(err.rx.recv_all().await?.len(), 1);
+            Ok(out.rx.recv_all().await?)
+        };
+
+        let inner = join(JoinType::Inner).await?;
+        let names: Vec<&str> = inner[0]
+            .schema_ref()
+            .fields()
+            .iter()
+            .map(|f| f.name().as_str())
+            .collect();
+        assert_eq!(names, ["user", "event", "name"]);
+        assert_eq!(
+            inner[0].column(1).as_primitive::<Int32Type>().values(),
+            &[10, 30, 50]
+        );
+        assert_eq!(inner[0].column(2).as_string::<i32>().value(1), "alice");
+
+        let left = join(JoinType::Left).await?;
+        assert_eq!(left[0].num_rows(), 5);
+        assert_eq!(left[0].column(2).null_count(), 2);
+
+        let semi = join(JoinType::Semi).await?;
+        assert_eq!(semi[0].num_columns(), 2);
+        assert_eq!(
+            semi[0].column(1).as_primitive::<Int32Type>().values(),
+            &[10, 30, 50]
+        );
+
+        let anti = join(JoinType::Anti).await?;
+        assert_eq!(
+            anti[0].column(1).as_primitive::<Int32Type>().values(),
+            &[20, 40]
+        );
+
+        // Numeric keys are compared exactly, without truncating either side:
+        let keys = [JoinKey::new("id", "user")];
+        let table = HashTable::new(users.clone(), &keys)?;
+        let probe = RecordBatch::try_from_iter([(
+            "user",
+            Arc::new(Decimal128Array::from(vec![150, 290, 200]).with_precision_and_scale(5, 2)?)
+                as ArrayRef,
+        )])?;
+        let joined = table.probe(&probe, &keys, JoinType::Inner)?;
+        assert_eq!(joined.num_rows(), 1);
+        assert_eq!(joined.column(1).as_string::<i32>().value(0), "bob");
+        let probe = record_batch!(("user", Int64, [1 << 32 | 2, 2]))?;
+        let joined = table.probe(&probe, &keys, JoinType::Inner)?;
+        assert_eq!(joined.num_rows(), 1);
+        let probe = record_batch!(("user", Float64, [2.0]))?;
+        assert!(table.probe(&probe, &keys, JoinType::Inner).is_err());
+
+        // Build columns mustn't collide with probe columns:
+        let probe = record_batch!(("user", Int32, [1]), ("name", Utf8, ["x"]))?;
+        assert!(table.probe(&probe, &keys, JoinType::Inner).is_err());
+        assert!(table.probe(&probe, &keys, JoinType::Semi).is_ok());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{JoinKey, JoinType, expr::cast};
+use alloc::{boxed::Box, format, sync::Arc, vec::Vec};
+use arrow_array::{Array, ArrayRef, RecordBatch, RecordBatchOptions, UInt32Array};
+use arrow_buffer::NullBuffer;
+use arrow_row::{RowConverter, Rows, SortField};
+use arrow_schema::{ArrowError, DataType, Field, Schema};
+use arrow_select::take::{take, take_record_batch};
+use hashbrown::HashMap;
+
+/// The indices of the rows of a batch by the row format of their keys.
+type Index = HashMap<Box<[u8]>, Vec<u32>>;
+
+/// An in-memory hash table over the rows of a batch, keyed by the values of
+/// its key columns, that other batches are joined against.
+pub(crate) struct HashTable {
+    batch: RecordBatch,
+    /// The indices of the columns that aren't key columns.
+    values: Vec<usize>,
+    key_types: Vec<DataType>,
+    converter: RowConverter,
+    rows: Index,
+}
+
+impl HashTable {
+    /// Builds a hash table over the given batch, whose key columns are the
+    /// build columns of the given keys. Rows with a null key are left out, as
+    /// nulls match nothing.
+    pub(crate) fn new(batch: RecordBatch, keys: &[JoinKey]) -> Result<Self, ArrowError> {
+        let schema = batch.schema();
+        let indices = keys
+            .iter()
+            .map(|key| schema.index_of(&key.build))
+            .collect::<Result<Vec<_>, _>>()?;
+        let columns: Vec<ArrayRef> = indices.iter().map(|&i| batch.column(i).clone()).collect();
+        let key_types: Vec<DataType> = columns.iter().map(|c| c.data_type().clone()).collect();
+        let converter = RowConverter::new(key_types.iter().cloned().map(SortField::new).collect())?;
+
+        let mut rows = Index::new();
+        let converted = converter.convert_columns(&columns)?;
+        for index in valid_rows(&columns, batch.num_rows()) {
+            rows.entry(converted.row(index).as_ref().into())
+                .or_default()
+                .push(index as u32);
+        }
+
+        let values = (0..schema.fields().len())
+            .filter(|index| !indices.contains(index))
+            .collect();
+        Ok(Self {
+            batch,
+            values,
+            key_types,
+            converter,
+            rows,
+        })
+    }
+
+    /// Joins the given probe batch against the table. The output consists of
+    /// the probe columns followed, for inner and left joins, by the table's
+    /// non-key columns, whose names mustn't collide with the probe columns.
+    ///
+    /// Numeric probe keys are compared with the table's keys exactly, so a
+    /// probe key that has no equal in the datatype of the table's keys (e.g.,
+    /// a fraction against integer keys) matches nothing. Integer and decimal
+    /// keys can't be exactly compared with floating-point keys, which is an
+    /// error. Other probe keys are cast to the table's datatypes, with keys
+    /// that don't convert being an error.
+    pub(crate) fn probe(
+        &self,
+        batch: &RecordBatch,
+        keys: &[JoinKey],
+        join_type: JoinType,
+    ) -> Result<RecordBatch, ArrowError> {
+        let columns = keys
+            .iter()
+            .zip(&self.key_types)
+            .map(|(key, data_type)| {
+                let column = batch.column(batch.schema_ref().index_of(&key.probe)?);
+                probe_keys(column, data_type)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let rows = self.converter.convert_columns(&columns)?;
+
+        let mut probe_indices: Vec<u32> = Vec::new();
+        let mut build_indices: Vec<Option<u32>> = Vec::new();
+        let mut valid = valid_rows(&columns, batch.num_rows()).peekable();
+        for index in 0..batch.num_rows() {
+            let matches = match valid.next_if_eq(&index) {
+                Some(_) => self.matches(&rows, index),
+                None => &[],
+            };
+            let index = index as u32;
+            match join_type {
+                JoinType::Inner | JoinType::Left => {
+                    for &build_index in matches {
+                        probe_indices.push(index);
+                        build_indices.push(Some(build_index));
+                    }
+                    if matches.is_empty() && join_type == JoinType::Left {
+                        probe_indices.push(index);
+                        build_indices.push(None);
+                    }
+                },
+                JoinType::Semi if !matches.is_empty() => probe_indices.push(index),
+                JoinType::Anti if matches.is_empty() => probe_indices.push(index),
+                JoinType::Semi | JoinType::Anti => {},
+            }
+        }
+
+        let probed = take_record_batch(batch, &UInt32Array::from(probe_indices))?;
+        if matches!(join_type, JoinType::Semi | JoinType::Anti) {
+            return Ok(probed);
+        }
+
+        let build_schema = self.batch.schema_ref();
+        let build_indices = UInt32Array::from(build_indices);
+        let mut fields = probed.schema_ref().fields().to_vec();
+        let mut arrays = probed.columns().to_vec();
+        for &index in &self.values {
+            let field = build_schema.field(index);
+            if probed.schema_ref().column_with_name(field.name()).is_some() {
+                return Err(ArrowError::SchemaError(format!(
+                    "the build column `{}` collides with a probe column",
+                    field.name()
+                )));
+            }
+            let nullable = field.is_nullable() || join_type == JoinType::Left;
+            fields.push(Arc::new(Field::clone(field).with_nullable(nullable)));
+            arrays.push(take(self.batch.column(index), &build_indices, None)?);
+        }
+
+        let schema = Schema::new_with_metadata(fields, batch.schema_ref().metadata().clone());
+        let options = RecordBatchOptions::new().with_row_count(Some(probed.num_rows()));
+        RecordBatch::try_new_with_options(Arc::new(schema), arrays, &options)
+    }
+
+    fn matches(&self, rows: &Rows, index: usize) -> &[u32] {
+        self.rows
+            .get(rows.row(index).as_ref())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+/// Converts the given probe keys to the given datatype of the table's keys.
+/// Numeric keys that have no equal in that datatype become null, matching
+/// nothing, while integer and decimal keys can't be exactly compared with
+/// floating-point keys.
+fn probe_keys(column: &ArrayRef, data_type: &DataType) -> Result<ArrayRef, ArrowError> {
+    let probe_type = column.data_type();
+    if probe_type == data_type {
+        return Ok(column.clone());
+    }
+    if !(probe_type.is_numeric() && data_type.is_numeric()) {
+        return cast(column, data_type);
+    }
+    if probe_type.is_floating() != data_type.is_floating() {
+        return Err(ArrowError::CastError(format!(
+            "can't exactly compare {probe_type} keys with {data_type} keys"
+        )));
+    }
+
+    // Keys that are out of range, or rounded, don't convert back unchanged:
+    let converted = arrow_cast::cast(column, data_type)?;
+    let exact = arrow_ord::cmp::eq(&arrow_cast::cast(&converted, probe_type)?, column)?;
+    arrow_select::nullif::nullif(&converted, &arrow_arith::boolean::not(&exact)?)
+}
+
+/// Returns the indices of the rows whose key columns are all non-null.
+fn valid_rows(columns: &[ArrayRef], num_rows: usize) -> impl Iterator<Item = usize> {
+    let nulls = columns.iter().fold(None, |nulls, column| {
+        NullBuffer::union(nulls.as_ref(), column.logical_nulls().as_ref())
+    });
+    (0..num_rows).filter(move |&index| nulls.as_ref().is_none_or(|nulls| nulls.is_valid(index)))
+}
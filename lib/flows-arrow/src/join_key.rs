@@ -0,0 +1,40 @@
+// This is free and unencumbered software released into the public domain.
+
+use alloc::string::String;
+
+/// A pair of key columns that rows of two joined batches are matched on,
+/// referenced by name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JoinKey {
+    /// The key column of the build (or dimension) side.
+    pub build: String,
+
+    /// The key column of the probe side.
+    pub probe: String,
+}
+
+impl JoinKey {
+    /// Matches the given build column with the given probe column.
+    pub fn new(build: impl Into<String>, probe: impl Into<String>) -> Self {
+        Self {
+            build: build.into(),
+            probe: probe.into(),
+        }
+    }
+
+    /// Matches the columns of the given name on both sides.
+    pub fn on(name: impl Into<String>) -> Self {
+        let name = name.into();
+        Self {
+            build: name.clone(),
+            probe: name,
+        }
+    }
+}
+
+impl From<&str> for JoinKey {
+    fn from(name: &str) -> Self {
+        Self::on(name)
+    }
+}
@@ -0,0 +1,22 @@
+// This is free and unencumbered software released into the public domain.
+
+/// The kind of join performed by the `hash_join` block.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum JoinType {
+    /// Outputs a row for each pair of matching probe and build rows.
+    #[default]
+    Inner,
+
+    /// Outputs a row for each pair of matching probe and build rows, and a
+    /// row with null build columns for each probe row without a match.
+    Left,
+
+    /// Outputs the probe rows that have a match, with the probe columns only.
+    Semi,
+
+    /// Outputs the probe rows that have no match, with the probe columns
+    /// only.
+    Anti,
+}
@@ -9,14 +9,113 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+mod batch_size;
+pub use batch_size::*;
+
 mod concat_batches;
 pub use concat_batches::*;
 
+mod concat_windows;
+pub use concat_windows::*;
+
 mod count_rows;
 pub use count_rows::*;
 
+mod csv_options;
+pub use csv_options::*;
+
+mod decode_csv;
+pub use decode_csv::*;
+
+mod decode_ipc;
+pub use decode_ipc::*;
+
+mod decode_ndjson;
+pub use decode_ndjson::*;
+
+mod encode_csv;
+pub use encode_csv::*;
+
+mod encode_ipc;
+pub use encode_ipc::*;
+
+mod encode_ndjson;
+pub use encode_ndjson::*;
+
+mod expr;
+pub use expr::*;
+
+mod filter_rows;
+pub use filter_rows::*;
+
+mod hash_join;
+pub use hash_join::*;
+
+mod hash_table;
+
+mod join_key;
+pub use join_key::*;
+
+mod join_type;
+pub use join_type::*;
+
+mod literal;
+pub use literal::*;
+
+#[cfg(feature = "std")]
+mod lookup;
+#[cfg(feature = "std")]
+pub use lookup::*;
+
+#[cfg(feature = "parquet")]
+mod parquet_options;
+#[cfg(feature = "parquet")]
+pub use parquet_options::*;
+
+mod predicate;
+pub use predicate::*;
+
 mod project_columns;
 pub use project_columns::*;
 
+mod projection;
+pub use projection::*;
+
+mod rebatch;
+pub use rebatch::*;
+
+#[cfg(feature = "parquet")]
+mod read_parquet;
+#[cfg(feature = "parquet")]
+pub use read_parquet::*;
+
+mod row_batcher;
+
+mod schema_source;
+pub use schema_source::*;
+
+mod schema_unifier;
+pub use schema_unifier::*;
+
+mod select_columns;
+pub use select_columns::*;
+
 mod slice_rows;
 pub use slice_rows::*;
+
+mod sort_batches;
+pub use sort_batches::*;
+
+mod sort_key;
+pub use sort_key::*;
+
+mod top_k;
+pub use top_k::*;
+
+mod with_column;
+pub use with_column::*;
+
+#[cfg(feature = "parquet")]
+mod write_parquet;
+#[cfg(feature = "parquet")]
+pub use write_parquet::*;
@@ -0,0 +1,123 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{expr::cast, schema_unifier::supertype};
+use alloc::{format, string::String, sync::Arc};
+use arrow_array::{
+    ArrayRef, BooleanArray, Float64Array, Int64Array, NullArray, Scalar, StringArray, UInt64Array,
+};
+use arrow_schema::ArrowError;
+
+/// A literal value used in predicates and expressions over record batches.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Literal {
+    Null,
+    Boolean(bool),
+    Int64(i64),
+    UInt64(u64),
+    Float64(f64),
+    Utf8(String),
+}
+
+impl Literal {
+    /// Returns the literal as a single-element array.
+    pub fn to_array(&self) -> ArrayRef {
+        self.to_array_of_len(1)
+    }
+
+    /// Returns the literal repeated as an array of the given length.
+    pub fn to_array_of_len(&self, len: usize) -> ArrayRef {
+        match self {
+            Literal::Null => Arc::new(NullArray::new(len)),
+            Literal::Boolean(value) => Arc::new(BooleanArray::from(alloc::vec![*value; len])),
+            Literal::Int64(value) => Arc::new(Int64Array::from_value(*value, len)),
+            Literal::UInt64(value) => Arc::new(UInt64Array::from_value(*value, len)),
+            Literal::Float64(value) => Arc::new(Float64Array::from_value(*value, len)),
+            Literal::Utf8(value) => Arc::new(StringArray::new_repeated(value, len)),
+        }
+    }
+
+    /// Returns the given array and the literal as a scalar, converted to a
+    /// common datatype for comparing or combining them.
+    ///
+    /// The literal takes on the datatype of the array unless that would
+    /// change its value (e.g., a fractional or out-of-range number), in which
+    /// case both are converted to a wider numeric datatype. The literal is
+    /// never truncated, and a literal that doesn't convert to the array's
+    /// datatype at all (e.g., a malformed date) is an error.
+    pub fn coerce(&self, array: &ArrayRef) -> Result<(ArrayRef, Scalar<ArrayRef>), ArrowError> {
+        let literal = self.to_array();
+        let (from, to) = (literal.data_type(), array.data_type());
+        if from == to {
+            return Ok((array.clone(), Scalar::new(literal)));
+        }
+        if !from.is_numeric() || !to.is_numeric() {
+            return Ok((array.clone(), Scalar::new(cast(&literal, to)?)));
+        }
+
+        // Floating-point literals would divide integers as integers:
+        if !(from.is_floating() && to.is_integer())
+            && let Ok(value) = cast(&literal, to)
+            && cast(&value, from)?.to_data() == literal.to_data()
+        {
+            return Ok((array.clone(), Scalar::new(value)));
+        }
+
+        let data_type = supertype(from, to).ok_or_else(|| {
+            ArrowError::InvalidArgumentError(format!("incompatible datatypes {to} and {from}"))
+        })?;
+        Ok((
+            cast(array, &data_type)?,
+            Scalar::new(cast(&literal, &data_type)?),
+        ))
+    }
+}
+
+impl From<bool> for Literal {
+    fn from(value: bool) -> Self {
+        Literal::Boolean(value)
+    }
+}
+
+impl From<i32> for Literal {
+    fn from(value: i32) -> Self {
+        Literal::Int64(value.into())
+    }
+}
+
+impl From<i64> for Literal {
+    fn from(value: i64) -> Self {
+        Literal::Int64(value)
+    }
+}
+
+impl From<u32> for Literal {
+    fn from(value: u32) -> Self {
+        Literal::UInt64(value.into())
+    }
+}
+
+impl From<u64> for Literal {
+    fn from(value: u64) -> Self {
+        Literal::UInt64(value)
+    }
+}
+
+impl From<f64> for Literal {
+    fn from(value: f64) -> Self {
+        Literal::Float64(value)
+    }
+}
+
+impl From<&str> for Literal {
+    fn from(value: &str) -> Self {
+        Literal::Utf8(value.into())
+    }
+}
+
+impl From<String> for Literal {
+    fn from(value: String) -> Self {
+        Literal::Utf8(value)
+    }
+}
@@ -0,0 +1,125 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{JoinKey, JoinType, hash_table::HashTable};
+use alloc::vec::Vec;
+use arrow_array::RecordBatch;
+use async_flow::{Inputs, Outputs, Port, Result};
+use flows_derive::block;
+use flows_error::BlockError;
+use tokio::select;
+
+/// A block that enriches input batches with the columns of a dimension
+/// table, looking up each input row by the given key columns.
+///
+/// Each batch received on the `dimensions` port is a complete snapshot of
+/// the dimension table, replacing the previous one, so the table can be
+/// refreshed while the block is running. Input batches are held back until
+/// the first snapshot arrives. The lookup is a left join: the output
+/// consists of the input columns followed by the table's non-key columns,
+/// which are null for input rows without a match, and an input row matching
+/// several table rows is output once for each of them. Keys are compared as
+/// by [`hash_join`](super::hash_join()), and table columns sharing a name
+/// with an input column fail the input batch.
+///
+/// If the `dimensions` port closes without any snapshots, input batches are
+/// output unchanged. Snapshots lacking a key column, which are disregarded,
+/// and input batches that fail to be looked up are reported on the `errors`
+/// port.
+#[block]
+pub async fn lookup(
+    keys: Vec<JoinKey>,
+    mut dimensions: Inputs<RecordBatch>,
+    mut inputs: Inputs<RecordBatch>,
+    outputs: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    let mut table: Option<HashTable> = None;
+    let mut dimensions_open = true;
+
+    loop {
+        select! {
+            biased; // apply refreshes before further lookups
+
+            snapshot = dimensions.recv(), if dimensions_open => match snapshot? {
+                Some(snapshot) => match HashTable::new(snapshot, &keys) {
+                    Ok(snapshot) => table = Some(snapshot),
+                    Err(error) if errors.is_connected() => {
+                        errors.send(BlockError::from_error("lookup", error)).await?
+                    },
+                    Err(_) => {},
+                },
+                None => dimensions_open = false,
+            },
+
+            input = inputs.recv(), if table.is_some() || !dimensions_open => {
+                let Some(input) = input? else {
+                    break;
+                };
+                let output = match &table {
+                    Some(table) => table.probe(&input, &keys, JoinType::Left),
+                    None => Ok(input),
+                };
+                match output {
+                    Ok(output) if !outputs.is_closed() => outputs.send(output).await?,
+                    Err(error) if errors.is_connected() => {
+                        errors.send(BlockError::from_error("lookup", error)).await?
+                    },
+                    _ => {},
+                }
+            },
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use arrow_array::{Array, cast::AsArray, record_batch};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_lookup() -> Result<(), Box<dyn Error>> {
+        let (mut dims, mut in_) = (Channel::bounded(10), Channel::bounded(10));
+        let (mut out, mut err) = (Channel::bounded(10), Channel::bounded(10));
+        let keys = vec![JoinKey::on("code")];
+        let lookuper = tokio::spawn(lookup(keys, dims.rx, in_.rx, out.tx, err.tx));
+
+        let input = record_batch!(("code", Utf8, ["fi", "se", "xx"]))?;
+        in_.tx.send(input.clone()).await?; // held back until the first snapshot
+        dims.tx
+            .send(record_batch!(
+                ("code", Utf8, ["fi", "se"]),
+                ("name", Utf8, ["Finland", "Sweden"])
+            )?)
+            .await?;
+        let output = out.rx.recv().await?.unwrap();
+        let names = output.column(1).as_string::<i32>();
+        assert_eq!((names.value(0), names.value(1)), ("Finland", "Sweden"));
+        assert!(names.is_null(2));
+
+        dims.tx
+            .send(record_batch!(
+                ("code", Utf8, ["xx"]),
+                ("name", Utf8, ["Unknown"])
+            )?)
+            .await?;
+        dims.tx.send(record_batch!(("id", Utf8, ["xx"]))?).await?; // lacks `code`
+        dims.tx.close();
+        assert_eq!(err.rx.recv().await?.map(|_| ()), Some(()));
+        in_.tx.send(input).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(lookuper);
+
+        let outputs = out.rx.recv_all().await?;
+        let names = outputs[0].column(1).as_string::<i32>();
+        assert_eq!(names.null_count(), 2);
+        assert_eq!(names.value(2), "Unknown");
+
+        Ok(())
+    }
+}
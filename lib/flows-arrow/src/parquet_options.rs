@@ -0,0 +1,100 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::Predicate;
+use alloc::{string::String, vec::Vec};
+use parquet::{basic::Compression, file::properties::WriterProperties};
+
+/// Options for the `read_parquet` block.
+///
+/// By default, all columns of all row groups are read, in batches of 1024
+/// rows.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParquetReadOptions {
+    /// The names of the columns to read, or `None` to read all columns.
+    pub columns: Option<Vec<String>>,
+
+    /// A predicate used to skip row groups whose statistics show that none
+    /// of their rows can match. Its column indices refer to the file's
+    /// schema, regardless of the projected columns.
+    pub predicate: Option<Predicate>,
+
+    /// The (maximum) number of rows in each output batch.
+    pub batch_size: usize,
+}
+
+impl Default for ParquetReadOptions {
+    fn default() -> Self {
+        Self {
+            columns: None,
+            predicate: None,
+            batch_size: 1024,
+        }
+    }
+}
+
+impl ParquetReadOptions {
+    /// Reads only the given columns.
+    pub fn with_columns(self, columns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            columns: Some(columns.into_iter().map(Into::into).collect()),
+            ..self
+        }
+    }
+
+    /// Skips row groups that can't contain rows matching the given predicate.
+    pub fn with_predicate(self, predicate: Predicate) -> Self {
+        Self {
+            predicate: Some(predicate),
+            ..self
+        }
+    }
+
+    /// Sets the (maximum) number of rows in each output batch.
+    pub fn with_batch_size(self, batch_size: usize) -> Self {
+        Self { batch_size, ..self }
+    }
+}
+
+/// Options for the `write_parquet` block.
+///
+/// By default, files are Snappy-compressed with row groups of (at most)
+/// 1M rows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParquetWriteOptions {
+    pub compression: Compression,
+    pub max_row_group_size: usize,
+}
+
+impl Default for ParquetWriteOptions {
+    fn default() -> Self {
+        Self {
+            compression: Compression::SNAPPY,
+            max_row_group_size: 1024 * 1024,
+        }
+    }
+}
+
+impl ParquetWriteOptions {
+    /// Sets the compression codec for all columns.
+    pub fn with_compression(self, compression: Compression) -> Self {
+        Self {
+            compression,
+            ..self
+        }
+    }
+
+    /// Sets the (maximum) number of rows in each row group.
+    pub fn with_max_row_group_size(self, max_row_group_size: usize) -> Self {
+        Self {
+            max_row_group_size,
+            ..self
+        }
+    }
+
+    pub(crate) fn properties(&self) -> WriterProperties {
+        WriterProperties::builder()
+            .set_compression(self.compression)
+            .set_max_row_group_size(self.max_row_group_size)
+            .build()
+    }
+}
@@ -0,0 +1,228 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::Literal;
+use alloc::{boxed::Box, format, string::String, vec, vec::Vec};
+use arrow_array::{ArrayRef, BooleanArray, RecordBatch};
+use arrow_schema::ArrowError;
+
+/// A boolean predicate over the columns of a record batch.
+///
+/// Columns are referenced by their index in the batch. Columns and values
+/// are compared as converted by [`Literal::coerce`], so that a value is
+/// never truncated to the datatype of its column. Comparisons with a null
+/// value, as well as comparisons with nulls in a column, evaluate to null,
+/// which filtering treats as `false`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Predicate {
+    Eq(usize, Literal),
+    NotEq(usize, Literal),
+    Lt(usize, Literal),
+    LtEq(usize, Literal),
+    Gt(usize, Literal),
+    GtEq(usize, Literal),
+    IsNull(usize),
+    IsNotNull(usize),
+    Contains(usize, String),
+    StartsWith(usize, String),
+    EndsWith(usize, String),
+    Not(Box<Predicate>),
+    And(Vec<Predicate>),
+    Or(Vec<Predicate>),
+}
+
+impl Predicate {
+    pub fn eq(column: usize, value: impl Into<Literal>) -> Self {
+        Self::Eq(column, value.into())
+    }
+
+    pub fn not_eq(column: usize, value: impl Into<Literal>) -> Self {
+        Self::NotEq(column, value.into())
+    }
+
+    pub fn lt(column: usize, value: impl Into<Literal>) -> Self {
+        Self::Lt(column, value.into())
+    }
+
+    pub fn lt_eq(column: usize, value: impl Into<Literal>) -> Self {
+        Self::LtEq(column, value.into())
+    }
+
+    pub fn gt(column: usize, value: impl Into<Literal>) -> Self {
+        Self::Gt(column, value.into())
+    }
+
+    pub fn gt_eq(column: usize, value: impl Into<Literal>) -> Self {
+        Self::GtEq(column, value.into())
+    }
+
+    pub fn is_null(column: usize) -> Self {
+        Self::IsNull(column)
+    }
+
+    pub fn is_not_null(column: usize) -> Self {
+        Self::IsNotNull(column)
+    }
+
+    pub fn contains(column: usize, pattern: impl Into<String>) -> Self {
+        Self::Contains(column, pattern.into())
+    }
+
+    pub fn starts_with(column: usize, prefix: impl Into<String>) -> Self {
+        Self::StartsWith(column, prefix.into())
+    }
+
+    pub fn ends_with(column: usize, suffix: impl Into<String>) -> Self {
+        Self::EndsWith(column, suffix.into())
+    }
+
+    /// Combines this predicate with another one using a logical AND.
+    pub fn and(self, other: Predicate) -> Self {
+        match self {
+            Self::And(mut predicates) => {
+                predicates.push(other);
+                Self::And(predicates)
+            },
+            predicate => Self::And(vec![predicate, other]),
+        }
+    }
+
+    /// Combines this predicate with another one using a logical OR.
+    pub fn or(self, other: Predicate) -> Self {
+        match self {
+            Self::Or(mut predicates) => {
+                predicates.push(other);
+                Self::Or(predicates)
+            },
+            predicate => Self::Or(vec![predicate, other]),
+        }
+    }
+
+    /// Evaluates this predicate against the given batch, returning a mask
+    /// with one entry per row.
+    pub fn evaluate(&self, batch: &RecordBatch) -> Result<BooleanArray, ArrowError> {
+        use arrow_arith::boolean::{and_kleene, is_not_null, is_null, not, or_kleene};
+        use arrow_ord::cmp;
+        use arrow_string::like;
+        Ok(match self {
+            Self::Eq(column, value) => compare(batch, *column, value, cmp::eq)?,
+            Self::NotEq(column, value) => compare(batch, *column, value, cmp::neq)?,
+            Self::Lt(column, value) => compare(batch, *column, value, cmp::lt)?,
+            Self::LtEq(column, value) => compare(batch, *column, value, cmp::lt_eq)?,
+            Self::Gt(column, value) => compare(batch, *column, value, cmp::gt)?,
+            Self::GtEq(column, value) => compare(batch, *column, value, cmp::gt_eq)?,
+            Self::IsNull(column) => is_null(column_at(batch, *column)?)?,
+            Self::IsNotNull(column) => is_not_null(column_at(batch, *column)?)?,
+            Self::Contains(column, pattern) => {
+                compare(batch, *column, &pattern.as_str().into(), like::contains)?
+            },
+            Self::StartsWith(column, prefix) => {
+                compare(batch, *column, &prefix.as_str().into(), like::starts_with)?
+            },
+            Self::EndsWith(column, suffix) => {
+                compare(batch, *column, &suffix.as_str().into(), like::ends_with)?
+            },
+            Self::Not(predicate) => not(&predicate.evaluate(batch)?)?,
+            Self::And(predicates) => {
+                let mut result = BooleanArray::from(vec![true; batch.num_rows()]);
+                for predicate in predicates {
+                    result = and_kleene(&result, &predicate.evaluate(batch)?)?;
+                }
+                result
+            },
+            Self::Or(predicates) => {
+                let mut result = BooleanArray::from(vec![false; batch.num_rows()]);
+                for predicate in predicates {
+                    result = or_kleene(&result, &predicate.evaluate(batch)?)?;
+                }
+                result
+            },
+        })
+    }
+}
+
+impl core::ops::Not for Predicate {
+    type Output = Predicate;
+
+    fn not(self) -> Self::Output {
+        Self::Not(Box::new(self))
+    }
+}
+
+fn column_at(batch: &RecordBatch, column: usize) -> Result<&ArrayRef, ArrowError> {
+    batch.columns().get(column).ok_or_else(|| {
+        ArrowError::InvalidArgumentError(format!("column index {column} is out of bounds"))
+    })
+}
+
+fn compare(
+    batch: &RecordBatch,
+    column: usize,
+    value: &Literal,
+    kernel: fn(
+        &dyn arrow_array::Datum,
+        &dyn arrow_array::Datum,
+    ) -> Result<BooleanArray, ArrowError>,
+) -> Result<BooleanArray, ArrowError> {
+    let (array, value) = value.coerce(column_at(batch, column)?)?;
+    kernel(&array, &value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::record_batch;
+
+    #[test]
+    fn test_predicate() -> Result<(), ArrowError> {
+        let batch = record_batch!(
+            ("a", Int32, [1, 2, 3, 4, 5]),
+            (
+                "b",
+                Utf8,
+                [
+                    Some("alpha"),
+                    Some("beta"),
+                    None,
+                    Some("gamma"),
+                    Some("alpine")
+                ]
+            )
+        )?;
+
+        let mask = Predicate::gt(0, 1)
+            .and(Predicate::lt_eq(0, 4))
+            .evaluate(&batch)?;
+        assert_eq!(
+            mask,
+            BooleanArray::from(vec![false, true, true, true, false])
+        );
+
+        let mask = Predicate::starts_with(1, "al")
+            .or(Predicate::is_null(1))
+            .evaluate(&batch)?;
+        assert_eq!(
+            mask,
+            BooleanArray::from(vec![true, false, true, false, true])
+        );
+
+        let mask = (!Predicate::contains(1, "ta")).evaluate(&batch)?;
+        assert_eq!(
+            mask,
+            BooleanArray::from(vec![Some(true), Some(false), None, Some(true), Some(true)])
+        );
+
+        assert!(Predicate::is_null(2).evaluate(&batch).is_err());
+
+        // Values are compared without truncating them to the column's type:
+        let batch = record_batch!(("n", Int8, [1, 2, 3]))?;
+        let mask = Predicate::lt(0, 1000).evaluate(&batch)?;
+        assert_eq!(mask, BooleanArray::from(vec![true, true, true]));
+        let mask = Predicate::lt(0, 2.5).evaluate(&batch)?;
+        assert_eq!(mask, BooleanArray::from(vec![true, true, false]));
+        let mask = Predicate::eq(0, 2.0).evaluate(&batch)?;
+        assert_eq!(mask, BooleanArray::from(vec![false, true, false]));
+        assert!(Predicate::eq(0, "two").evaluate(&batch).is_err());
+
+        Ok(())
+    }
+}
@@ -3,24 +3,33 @@
 use arrow_array::RecordBatch;
 use async_flow::{Inputs, Outputs, Port, Result};
 use flows_derive::block;
+use flows_error::BlockError;
 
 /// A block that projects columns from input batches to output batches.
 ///
-/// Panics in case the specified columns are out of bounds.
+/// Batches that the specified columns are out of bounds for are reported on
+/// the `errors` port. See `select_columns` for a block that selects columns
+/// by name instead, which is robust to upstream schema changes.
 #[block]
 pub async fn project_columns(
     columns: &[usize],
     mut inputs: Inputs<RecordBatch>,
     outputs: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
 ) -> Result {
     while let Some(input) = inputs.recv().await? {
         if input.num_rows() == 0 {
             continue; // skip empty batches
         }
 
-        let output = input.project(columns).unwrap();
-        if !outputs.is_closed() {
-            outputs.send(output).await?;
+        match input.project(columns) {
+            Ok(output) if !outputs.is_closed() => outputs.send(output).await?,
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("project_columns", error))
+                    .await?
+            },
+            _ => continue,
         }
     }
 
@@ -39,7 +48,8 @@ mod tests {
     async fn test_project_columns() -> Result<(), Box<dyn Error>> {
         let mut inputs = Channel::bounded(10);
         let mut outputs = Channel::bounded(10);
-        let projecter = tokio::spawn(project_columns(&[1], inputs.rx, outputs.tx));
+        let mut errors = Channel::bounded(10);
+        let projecter = tokio::spawn(project_columns(&[1], inputs.rx, outputs.tx, errors.tx));
 
         let input = record_batch!(
             ("a", Int32, [1, 2, 3]),
@@ -50,6 +60,7 @@ mod tests {
         assert_eq!(input.num_rows(), 3);
         inputs.tx.send(input.clone()).await?;
         inputs.tx.send(input.clone()).await?;
+        inputs.tx.send(input.project(&[0])?).await?; // out of bounds
         inputs.tx.close();
 
         let _ = tokio::join!(projecter);
@@ -61,6 +72,10 @@ mod tests {
             assert_eq!(output.num_rows(), 3);
         }
 
+        let errors = errors.rx.recv_all().await?;
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].block(), "project_columns");
+
         Ok(())
     }
 }
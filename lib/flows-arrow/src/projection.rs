@@ -0,0 +1,210 @@
+// This is free and unencumbered software released into the public domain.
+
+use alloc::{format, string::String, sync::Arc, vec::Vec};
+use arrow_array::{ArrayRef, RecordBatch, RecordBatchOptions, new_null_array};
+use arrow_cast::{CastOptions, cast_with_options};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef};
+
+/// A projection of record batches onto columns selected by name, optionally
+/// renaming them and casting them to another datatype.
+///
+/// Cast columns are nullable in the output schema, and values that can't be
+/// cast are an error rather than nulled out.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Projection {
+    pub columns: Vec<ProjectedColumn>,
+    pub missing: MissingColumns,
+}
+
+/// A column selected by a [`Projection`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProjectedColumn {
+    pub name: String,
+    pub alias: Option<String>,
+    pub data_type: Option<DataType>,
+}
+
+/// How a [`Projection`] treats selected columns that a batch lacks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MissingColumns {
+    /// Rejects the batch.
+    #[default]
+    Error,
+
+    /// Substitutes a column of nulls, typed as the column's cast datatype, if
+    /// any, or else as `DataType::Null`.
+    Null,
+
+    /// Leaves the column out of the output batch.
+    Skip,
+}
+
+impl Projection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Selects the given column as is.
+    pub fn column(self, name: impl Into<String>) -> Self {
+        self.with(ProjectedColumn::new(name))
+    }
+
+    /// Selects the given column, renaming it to the given alias.
+    pub fn renamed(self, name: impl Into<String>, alias: impl Into<String>) -> Self {
+        self.with(ProjectedColumn::new(name).with_alias(alias))
+    }
+
+    /// Selects the given column, casting it to the given datatype.
+    pub fn cast(self, name: impl Into<String>, data_type: DataType) -> Self {
+        self.with(ProjectedColumn::new(name).with_type(data_type))
+    }
+
+    /// Selects the given column.
+    pub fn with(mut self, column: ProjectedColumn) -> Self {
+        self.columns.push(column);
+        self
+    }
+
+    /// Sets how selected columns that a batch lacks are treated.
+    pub fn on_missing(self, missing: MissingColumns) -> Self {
+        Self { missing, ..self }
+    }
+
+    /// Returns the output schema, if it is known without seeing any input,
+    /// i.e., if every column is cast and none are skipped.
+    pub fn schema(&self) -> Option<SchemaRef> {
+        if self.missing == MissingColumns::Skip {
+            return None;
+        }
+        let fields = self
+            .columns
+            .iter()
+            .map(|column| {
+                let data_type = column.data_type.clone()?;
+                Some(Field::new(column.output_name(), data_type, true))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Arc::new(Schema::new(fields)))
+    }
+
+    /// Applies the projection to the given batch.
+    pub fn project(&self, batch: &RecordBatch) -> Result<RecordBatch, ArrowError> {
+        let cast_options = CastOptions {
+            safe: false,
+            ..Default::default()
+        };
+
+        let mut fields = Vec::with_capacity(self.columns.len());
+        let mut arrays: Vec<ArrayRef> = Vec::with_capacity(self.columns.len());
+        for column in &self.columns {
+            let name = column.output_name();
+            match batch.schema_ref().column_with_name(&column.name) {
+                Some((index, field)) => match &column.data_type {
+                    Some(data_type) => {
+                        let array = batch.column(index);
+                        arrays.push(match array.data_type() == data_type {
+                            true => array.clone(),
+                            false => cast_with_options(array, data_type, &cast_options)?,
+                        });
+                        fields.push(Field::new(name, data_type.clone(), true));
+                    },
+                    None => {
+                        arrays.push(batch.column(index).clone());
+                        fields.push(field.clone().with_name(name));
+                    },
+                },
+                None => match self.missing {
+                    MissingColumns::Error => {
+                        return Err(ArrowError::SchemaError(format!(
+                            "column '{}' not found",
+                            column.name
+                        )));
+                    },
+                    MissingColumns::Null => {
+                        let data_type = column.data_type.clone().unwrap_or(DataType::Null);
+                        arrays.push(new_null_array(&data_type, batch.num_rows()));
+                        fields.push(Field::new(name, data_type, true));
+                    },
+                    MissingColumns::Skip => continue,
+                },
+            }
+        }
+
+        let options = RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
+        RecordBatch::try_new_with_options(Arc::new(Schema::new(fields)), arrays, &options)
+    }
+}
+
+impl ProjectedColumn {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            alias: None,
+            data_type: None,
+        }
+    }
+
+    /// Renames the column to the given alias.
+    pub fn with_alias(self, alias: impl Into<String>) -> Self {
+        Self {
+            alias: Some(alias.into()),
+            ..self
+        }
+    }
+
+    /// Casts the column to the given datatype.
+    pub fn with_type(self, data_type: DataType) -> Self {
+        Self {
+            data_type: Some(data_type),
+            ..self
+        }
+    }
+
+    /// Returns the name of the column in the output schema.
+    pub fn output_name(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use arrow_array::{cast::AsArray, record_batch, types::Int64Type};
+    use core::error::Error;
+
+    #[test]
+    fn test_projection() -> Result<(), Box<dyn Error>> {
+        let batch = record_batch!(("a", Int32, [1, 2]), ("b", Utf8, ["3", "4"]))?;
+
+        let projection = Projection::new()
+            .cast("b", DataType::Int64)
+            .renamed("a", "x")
+            .with(ProjectedColumn::new("c").with_type(DataType::Utf8));
+        assert!(projection.project(&batch).is_err()); // missing column
+
+        let projection = projection.on_missing(MissingColumns::Null);
+        let output = projection.project(&batch)?;
+        assert_eq!(output.schema().field(0).name(), "b");
+        assert_eq!(
+            output.column(0).as_primitive::<Int64Type>().values(),
+            &[3, 4]
+        );
+        assert_eq!(output.schema().field(1).name(), "x");
+        assert_eq!(output.column(2).null_count(), 2);
+        assert_eq!(projection.schema(), None); // `x` isn't cast
+
+        let projection = projection.on_missing(MissingColumns::Skip);
+        assert_eq!(projection.project(&batch)?.num_columns(), 2);
+
+        let batch = record_batch!(("b", Utf8, ["x"]))?;
+        assert!(
+            Projection::new()
+                .cast("b", DataType::Int64)
+                .project(&batch)
+                .is_err()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,314 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{ParquetReadOptions, Predicate};
+use alloc::{format, vec::Vec};
+use arrow_arith::boolean::{and_kleene, or_kleene};
+use arrow_array::{Array, ArrayRef, BooleanArray, RecordBatch, UInt64Array};
+use arrow_ord::cmp;
+use arrow_schema::{ArrowError, Schema};
+use async_flow::{Outputs, Port, Result};
+use flows_derive::block;
+use flows_error::BlockError;
+use parquet::{
+    arrow::{
+        ParquetRecordBatchStreamBuilder, ProjectionMask,
+        arrow_reader::statistics::StatisticsConverter, async_reader::ParquetRecordBatchStream,
+    },
+    errors::ParquetError,
+    file::metadata::{ParquetMetaData, RowGroupMetaData},
+};
+use tokio::io::{AsyncRead, AsyncSeek};
+
+/// A block that reads a Parquet file from an async reader, outputting its
+/// record batches one row group at a time.
+///
+/// Only the columns selected by the options are read, and row groups whose
+/// statistics show that they contain no rows matching the options' predicate
+/// are skipped altogether. Note that the rows of the remaining row groups are
+/// output as is; see `filter_rows` for a block that filters them. Failures to
+/// open the file are reported on the `errors` port, after which the block
+/// exits.
+#[block]
+pub async fn read_parquet<R>(
+    reader: R,
+    options: ParquetReadOptions,
+    outputs: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result
+where
+    R: AsyncRead + AsyncSeek + Unpin + Send + 'static,
+{
+    let mut stream = match open(reader, &options).await {
+        Ok(stream) => stream,
+        Err(error) => {
+            if errors.is_connected() {
+                errors
+                    .send(BlockError::from_error("read_parquet", error))
+                    .await?;
+            }
+            return Ok(());
+        },
+    };
+
+    loop {
+        let batches = match stream.next_row_group().await {
+            Ok(Some(batches)) => batches,
+            Ok(None) => break, // end of file
+            Err(error) => {
+                if errors.is_connected() {
+                    errors
+                        .send(BlockError::from_error("read_parquet", error))
+                        .await?;
+                }
+                return Ok(());
+            },
+        };
+        for batch in batches {
+            match batch {
+                Ok(batch) if !outputs.is_closed() => outputs.send(batch).await?,
+                Err(error) if errors.is_connected() => {
+                    errors
+                        .send(BlockError::from_error("read_parquet", error))
+                        .await?
+                },
+                _ => continue,
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn open<R>(
+    reader: R,
+    options: &ParquetReadOptions,
+) -> core::result::Result<ParquetRecordBatchStream<R>, ParquetError>
+where
+    R: AsyncRead + AsyncSeek + Unpin + Send + 'static,
+{
+    let mut builder = ParquetRecordBatchStreamBuilder::new(reader)
+        .await?
+        .with_batch_size(options.batch_size);
+
+    if let Some(columns) = &options.columns {
+        let indices = columns
+            .iter()
+            .map(|column| builder.schema().index_of(column))
+            .collect::<core::result::Result<Vec<_>, _>>()?;
+        let mask = ProjectionMask::roots(builder.parquet_schema(), indices);
+        builder = builder.with_projection(mask);
+    }
+
+    if let Some(predicate) = &options.predicate {
+        let row_groups = RowGroups::new(builder.metadata(), builder.schema()).prune(predicate)?;
+        builder = builder.with_row_groups(row_groups);
+    }
+
+    builder.build()
+}
+
+/// The statistics of the row groups of a Parquet file.
+struct RowGroups<'a> {
+    metadata: &'a ParquetMetaData,
+    schema: &'a Schema,
+}
+
+impl<'a> RowGroups<'a> {
+    fn new(metadata: &'a ParquetMetaData, schema: &'a Schema) -> Self {
+        Self { metadata, schema }
+    }
+
+    /// Returns the indices of the row groups that may contain rows matching
+    /// the given predicate.
+    fn prune(&self, predicate: &Predicate) -> core::result::Result<Vec<usize>, ArrowError> {
+        let matches = self.may_match(predicate)?;
+        Ok((0..matches.len())
+            .filter(|&index| matches.is_null(index) || matches.value(index))
+            .collect())
+    }
+
+    /// Evaluates the given predicate against the statistics of each row
+    /// group, with null meaning that the statistics are inconclusive.
+    fn may_match(&self, predicate: &Predicate) -> core::result::Result<BooleanArray, ArrowError> {
+        use Predicate::*;
+        let len = self.metadata.num_row_groups();
+        Ok(match predicate {
+            Eq(column, value) => {
+                let (mins, maxes) = self.min_max(*column)?;
+                // The statistics take on the same datatype for the same value:
+                let (maxes, _) = value.coerce(&maxes)?;
+                let (mins, value) = value.coerce(&mins)?;
+                and_kleene(&cmp::lt_eq(&mins, &value)?, &cmp::gt_eq(&maxes, &value)?)?
+            },
+            Lt(column, value) | LtEq(column, value) => {
+                let (mins, _) = self.min_max(*column)?;
+                let (mins, value) = value.coerce(&mins)?;
+                match predicate {
+                    Lt(..) => cmp::lt(&mins, &value)?,
+                    _ => cmp::lt_eq(&mins, &value)?,
+                }
+            },
+            Gt(column, value) | GtEq(column, value) => {
+                let (_, maxes) = self.min_max(*column)?;
+                let (maxes, value) = value.coerce(&maxes)?;
+                match predicate {
+                    Gt(..) => cmp::gt(&maxes, &value)?,
+                    _ => cmp::gt_eq(&maxes, &value)?,
+                }
+            },
+            IsNull(column) => {
+                let null_counts = self
+                    .converter(*column)?
+                    .row_group_null_counts(self.iter())?;
+                cmp::gt(&null_counts, &UInt64Array::new_scalar(0))?
+            },
+            IsNotNull(column) => {
+                let converter = self.converter(*column)?;
+                let null_counts = converter.row_group_null_counts(self.iter())?;
+                match converter.row_group_row_counts(self.iter())? {
+                    Some(row_counts) => cmp::lt(&null_counts, &row_counts)?,
+                    None => BooleanArray::new_null(len),
+                }
+            },
+            And(predicates) => predicates.iter().try_fold(
+                BooleanArray::from(alloc::vec![true; len]),
+                |result, predicate| and_kleene(&result, &self.may_match(predicate)?),
+            )?,
+            Or(predicates) => predicates.iter().try_fold(
+                BooleanArray::from(alloc::vec![false; len]),
+                |result, predicate| or_kleene(&result, &self.may_match(predicate)?),
+            )?,
+            NotEq(..) | Contains(..) | StartsWith(..) | EndsWith(..) | Not(_) => {
+                BooleanArray::new_null(len)
+            },
+        })
+    }
+
+    fn min_max(&self, column: usize) -> core::result::Result<(ArrayRef, ArrayRef), ArrowError> {
+        let converter = self.converter(column)?;
+        Ok((
+            converter.row_group_mins(self.iter())?,
+            converter.row_group_maxes(self.iter())?,
+        ))
+    }
+
+    fn converter(
+        &self,
+        column: usize,
+    ) -> core::result::Result<StatisticsConverter<'a>, ArrowError> {
+        let field = self.schema.fields().get(column).ok_or_else(|| {
+            ArrowError::InvalidArgumentError(format!("column index {column} is out of bounds"))
+        })?;
+        let converter = StatisticsConverter::try_new(
+            field.name(),
+            self.schema,
+            self.metadata.file_metadata().schema_descr(),
+        )?;
+        Ok(converter.with_missing_null_counts_as_zero(false))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &'a RowGroupMetaData> {
+        self.metadata.row_groups().iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParquetWriteOptions, write_parquet};
+    use alloc::{boxed::Box, sync::Arc};
+    use arrow_array::{Int32Array, StringArray, cast::AsArray, types::Int32Type};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[cfg(feature = "std")]
+    #[tokio::test]
+    async fn test_read_parquet() -> Result<(), Box<dyn Error>> {
+        let file = write_sample_file().await?;
+
+        let (mut out, mut err) = (Channel::bounded(10), Channel::bounded(10));
+        let options = ParquetReadOptions::default()
+            .with_columns(["n"])
+            .with_predicate(Predicate::gt_eq(0, 25).and(Predicate::lt(0, 35)));
+        let reader = tokio::spawn(read_parquet(
+            std::io::Cursor::new(file),
+            options,
+            out.tx,
+            err.tx,
+        ));
+        let _ = tokio::join!(reader);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs.len(), 2); // the row groups of 20..30 and 30..40
+        assert!(outputs.iter().all(|batch| batch.num_columns() == 1));
+        let values: Vec<i32> = outputs
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column(0)
+                    .as_primitive::<Int32Type>()
+                    .values()
+                    .to_vec()
+            })
+            .collect();
+        assert_eq!(values, (20..40).collect::<Vec<_>>());
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[tokio::test]
+    async fn test_read_parquet_float_predicate() -> Result<(), Box<dyn Error>> {
+        let file = write_sample_file().await?;
+
+        // The row group of 20..30 may contain values less than 20.5:
+        let (mut out, mut err) = (Channel::bounded(10), Channel::bounded(10));
+        let options = ParquetReadOptions::default().with_predicate(Predicate::lt(0, 20.5));
+        let reader = tokio::spawn(read_parquet(
+            std::io::Cursor::new(file),
+            options,
+            out.tx,
+            err.tx,
+        ));
+        let _ = tokio::join!(reader);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs.len(), 3); // the row groups of 0..10, 10..20 and 20..30
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    async fn write_sample_file() -> Result<Vec<u8>, Box<dyn Error>> {
+        let (mut in_, mut out, mut err) = (
+            Channel::bounded(10),
+            Channel::oneshot(),
+            Channel::bounded(10),
+        );
+        let mut file = Vec::new();
+        let options = ParquetWriteOptions::default().with_max_row_group_size(10);
+        let writer = write_parquet(&mut file, options, in_.rx, out.tx, err.tx);
+        let sender = async move {
+            for i in 0..5 {
+                let numbers = Int32Array::from_iter_values(i * 10..(i + 1) * 10);
+                let names =
+                    StringArray::from_iter_values(numbers.values().iter().map(|n| format!("#{n}")));
+                let batch = RecordBatch::try_from_iter([
+                    ("n", Arc::new(numbers) as _),
+                    ("name", Arc::new(names) as _),
+                ])?;
+                in_.tx.send(batch).await?;
+            }
+            in_.tx.close();
+            Ok::<_, Box<dyn Error>>(())
+        };
+        let (result, sent) = tokio::join!(writer, sender);
+        result?;
+        sent?;
+        assert_eq!(out.rx.recv_all().await?.len(), 1);
+        assert!(err.rx.recv_all().await?.is_empty());
+        Ok(file)
+    }
+}
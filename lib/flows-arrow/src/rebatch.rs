@@ -0,0 +1,189 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::BatchSize;
+use alloc::vec::Vec;
+use arrow_array::RecordBatch;
+use arrow_schema::{ArrowError, SchemaRef};
+use async_flow::{Inputs, Outputs, Port, Result};
+use flows_derive::block;
+use flows_error::BlockError;
+
+/// A block that re-batches input batches into output batches of the given
+/// target size, coalescing small batches and slicing big ones.
+///
+/// Slicing is zero-copy, so input rows are only copied when several batches
+/// are coalesced into one. Given a target in bytes, the size of each input
+/// batch's rows is estimated from its array memory size, and a row larger
+/// than the target is output on its own. The remaining rows are output at
+/// the end of the stream. Batches with a schema that differs from the first
+/// batch are reported on the `errors` port.
+#[block]
+pub async fn rebatch(
+    size: BatchSize,
+    mut inputs: Inputs<RecordBatch>,
+    outputs: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    let mut rebatcher = Rebatcher::new(size);
+
+    while let Some(input) = inputs.recv().await? {
+        match rebatcher.push(input) {
+            Ok(batches) if !outputs.is_closed() => {
+                for batch in batches {
+                    outputs.send(batch).await?;
+                }
+            },
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("rebatch", error))
+                    .await?
+            },
+            _ => continue,
+        }
+    }
+
+    match rebatcher.flush() {
+        Ok(Some(batch)) if !outputs.is_closed() => outputs.send(batch).await?,
+        Err(error) if errors.is_connected() => {
+            errors
+                .send(BlockError::from_error("rebatch", error))
+                .await?
+        },
+        _ => {},
+    }
+
+    Ok(())
+}
+
+struct Rebatcher {
+    size: BatchSize,
+    schema: Option<SchemaRef>,
+    pending: Vec<RecordBatch>,
+    pending_size: usize,
+}
+
+impl Rebatcher {
+    fn new(size: BatchSize) -> Self {
+        Self {
+            size,
+            schema: None,
+            pending: Vec::new(),
+            pending_size: 0,
+        }
+    }
+
+    /// Adds the given batch, returning the batches that are now complete.
+    fn push(&mut self, batch: RecordBatch) -> core::result::Result<Vec<RecordBatch>, ArrowError> {
+        match &self.schema {
+            None => self.schema = Some(batch.schema()),
+            Some(schema) if *schema != batch.schema() => {
+                return Err(ArrowError::SchemaError(
+                    "batch schema differs from the first batch".into(),
+                ));
+            },
+            Some(_) => {},
+        }
+
+        let mut outputs = Vec::new();
+        let (target, weight) = (self.size.target(), self.size.row_weight(&batch));
+        let mut offset = 0;
+        while offset < batch.num_rows() {
+            let mut fit = target.saturating_sub(self.pending_size) / weight;
+            if fit == 0 {
+                if !self.pending.is_empty() {
+                    outputs.extend(self.flush()?);
+                    continue;
+                }
+                fit = 1; // an oversized row goes on its own
+            }
+
+            let len = fit.min(batch.num_rows() - offset);
+            let slice = batch.slice(offset, len);
+            offset += len;
+
+            if len < fit {
+                self.pending.push(slice); // await more rows
+                self.pending_size += len * weight;
+            } else if self.pending.is_empty() {
+                outputs.push(slice); // no need to copy
+            } else {
+                self.pending.push(slice);
+                outputs.extend(self.flush()?);
+            }
+        }
+
+        Ok(outputs)
+    }
+
+    /// Coalesces the pending batches into one.
+    fn flush(&mut self) -> core::result::Result<Option<RecordBatch>, ArrowError> {
+        self.pending_size = 0;
+        let pending = core::mem::take(&mut self.pending);
+        match pending.len() {
+            0 => Ok(None),
+            1 => Ok(pending.into_iter().next()),
+            _ => arrow_select::concat::concat_batches(&pending[0].schema(), &pending).map(Some),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, sync::Arc, vec};
+    use arrow_array::{Int32Array, cast::AsArray, record_batch, types::Int32Type};
+    use arrow_schema::{DataType, Field, Schema};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_rebatch() -> Result<(), Box<dyn Error>> {
+        let (mut in_, mut out) = (Channel::bounded(100), Channel::bounded(100));
+        let mut err = Channel::bounded(10);
+        let rebatcher = tokio::spawn(rebatch(BatchSize::Rows(4), in_.rx, out.tx, err.tx));
+
+        let schema = Arc::new(Schema::new(vec![Field::new("n", DataType::Int32, false)]));
+        let mut next = 0;
+        for len in [1, 1, 1, 10, 0, 2, 3] {
+            let values = Int32Array::from_iter_values(next..next + len);
+            next += len;
+            in_.tx
+                .send(RecordBatch::try_new(
+                    schema.clone(),
+                    vec![Arc::new(values)],
+                )?)
+                .await?;
+        }
+        in_.tx.send(record_batch!(("m", Int32, [1]))?).await?; // mismatched schema
+        in_.tx.close();
+
+        let _ = tokio::join!(rebatcher);
+
+        let outputs = out.rx.recv_all().await?;
+        let sizes: Vec<usize> = outputs.iter().map(RecordBatch::num_rows).collect();
+        assert_eq!(sizes, [4, 4, 4, 4, 2]);
+        let values: Vec<i32> = outputs
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column(0)
+                    .as_primitive::<Int32Type>()
+                    .values()
+                    .to_vec()
+            })
+            .collect();
+        assert_eq!(values, (0..18).collect::<Vec<_>>());
+        assert_eq!(err.rx.recv_all().await?.len(), 1);
+
+        let values = Int32Array::from_iter_values(0..1000);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(values)])?;
+        let row_size = BatchSize::Bytes(0).row_weight(&batch);
+        let mut rebatcher = Rebatcher::new(BatchSize::Bytes(300 * row_size));
+        let outputs = rebatcher.push(batch)?;
+        let sizes: Vec<usize> = outputs.iter().map(RecordBatch::num_rows).collect();
+        assert_eq!(sizes, [300, 300, 300]);
+        assert_eq!(rebatcher.flush()?.map(|batch| batch.num_rows()), Some(100));
+
+        Ok(())
+    }
+}
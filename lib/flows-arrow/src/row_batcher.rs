@@ -0,0 +1,134 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::SchemaSource;
+use alloc::{sync::Arc, vec::Vec};
+use arrow_array::RecordBatch;
+use arrow_schema::{ArrowError, Schema, SchemaRef};
+
+/// A text format whose rows can be decoded independently of each other.
+pub(crate) trait RowFormat {
+    /// Infers a schema from the given (concatenated) rows.
+    fn infer_schema(&self, rows: &[u8], max_rows: usize) -> Result<Schema, ArrowError>;
+
+    /// Decodes the given (concatenated) rows into a batch.
+    fn decode(
+        &self,
+        schema: &SchemaRef,
+        rows: &[u8],
+        num_rows: usize,
+    ) -> Result<Option<RecordBatch>, ArrowError>;
+}
+
+/// A raw row of text input, numbered from 1 in order of arrival.
+pub(crate) struct Row {
+    pub(crate) number: usize,
+    pub(crate) bytes: Vec<u8>,
+}
+
+/// The result of decoding a batch of rows: the rows that decoded fine, and
+/// the numbers of those that didn't.
+pub(crate) struct Decoded {
+    pub(crate) batch: Option<RecordBatch>,
+    pub(crate) errors: Vec<(usize, ArrowError)>,
+}
+
+/// Buffers raw rows into batches of a given size, inferring the schema from
+/// the initial rows if need be.
+pub(crate) struct RowBatcher<F> {
+    format: F,
+    schema: SchemaSource,
+    batch_size: usize,
+    rows: Vec<Row>,
+}
+
+impl<F: RowFormat> RowBatcher<F> {
+    /// Returns a batcher for batches of the given size, or an error in case
+    /// the size is zero.
+    pub(crate) fn new(
+        format: F,
+        schema: SchemaSource,
+        batch_size: usize,
+    ) -> Result<Self, ArrowError> {
+        if batch_size == 0 {
+            return Err(ArrowError::InvalidArgumentError(
+                "the batch size is zero".into(),
+            ));
+        }
+        Ok(Self {
+            format,
+            schema,
+            batch_size,
+            rows: Vec::new(),
+        })
+    }
+
+    pub(crate) fn format_mut(&mut self) -> &mut F {
+        &mut self.format
+    }
+
+    pub(crate) fn push(&mut self, row: Row) {
+        self.rows.push(row);
+    }
+
+    /// Decodes the next batch of rows, if enough rows are buffered or if
+    /// `finished` is set. Failing to infer a schema is a fatal error.
+    pub(crate) fn next_batch(&mut self, finished: bool) -> Result<Option<Decoded>, ArrowError> {
+        let schema = match &self.schema {
+            SchemaSource::Fixed(schema) => schema.clone(),
+            SchemaSource::Infer(max_rows) => {
+                let max_rows = (*max_rows).max(1);
+                if self.rows.is_empty() || (self.rows.len() < max_rows && !finished) {
+                    return Ok(None);
+                }
+                let sample = concat(&self.rows[..self.rows.len().min(max_rows)]);
+                let schema = Arc::new(self.format.infer_schema(&sample, max_rows)?);
+                self.schema = SchemaSource::Fixed(schema.clone());
+                schema
+            },
+        };
+
+        if self.rows.is_empty() || (self.rows.len() < self.batch_size && !finished) {
+            return Ok(None);
+        }
+
+        let rows: Vec<Row> = self
+            .rows
+            .drain(..self.rows.len().min(self.batch_size))
+            .collect();
+        let batch = match self.format.decode(&schema, &concat(&rows), rows.len()) {
+            Ok(batch) => Decoded {
+                batch,
+                errors: Vec::new(),
+            },
+            Err(_) => self.decode_each(&schema, &rows)?,
+        };
+        Ok(Some(batch))
+    }
+
+    /// Decodes the given rows one at a time, so as to isolate the rows that
+    /// fail to decode from those that don't.
+    fn decode_each(&self, schema: &SchemaRef, rows: &[Row]) -> Result<Decoded, ArrowError> {
+        let mut batches = Vec::with_capacity(rows.len());
+        let mut errors = Vec::new();
+        for row in rows {
+            match self.format.decode(schema, &row.bytes, 1) {
+                Ok(Some(batch)) => batches.push(batch),
+                Ok(None) => {},
+                Err(error) => errors.push((row.number, error)),
+            }
+        }
+        let batch = match batches.is_empty() {
+            true => None,
+            false => Some(arrow_select::concat::concat_batches(schema, &batches)?),
+        };
+        Ok(Decoded { batch, errors })
+    }
+}
+
+fn concat(rows: &[Row]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(rows.iter().map(|row| row.bytes.len()).sum());
+    for row in rows {
+        bytes.extend_from_slice(&row.bytes);
+    }
+    bytes
+}
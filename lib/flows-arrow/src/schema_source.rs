@@ -0,0 +1,25 @@
+// This is free and unencumbered software released into the public domain.
+
+use arrow_schema::SchemaRef;
+
+/// Where a decoder block obtains the schema of the batches it outputs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SchemaSource {
+    /// Decodes rows using the given schema.
+    Fixed(SchemaRef),
+
+    /// Infers the schema from (at most) the given number of initial rows.
+    Infer(usize),
+}
+
+impl Default for SchemaSource {
+    fn default() -> Self {
+        Self::Infer(1000)
+    }
+}
+
+impl From<SchemaRef> for SchemaSource {
+    fn from(schema: SchemaRef) -> Self {
+        Self::Fixed(schema)
+    }
+}
@@ -0,0 +1,296 @@
+// This is free and unencumbered software released into the public domain.
+
+use alloc::{format, string::String, sync::Arc, vec::Vec};
+use arrow_array::{ArrayRef, RecordBatch, RecordBatchOptions, new_null_array};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef};
+
+/// How blocks that combine batches treat batches with differing schemas.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SchemaMode {
+    /// Requires every batch to have the same schema as the first batch.
+    #[default]
+    Strict,
+
+    /// Unifies the schemas of all batches: columns are matched by name
+    /// regardless of their position, columns that some batches lack are made
+    /// nullable, and numeric columns are widened to a common datatype.
+    Unify,
+}
+
+/// Combines the schemas of a sequence of batches according to a
+/// [`SchemaMode`].
+#[derive(Clone, Debug, Default)]
+pub struct SchemaUnifier {
+    mode: SchemaMode,
+    schema: Option<SchemaRef>,
+}
+
+impl SchemaUnifier {
+    pub fn new(mode: SchemaMode) -> Self {
+        Self { mode, schema: None }
+    }
+
+    /// Returns the schema combined so far, if any.
+    pub fn schema(&self) -> Option<&SchemaRef> {
+        self.schema.as_ref()
+    }
+
+    /// Combines the given schema into the schema so far, leaving the latter
+    /// unchanged if the given schema is incompatible with it.
+    pub fn merge(&mut self, schema: &SchemaRef) -> Result<(), ArrowError> {
+        let Some(current) = &self.schema else {
+            self.schema = Some(schema.clone());
+            return Ok(());
+        };
+        if current == schema {
+            return Ok(());
+        }
+
+        if self.mode == SchemaMode::Strict {
+            return Err(ArrowError::SchemaError(format!(
+                "batch schema ({}) differs from the first batch ({})",
+                describe(schema),
+                describe(current),
+            )));
+        }
+
+        let mut fields: Vec<Field> = current
+            .fields()
+            .iter()
+            .map(|field| {
+                let present = schema.field_with_name(field.name()).is_ok();
+                field
+                    .as_ref()
+                    .clone()
+                    .with_nullable(field.is_nullable() || !present)
+            })
+            .collect();
+        for field in schema.fields() {
+            match fields.iter_mut().find(|other| other.name() == field.name()) {
+                Some(other) => {
+                    let data_type =
+                        widen(other.data_type(), field.data_type()).ok_or_else(|| {
+                            ArrowError::SchemaError(format!(
+                                "column '{}' has type {}, which is incompatible with {}",
+                                field.name(),
+                                field.data_type(),
+                                other.data_type(),
+                            ))
+                        })?;
+                    let nullable = other.is_nullable() || field.is_nullable();
+                    *other = other
+                        .clone()
+                        .with_data_type(data_type)
+                        .with_nullable(nullable);
+                },
+                None => fields.push(field.as_ref().clone().with_nullable(true)),
+            }
+        }
+
+        let metadata = current.metadata().clone();
+        self.schema = Some(Arc::new(Schema::new_with_metadata(fields, metadata)));
+        Ok(())
+    }
+
+    /// Conforms the given batch to the schema combined so far, reordering,
+    /// casting, and null-filling its columns as needed.
+    pub fn align(&self, batch: &RecordBatch) -> Result<RecordBatch, ArrowError> {
+        let Some(schema) = &self.schema else {
+            return Ok(batch.clone());
+        };
+        if batch.schema_ref() == schema {
+            return Ok(batch.clone());
+        }
+
+        let columns = schema
+            .fields()
+            .iter()
+            .map(|field| match batch.column_by_name(field.name()) {
+                Some(column) if column.data_type() == field.data_type() => Ok(column.clone()),
+                Some(column) => arrow_cast::cast(column, field.data_type()),
+                None => Ok(new_null_array(field.data_type(), batch.num_rows())),
+            })
+            .collect::<Result<Vec<ArrayRef>, _>>()?;
+
+        let options = RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
+        RecordBatch::try_new_with_options(schema.clone(), columns, &options)
+    }
+}
+
+/// Returns the narrowest datatype that both given datatypes convert to
+/// without loss, except that large integers may convert to floating point.
+pub(crate) fn widen(lhs: &DataType, rhs: &DataType) -> Option<DataType> {
+    use DataType::*;
+    if lhs == rhs {
+        return Some(lhs.clone());
+    }
+    match (lhs, rhs) {
+        (Null, other) | (other, Null) => Some(other.clone()),
+        _ if lhs.is_floating() || rhs.is_floating() => {
+            let fits_f32 = |data_type: &DataType| {
+                matches!(data_type, Float16 | Float32 | Int8 | Int16 | UInt8 | UInt16)
+            };
+            if !is_int_or_float(lhs) || !is_int_or_float(rhs) {
+                None
+            } else if fits_f32(lhs) && fits_f32(rhs) {
+                Some(Float32)
+            } else {
+                Some(Float64)
+            }
+        },
+        _ => {
+            let (lhs_width, rhs_width) = (int_width(lhs)?, int_width(rhs)?);
+            match (lhs.is_signed_integer(), rhs.is_signed_integer()) {
+                (true, true) | (false, false) => match lhs_width >= rhs_width {
+                    true => Some(lhs.clone()),
+                    false => Some(rhs.clone()),
+                },
+                (true, false) => signed_int(lhs_width.max(rhs_width * 2)),
+                (false, true) => signed_int(rhs_width.max(lhs_width * 2)),
+            }
+        },
+    }
+}
+
+/// Returns the narrowest datatype that both given numeric datatypes convert
+/// to without loss, for comparing or combining their values. Unlike
+/// [`widen`], this also covers 64-bit integers of differing signedness and
+/// decimals.
+pub(crate) fn supertype(lhs: &DataType, rhs: &DataType) -> Option<DataType> {
+    use DataType::*;
+    if let Some(data_type) = widen(lhs, rhs) {
+        return Some(data_type);
+    }
+    let (decimal, other) = match lhs.is_decimal() {
+        true => (lhs, rhs),
+        false => (rhs, lhs),
+    };
+    match decimal {
+        _ if lhs.is_integer() && rhs.is_integer() => Some(Decimal128(20, 0)),
+        _ if !decimal.is_decimal() => None,
+        _ if other.is_floating() => Some(Float64),
+        Decimal32(precision, scale)
+        | Decimal64(precision, scale)
+        | Decimal128(precision, scale)
+        | Decimal256(precision, scale)
+            if other.is_integer() =>
+        {
+            // Leave room for the integer digits of both the decimal and any
+            // 64-bit integer:
+            let digits = (*precision as i16 - *scale as i16).max(20);
+            let scale = (*scale).max(0);
+            match digits + scale as i16 <= 38 {
+                true => Some(Decimal128(38, scale)),
+                false => Some(Decimal256(76, scale)),
+            }
+        },
+        _ => None,
+    }
+}
+
+fn is_int_or_float(data_type: &DataType) -> bool {
+    data_type.is_integer() || data_type.is_floating()
+}
+
+fn int_width(data_type: &DataType) -> Option<u32> {
+    use DataType::*;
+    match data_type {
+        Int8 | UInt8 => Some(8),
+        Int16 | UInt16 => Some(16),
+        Int32 | UInt32 => Some(32),
+        Int64 | UInt64 => Some(64),
+        _ => None,
+    }
+}
+
+fn signed_int(width: u32) -> Option<DataType> {
+    use DataType::*;
+    match width {
+        8 => Some(Int8),
+        16 => Some(Int16),
+        32 => Some(Int32),
+        64 => Some(Int64),
+        _ => None,
+    }
+}
+
+fn describe(schema: &Schema) -> String {
+    let fields: Vec<String> = schema
+        .fields()
+        .iter()
+        .map(|field| format!("{}: {}", field.name(), field.data_type()))
+        .collect();
+    fields.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, string::ToString, vec};
+    use arrow_array::{cast::AsArray, record_batch, types::Int64Type};
+    use core::error::Error;
+
+    #[test]
+    fn test_schema_unifier() -> Result<(), Box<dyn Error>> {
+        let batch1 = record_batch!(("a", Int32, [1, 2]), ("b", Utf8, ["x", "y"]))?;
+        let batch2 = record_batch!(
+            ("b", Utf8, ["z"]),
+            ("a", Int64, [3]),
+            ("c", Boolean, [true])
+        )?;
+        let batch3 = record_batch!(("a", Utf8, ["oops"]))?;
+
+        let mut strict = SchemaUnifier::new(SchemaMode::Strict);
+        strict.merge(batch1.schema_ref())?;
+        assert!(strict.merge(batch2.schema_ref()).is_err());
+
+        let mut unifier = SchemaUnifier::new(SchemaMode::Unify);
+        unifier.merge(batch1.schema_ref())?;
+        unifier.merge(batch2.schema_ref())?;
+        let error = unifier.merge(batch3.schema_ref()).unwrap_err();
+        assert!(error.to_string().contains("column 'a' has type Utf8"));
+
+        let schema = unifier.schema().unwrap().clone();
+        let names: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(names, ["a", "b", "c"]);
+        assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+        assert!(schema.field(2).is_nullable());
+
+        let batch = unifier.align(&batch1)?;
+        assert_eq!(batch.schema(), schema);
+        assert_eq!(
+            batch.column(0).as_primitive::<Int64Type>().values(),
+            &[1, 2]
+        );
+        assert_eq!(batch.column(2).null_count(), 2);
+
+        assert_eq!(
+            widen(&DataType::UInt32, &DataType::Int8),
+            Some(DataType::Int64)
+        );
+        assert_eq!(widen(&DataType::UInt64, &DataType::Int8), None);
+        assert_eq!(
+            widen(&DataType::Int16, &DataType::Float16),
+            Some(DataType::Float32)
+        );
+        assert_eq!(
+            widen(&DataType::Int32, &DataType::Float32),
+            Some(DataType::Float64)
+        );
+        assert_eq!(
+            supertype(&DataType::UInt64, &DataType::Int8),
+            Some(DataType::Decimal128(20, 0))
+        );
+        assert_eq!(
+            supertype(&DataType::Decimal128(10, 2), &DataType::Int64),
+            Some(DataType::Decimal128(38, 2))
+        );
+        assert_eq!(
+            supertype(&DataType::Float64, &DataType::Decimal32(9, 2)),
+            Some(DataType::Float64)
+        );
+        assert_eq!(supertype(&DataType::Utf8, &DataType::Int64), None);
+
+        Ok(())
+    }
+}
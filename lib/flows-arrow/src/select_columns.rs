@@ -0,0 +1,109 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::Projection;
+use arrow_array::RecordBatch;
+use arrow_schema::SchemaRef;
+use async_flow::{Inputs, Outputs, Port, Result};
+use flows_derive::block;
+use flows_error::BlockError;
+
+/// A block that projects input batches onto columns selected by name,
+/// renaming and casting them as specified by the given projection.
+///
+/// The output schema is output on the `schemas` port ahead of the output
+/// batches: right away if the projection alone determines it, or else along
+/// with the first output batch. It is output again whenever it changes, as
+/// may happen when upstream schemas evolve. Batches that can't be projected
+/// (e.g., due to a missing column or a value that can't be cast) are
+/// reported on the `errors` port.
+#[block]
+pub async fn select_columns(
+    projection: Projection,
+    mut inputs: Inputs<RecordBatch>,
+    outputs: Outputs<RecordBatch>,
+    schemas: Outputs<SchemaRef>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    let mut schema = projection.schema();
+    if let Some(schema) = &schema
+        && !schemas.is_closed()
+    {
+        schemas.send(schema.clone()).await?;
+    }
+
+    while let Some(input) = inputs.recv().await? {
+        let output = match projection.project(&input) {
+            Ok(output) => output,
+            Err(error) => {
+                if errors.is_connected() {
+                    errors
+                        .send(BlockError::from_error("select_columns", error))
+                        .await?;
+                }
+                continue;
+            },
+        };
+
+        if schema.as_ref() != Some(output.schema_ref()) {
+            let schema = schema.insert(output.schema());
+            if !schemas.is_closed() {
+                schemas.send(schema.clone()).await?;
+            }
+        }
+
+        if !outputs.is_closed() {
+            outputs.send(output).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MissingColumns;
+    use alloc::{boxed::Box, vec};
+    use arrow_array::record_batch;
+    use arrow_schema::DataType;
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_select_columns() -> Result<(), Box<dyn Error>> {
+        let (mut in_, mut out) = (Channel::bounded(10), Channel::bounded(10));
+        let (mut schemas, mut err) = (Channel::bounded(10), Channel::bounded(10));
+        let projection = Projection::new()
+            .cast("a", DataType::Int64)
+            .cast("b", DataType::Utf8)
+            .on_missing(MissingColumns::Null);
+        let expected_schema = projection.schema().unwrap();
+        let selector = tokio::spawn(select_columns(
+            projection, in_.rx, out.tx, schemas.tx, err.tx,
+        ));
+
+        in_.tx
+            .send(record_batch!(
+                ("b", Int32, [1, 2]),
+                ("a", Utf8, ["3", "4"])
+            )?)
+            .await?;
+        in_.tx.send(record_batch!(("a", Utf8, ["5"]))?).await?;
+        in_.tx.send(record_batch!(("a", Utf8, ["oops"]))?).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(selector);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs.len(), 2);
+        assert!(
+            outputs
+                .iter()
+                .all(|batch| batch.schema() == expected_schema)
+        );
+        assert_eq!(schemas.rx.recv_all().await?, [expected_schema]);
+        assert_eq!(err.rx.recv_all().await?.len(), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{SortKey, sort_key::sort_columns};
+use alloc::vec::Vec;
+use arrow_array::RecordBatch;
+use arrow_ord::sort::{SortColumn, lexsort_to_indices};
+use arrow_schema::ArrowError;
+use async_flow::{Inputs, Output, Outputs, Port, Result};
+use flows_derive::block;
+use flows_error::BlockError;
+
+/// A block that sorts all input batches by the given keys, outputting a
+/// single sorted batch at the end of the stream.
+///
+/// Batches are buffered in memory until the input is closed; see `top_k` for
+/// a block that only needs to retain a bounded number of rows. Failures to
+/// concatenate or sort the batches are reported on the `errors` port.
+#[block]
+pub async fn sort_batches(
+    keys: &[SortKey],
+    mut inputs: Inputs<RecordBatch>,
+    output: Output<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    let mut batches: Vec<RecordBatch> = Vec::new();
+
+    while let Some(batch) = inputs.recv().await? {
+        if batch.num_rows() == 0 && !batches.is_empty() {
+            continue; // skip empty batches after the first one
+        }
+        batches.push(batch);
+    }
+
+    if !batches.is_empty() {
+        match sort(&batches, keys) {
+            Ok(batch) if !output.is_closed() => output.send(batch).await?,
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("sort_batches", error))
+                    .await?
+            },
+            _ => {},
+        }
+    }
+
+    Ok(())
+}
+
+fn sort(
+    batches: &[RecordBatch],
+    keys: &[SortKey],
+) -> core::result::Result<RecordBatch, ArrowError> {
+    let batch = arrow_select::concat::concat_batches(&batches[0].schema(), batches)?;
+
+    let columns: Vec<SortColumn> = sort_columns(&batch, keys)?
+        .into_iter()
+        .zip(keys)
+        .map(|(values, key)| SortColumn {
+            values,
+            options: Some(key.options()),
+        })
+        .collect();
+
+    let indices = lexsort_to_indices(&columns, None)?;
+    arrow_select::take::take_record_batch(&batch, &indices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use arrow_array::{cast::AsArray, record_batch, types::Int32Type};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_sort_batches() -> Result<(), Box<dyn Error>> {
+        let (mut in_, mut out) = (Channel::bounded(10), Channel::oneshot());
+        let mut err = Channel::bounded(1);
+        const KEYS: &[SortKey] = &[SortKey::asc(1).nulls_first(), SortKey::desc(0)];
+        let sorter = tokio::spawn(sort_batches(KEYS, in_.rx, out.tx, err.tx));
+
+        in_.tx
+            .send(record_batch!(
+                ("a", Int32, [1, 2, 3]),
+                ("b", Utf8, [Some("y"), None, Some("x")])
+            )?)
+            .await?;
+        in_.tx
+            .send(record_batch!(
+                ("a", Int32, [4, 5, 6]),
+                ("b", Utf8, [Some("x"), Some("y"), None])
+            )?)
+            .await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(sorter);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(
+            outputs[0].column(0).as_primitive::<Int32Type>().values(),
+            &[6, 2, 4, 3, 5, 1]
+        );
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+// This is free and unencumbered software released into the public domain.
+
+use alloc::{format, vec::Vec};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_schema::{ArrowError, SortOptions};
+
+/// A sort key over a column of a record batch, referenced by its index.
+///
+/// Keys sort in ascending order with nulls last by default.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SortKey {
+    pub column: usize,
+    pub descending: bool,
+    pub nulls_first: bool,
+}
+
+impl SortKey {
+    /// Sorts the given column in ascending order.
+    pub const fn asc(column: usize) -> Self {
+        Self {
+            column,
+            descending: false,
+            nulls_first: false,
+        }
+    }
+
+    /// Sorts the given column in descending order.
+    pub const fn desc(column: usize) -> Self {
+        Self {
+            column,
+            descending: true,
+            nulls_first: false,
+        }
+    }
+
+    /// Sorts nulls before non-null values.
+    pub const fn nulls_first(self) -> Self {
+        Self {
+            nulls_first: true,
+            ..self
+        }
+    }
+
+    /// Sorts nulls after non-null values.
+    pub const fn nulls_last(self) -> Self {
+        Self {
+            nulls_first: false,
+            ..self
+        }
+    }
+
+    pub const fn options(&self) -> SortOptions {
+        SortOptions {
+            descending: self.descending,
+            nulls_first: self.nulls_first,
+        }
+    }
+}
+
+/// Returns the columns of the given batch that the given keys refer to.
+pub(crate) fn sort_columns(
+    batch: &RecordBatch,
+    keys: &[SortKey],
+) -> Result<Vec<ArrayRef>, ArrowError> {
+    keys.iter()
+        .map(|key| {
+            batch.columns().get(key.column).cloned().ok_or_else(|| {
+                ArrowError::InvalidArgumentError(format!(
+                    "column index {} is out of bounds",
+                    key.column
+                ))
+            })
+        })
+        .collect()
+}
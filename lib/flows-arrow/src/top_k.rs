@@ -0,0 +1,220 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{SortKey, sort_key::sort_columns};
+use alloc::{collections::BinaryHeap, vec::Vec};
+use arrow_array::RecordBatch;
+use arrow_row::{OwnedRow, RowConverter, SortField};
+use arrow_schema::{ArrowError, SchemaRef};
+use async_flow::{Inputs, Output, Outputs, Port, Result};
+use flows_derive::block;
+use flows_error::BlockError;
+
+/// A block that outputs the first `k` rows of all input batches as ordered
+/// by the given keys, i.e., `ORDER BY ... LIMIT k`, as a single sorted batch
+/// at the end of the stream.
+///
+/// Only the current top `k` rows are retained in a bounded heap, so memory
+/// use stays constant regardless of the number of input batches. Batches
+/// that can't be ranked (e.g., due to an out-of-bounds column index or a
+/// schema that differs from the first batch) are reported on the `errors`
+/// port.
+#[block]
+pub async fn top_k(
+    k: usize,
+    keys: &[SortKey],
+    mut inputs: Inputs<RecordBatch>,
+    output: Output<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    let mut heap = TopK::new(k, keys);
+
+    while let Some(batch) = inputs.recv().await? {
+        if let Err(error) = heap.insert(batch)
+            && errors.is_connected()
+        {
+            errors.send(BlockError::from_error("top_k", error)).await?;
+        }
+    }
+
+    match heap.finish() {
+        Ok(Some(batch)) if !output.is_closed() => output.send(batch).await?,
+        Err(error) if errors.is_connected() => {
+            errors.send(BlockError::from_error("top_k", error)).await?
+        },
+        _ => {},
+    }
+
+    Ok(())
+}
+
+struct TopK<'a> {
+    k: usize,
+    keys: &'a [SortKey],
+    schema: Option<SchemaRef>,
+    converter: Option<RowConverter>,
+    entries: BinaryHeap<Entry>,
+    batches: Vec<RecordBatch>,
+}
+
+/// A row in the heap, ordered by its sort key and then by arrival.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Entry {
+    row: OwnedRow,
+    batch: usize,
+    index: usize,
+}
+
+impl<'a> TopK<'a> {
+    fn new(k: usize, keys: &'a [SortKey]) -> Self {
+        Self {
+            k,
+            keys,
+            schema: None,
+            converter: None,
+            entries: BinaryHeap::with_capacity(k),
+            batches: Vec::new(),
+        }
+    }
+
+    fn insert(&mut self, batch: RecordBatch) -> core::result::Result<(), ArrowError> {
+        match &self.schema {
+            None => self.schema = Some(batch.schema()),
+            Some(schema) if *schema != batch.schema() => {
+                return Err(ArrowError::SchemaError(
+                    "batch schema differs from the first batch".into(),
+                ));
+            },
+            Some(_) => {},
+        }
+
+        if self.k == 0 || batch.num_rows() == 0 {
+            return Ok(()); // skip empty batches
+        }
+
+        let columns = sort_columns(&batch, self.keys)?;
+        let converter = match &mut self.converter {
+            Some(converter) => converter,
+            None => self.converter.insert(RowConverter::new(
+                columns
+                    .iter()
+                    .zip(self.keys)
+                    .map(|(column, key)| {
+                        SortField::new_with_options(column.data_type().clone(), key.options())
+                    })
+                    .collect(),
+            )?),
+        };
+        let rows = converter.convert_columns(&columns)?;
+
+        let slot = self.batches.len();
+        let mut retained = false;
+        for (index, row) in rows.iter().enumerate() {
+            if self.entries.len() == self.k {
+                match self.entries.peek() {
+                    Some(top) if row < top.row.row() => _ = self.entries.pop(),
+                    _ => continue, // not among the top k rows
+                }
+            }
+            self.entries.push(Entry {
+                row: row.owned(),
+                batch: slot,
+                index,
+            });
+            retained = true;
+        }
+
+        if retained {
+            self.batches.push(batch);
+        }
+
+        let retained_rows: usize = self.batches.iter().map(RecordBatch::num_rows).sum();
+        if self.batches.len() > 1 && retained_rows > 2 * self.k {
+            self.compact()?;
+        }
+
+        Ok(())
+    }
+
+    /// Copies the rows in the heap into a single batch, releasing the input
+    /// batches they were drawn from.
+    fn compact(&mut self) -> core::result::Result<(), ArrowError> {
+        let entries = core::mem::take(&mut self.entries).into_sorted_vec();
+        let batch = self.take(&entries)?;
+        self.entries = entries
+            .into_iter()
+            .enumerate()
+            .map(|(index, entry)| Entry {
+                row: entry.row,
+                batch: 0,
+                index,
+            })
+            .collect();
+        self.batches = alloc::vec![batch];
+        Ok(())
+    }
+
+    fn finish(mut self) -> core::result::Result<Option<RecordBatch>, ArrowError> {
+        let Some(schema) = self.schema.take() else {
+            return Ok(None); // no input batches
+        };
+        if self.entries.is_empty() {
+            return Ok(Some(RecordBatch::new_empty(schema)));
+        }
+        let entries = core::mem::take(&mut self.entries).into_sorted_vec();
+        self.take(&entries).map(Some)
+    }
+
+    fn take(&self, entries: &[Entry]) -> core::result::Result<RecordBatch, ArrowError> {
+        let batches: Vec<&RecordBatch> = self.batches.iter().collect();
+        let indices: Vec<(usize, usize)> = entries
+            .iter()
+            .map(|entry| (entry.batch, entry.index))
+            .collect();
+        arrow_select::interleave::interleave_record_batch(&batches, &indices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, sync::Arc, vec};
+    use arrow_array::{Int32Array, cast::AsArray, record_batch, types::Int32Type};
+    use arrow_schema::{DataType, Field, Schema};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_top_k() -> Result<(), Box<dyn Error>> {
+        let (mut in_, mut out) = (Channel::bounded(10), Channel::oneshot());
+        let mut err = Channel::bounded(10);
+        const KEYS: &[SortKey] = &[SortKey::desc(0)];
+        let ranker = tokio::spawn(top_k(3, KEYS, in_.rx, out.tx, err.tx));
+
+        let schema = Arc::new(Schema::new(vec![Field::new("n", DataType::Int32, false)]));
+        for i in 0..100 {
+            let values = Int32Array::from_iter_values((0..10).map(|j| (i * 37 + j * 11) % 1000));
+            let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(values)])?;
+            in_.tx.send(batch).await?;
+        }
+        in_.tx.send(record_batch!(("m", Int32, [1]))?).await?; // mismatched schema
+        in_.tx.close();
+
+        let _ = tokio::join!(ranker);
+
+        let mut expected: Vec<i32> = (0..100)
+            .flat_map(|i| (0..10).map(move |j| (i * 37 + j * 11) % 1000))
+            .collect();
+        expected.sort_by(|a, b| b.cmp(a));
+        expected.truncate(3);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(
+            outputs[0].column(0).as_primitive::<Int32Type>().values(),
+            expected.as_slice()
+        );
+        assert_eq!(err.rx.recv_all().await?.len(), 1);
+
+        Ok(())
+    }
+}
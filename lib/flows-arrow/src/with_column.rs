@@ -0,0 +1,149 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::Expr;
+use alloc::{string::String, sync::Arc, vec::Vec};
+use arrow_array::{RecordBatch, RecordBatchOptions};
+use arrow_schema::{ArrowError, Field, Schema};
+use async_flow::{Inputs, Outputs, Port, Result};
+use flows_derive::block;
+use flows_error::BlockError;
+
+/// A block that adds a column computed by an expression to input batches,
+/// replacing any existing column of the same name in place.
+///
+/// The computed column is nullable. Batches that the expression fails to
+/// evaluate on (e.g., due to a missing column or an overflow) are reported on
+/// the `errors` port.
+#[block]
+pub async fn with_column(
+    name: impl Into<String>,
+    expr: Expr,
+    mut inputs: Inputs<RecordBatch>,
+    outputs: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    let name = name.into();
+
+    while let Some(input) = inputs.recv().await? {
+        match with_expr(&input, &name, &expr) {
+            Ok(output) if !outputs.is_closed() => outputs.send(output).await?,
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("with_column", error))
+                    .await?
+            },
+            _ => continue,
+        }
+    }
+
+    Ok(())
+}
+
+/// A block that adds several columns computed by expressions to input
+/// batches, replacing any existing columns of the same names in place.
+///
+/// The columns are computed in order, so an expression may refer to the
+/// columns computed before it. Batches that any expression fails to evaluate
+/// on are reported on the `errors` port.
+#[block]
+pub async fn with_columns(
+    columns: Vec<(String, Expr)>,
+    mut inputs: Inputs<RecordBatch>,
+    outputs: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    while let Some(input) = inputs.recv().await? {
+        let output = columns
+            .iter()
+            .try_fold(input, |batch, (name, expr)| with_expr(&batch, name, expr));
+
+        match output {
+            Ok(output) if !outputs.is_closed() => outputs.send(output).await?,
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("with_columns", error))
+                    .await?
+            },
+            _ => continue,
+        }
+    }
+
+    Ok(())
+}
+
+fn with_expr(
+    batch: &RecordBatch,
+    name: &str,
+    expr: &Expr,
+) -> core::result::Result<RecordBatch, ArrowError> {
+    let array = expr.evaluate(batch)?;
+    let field = Arc::new(Field::new(name, array.data_type().clone(), true));
+
+    let schema = batch.schema_ref();
+    let mut fields = schema.fields().to_vec();
+    let mut arrays = batch.columns().to_vec();
+    match schema.index_of(name) {
+        Ok(index) => {
+            fields[index] = field;
+            arrays[index] = array;
+        },
+        Err(_) => {
+            fields.push(field);
+            arrays.push(array);
+        },
+    }
+
+    let schema = Schema::new_with_metadata(fields, schema.metadata().clone());
+    let options = RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
+    RecordBatch::try_new_with_options(Arc::new(schema), arrays, &options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use arrow_array::{cast::AsArray, record_batch, types::Int64Type};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_with_columns() -> Result<(), Box<dyn Error>> {
+        let (mut in_, mut out) = (Channel::bounded(10), Channel::bounded(10));
+        let mut err = Channel::bounded(10);
+        let columns = vec![
+            (
+                "a".into(),
+                Expr::column("a").cast(arrow_schema::DataType::Int64),
+            ),
+            ("c".into(), Expr::column("a") * Expr::column("b")),
+            ("s".into(), Expr::column("s").upper()),
+        ];
+        let block = tokio::spawn(with_columns(columns, in_.rx, out.tx, err.tx));
+
+        in_.tx
+            .send(record_batch!(
+                ("a", Int32, [1, 2]),
+                ("s", Utf8, ["x", "y"]),
+                ("b", Int64, [3, 4])
+            )?)
+            .await?;
+        in_.tx.send(record_batch!(("a", Int32, [1]))?).await?; // lacks `b`
+        in_.tx.close();
+
+        let _ = tokio::join!(block);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs.len(), 1);
+        let schema = outputs[0].schema();
+        let names: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(names, ["a", "s", "b", "c"]);
+        assert_eq!(
+            outputs[0].column(3).as_primitive::<Int64Type>().values(),
+            &[3, 8]
+        );
+        assert_eq!(outputs[0].column(1).as_string::<i32>().value(1), "Y");
+        assert_eq!(err.rx.recv_all().await?.len(), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::ParquetWriteOptions;
+use arrow_array::RecordBatch;
+use async_flow::{Inputs, Output, Outputs, Port, Result};
+use flows_derive::block;
+use flows_error::BlockError;
+use parquet::{arrow::AsyncArrowWriter, errors::ParquetError, file::metadata::ParquetMetaData};
+use tokio::io::AsyncWrite;
+
+/// A block that writes input batches to a Parquet file on an async writer,
+/// outputting the file's metadata once the input is closed.
+///
+/// The file's schema is taken from the first batch, and rows are buffered
+/// into row groups of the size given in the options. Batches with a different
+/// schema, as well as failures to write them, are reported on the `errors`
+/// port. Nothing is written if there are no input batches.
+#[block]
+pub async fn write_parquet<W>(
+    writer: W,
+    options: ParquetWriteOptions,
+    mut inputs: Inputs<RecordBatch>,
+    output: Output<ParquetMetaData>,
+    errors: Outputs<BlockError>,
+) -> Result
+where
+    W: AsyncWrite + Unpin + Send,
+{
+    let Some(mut batch) = inputs.recv().await? else {
+        return Ok(()); // no input
+    };
+
+    let mut writer =
+        match AsyncArrowWriter::try_new(writer, batch.schema(), Some(options.properties())) {
+            Ok(writer) => writer,
+            Err(error) => {
+                if errors.is_connected() {
+                    errors
+                        .send(BlockError::from_error("write_parquet", error))
+                        .await?;
+                }
+                return Ok(());
+            },
+        };
+
+    let schema = batch.schema();
+    loop {
+        // Checked up front, as a failed write leaves the writer in a broken state:
+        let result = match batch.schema() == schema {
+            true => writer.write(&batch).await,
+            false => Err(ParquetError::ArrowError(
+                "batch schema differs from the first batch".into(),
+            )),
+        };
+        if let Err(error) = result
+            && errors.is_connected()
+        {
+            errors
+                .send(BlockError::from_error("write_parquet", error))
+                .await?;
+        }
+        match inputs.recv().await? {
+            Some(next) => batch = next,
+            None => break,
+        }
+    }
+
+    match writer.close().await {
+        Ok(metadata) if !output.is_closed() => output.send(metadata).await?,
+        Err(error) if errors.is_connected() => {
+            errors
+                .send(BlockError::from_error("write_parquet", error))
+                .await?
+        },
+        _ => {},
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec, vec::Vec};
+    use arrow_array::record_batch;
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+    use parquet::basic::Compression;
+
+    #[tokio::test]
+    async fn test_write_parquet() -> Result<(), Box<dyn Error>> {
+        let (mut in_, mut out, mut err) = (
+            Channel::bounded(10),
+            Channel::oneshot(),
+            Channel::bounded(10),
+        );
+        let mut file: Vec<u8> = Vec::new();
+        let options = ParquetWriteOptions::default()
+            .with_compression(Compression::LZ4_RAW)
+            .with_max_row_group_size(2);
+        let writer = write_parquet(&mut file, options, in_.rx, out.tx, err.tx);
+
+        let batch = record_batch!(("a", Int32, [1, 2, 3]))?;
+        in_.tx.send(batch.clone()).await?;
+        in_.tx.send(record_batch!(("b", Utf8, ["x"]))?).await?; // mismatched schema
+        in_.tx.send(batch).await?;
+        in_.tx.close();
+        writer.await?;
+
+        let metadata = out.rx.recv_all().await?;
+        assert_eq!(metadata.len(), 1);
+        assert_eq!(metadata[0].file_metadata().num_rows(), 6);
+        assert_eq!(metadata[0].num_row_groups(), 3);
+        assert_eq!(
+            metadata[0].row_group(0).column(0).compression(),
+            Compression::LZ4_RAW
+        );
+        assert_eq!(err.rx.recv_all().await?.len(), 1);
+        assert_eq!(&file[..4], b"PAR1");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,29 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::ColumnRef;
+use alloc::format;
+use arrow_array::RecordBatch;
+use datafusion_common::{DataFusionError, Result};
+use datafusion_expr::Accumulator;
+
+/// Updates the given accumulator with the values in a given column of the
+/// given batch, failing for columns with a non-numeric datatype.
+pub(crate) fn accumulate_batch(
+    accumulator: &mut dyn Accumulator,
+    input: &RecordBatch,
+    column: &ColumnRef,
+) -> Result<()> {
+    if input.num_rows() == 0 {
+        return Ok(()); // skip empty batches
+    }
+
+    let column_array = column.resolve(input)?;
+    if !column_array.data_type().is_numeric() {
+        return Err(DataFusionError::Execution(format!(
+            "expected a numeric column, got {}",
+            column_array.data_type()
+        )));
+    }
+
+    accumulator.update_batch(core::slice::from_ref(column_array))
+}
@@ -0,0 +1,65 @@
+// This is free and unencumbered software released into the public domain.
+
+use alloc::{format, string::String, sync::Arc};
+use datafusion_expr::AggregateUDF;
+use datafusion_functions_aggregate::{average, count, min_max, sum};
+
+/// An aggregate function applied to a column, referenced by name, as
+/// computed by the `group_by` block.
+///
+/// Any DataFusion aggregate function can be used, with the input column
+/// coerced to a datatype that the function accepts.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Aggregate {
+    pub function: Arc<AggregateUDF>,
+    pub column: String,
+    pub alias: Option<String>,
+}
+
+impl Aggregate {
+    pub fn new(function: Arc<AggregateUDF>, column: impl Into<String>) -> Self {
+        Self {
+            function,
+            column: column.into(),
+            alias: None,
+        }
+    }
+
+    /// Counts the non-null values of the given column.
+    pub fn count(column: impl Into<String>) -> Self {
+        Self::new(count::count_udaf(), column)
+    }
+
+    pub fn sum(column: impl Into<String>) -> Self {
+        Self::new(sum::sum_udaf(), column)
+    }
+
+    pub fn min(column: impl Into<String>) -> Self {
+        Self::new(min_max::min_udaf(), column)
+    }
+
+    pub fn max(column: impl Into<String>) -> Self {
+        Self::new(min_max::max_udaf(), column)
+    }
+
+    pub fn avg(column: impl Into<String>) -> Self {
+        Self::new(average::avg_udaf(), column)
+    }
+
+    /// Names the output column with the given alias.
+    pub fn with_alias(self, alias: impl Into<String>) -> Self {
+        Self {
+            alias: Some(alias.into()),
+            ..self
+        }
+    }
+
+    /// Returns the name of the output column, which defaults to the form
+    /// `sum(column)`.
+    pub fn output_name(&self) -> String {
+        match &self.alias {
+            Some(alias) => alias.clone(),
+            None => format!("{}({})", self.function.name(), self.column),
+        }
+    }
+}
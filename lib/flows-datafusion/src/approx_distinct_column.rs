@@ -0,0 +1,268 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{
+    ColumnRef, DistinctSketch, Running,
+    distinct_sketch::check_precision,
+    running::{Event, Ticker},
+};
+use arrow_array::RecordBatch;
+use async_flow::{Inputs, Output, Outputs, Port, Result};
+use datafusion_common::ScalarValue;
+use flows_derive::block;
+use flows_error::BlockError;
+
+/// A block that outputs an estimate of the number of distinct non-null values
+/// in a given column, as an `Int64`.
+///
+/// The values are summarized in a [`DistinctSketch`], so memory use stays
+/// bounded regardless of the number of distinct values.
+/// Batches that lack the specified column are reported on the `errors` port.
+/// Running estimates are output on the `running` port as specified by `every`.
+#[block]
+pub async fn approx_distinct_column(
+    column: ColumnRef,
+    every: Running,
+    mut inputs: Inputs<RecordBatch>,
+    output: Output<ScalarValue>,
+    running: Outputs<ScalarValue>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    let mut sketch = DistinctSketch::new();
+    let mut ticker = Ticker::start(every, "approx_distinct_column", &errors).await?;
+
+    while let Some(event) = ticker.recv(&mut inputs).await? {
+        let input = match event {
+            Event::Input(input) => input,
+            Event::Tick => {
+                if running.is_connected() {
+                    running.send(distinct_result(&sketch)).await?;
+                }
+                continue;
+            },
+        };
+
+        if let Err(error) = sketch_batch(&mut sketch, &input, &column)
+            && errors.is_connected()
+        {
+            errors
+                .send(BlockError::from_error("approx_distinct_column", error))
+                .await?;
+        }
+    }
+
+    if !output.is_closed() {
+        output.send(distinct_result(&sketch)).await?;
+    }
+
+    Ok(())
+}
+
+/// A block that outputs a [`DistinctSketch`] of the values in a given column
+/// of the given precision, e.g., as the partial result of a partition of a
+/// stream to merge with `merge_distinct_sketches`.
+///
+/// Batches that lack the specified column are reported on the `errors` port.
+/// A precision outside of the valid range is reported there as well, after
+/// which the block exits.
+#[block]
+pub async fn sketch_distinct_column(
+    column: ColumnRef,
+    precision: u8,
+    mut inputs: Inputs<RecordBatch>,
+    output: Output<DistinctSketch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    if let Err(error) = check_precision(precision) {
+        if errors.is_connected() {
+            errors
+                .send(BlockError::from_error("sketch_distinct_column", error))
+                .await?;
+        }
+        return Ok(());
+    }
+
+    let mut sketch = DistinctSketch::with_precision(precision);
+
+    while let Some(input) = inputs.recv().await? {
+        if let Err(error) = sketch_batch(&mut sketch, &input, &column)
+            && errors.is_connected()
+        {
+            errors
+                .send(BlockError::from_error("sketch_distinct_column", error))
+                .await?;
+        }
+    }
+
+    if !output.is_closed() {
+        output.send(sketch).await?;
+    }
+
+    Ok(())
+}
+
+/// A block that merges all input sketches into one, which is output once the
+/// input closes.
+///
+/// Sketches of a different precision than the first one are reported on the
+/// `errors` port. Outputs an empty sketch of the default precision in case
+/// there were no input sketches.
+#[block]
+pub async fn merge_distinct_sketches(
+    mut inputs: Inputs<DistinctSketch>,
+    output: Output<DistinctSketch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    let mut result: Option<DistinctSketch> = None;
+
+    while let Some(input) = inputs.recv().await? {
+        let Some(result) = &mut result else {
+            result = Some(input);
+            continue;
+        };
+        if let Err(error) = result.merge(&input)
+            && errors.is_connected()
+        {
+            errors
+                .send(BlockError::from_error("merge_distinct_sketches", error))
+                .await?;
+        }
+    }
+
+    if !output.is_closed() {
+        output.send(result.unwrap_or_default()).await?;
+    }
+
+    Ok(())
+}
+
+fn sketch_batch(
+    sketch: &mut DistinctSketch,
+    input: &RecordBatch,
+    column: &ColumnRef,
+) -> datafusion_common::Result<()> {
+    if input.num_rows() == 0 {
+        return Ok(()); // skip empty batches
+    }
+
+    let column_array = column.resolve(input)?;
+    sketch.update(column_array)
+}
+
+fn distinct_result(sketch: &DistinctSketch) -> ScalarValue {
+    ScalarValue::Int64(Some(sketch.count() as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use arrow_array::record_batch;
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_approx_distinct_column() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::oneshot();
+        let mut err = Channel::bounded(10);
+        let counter = tokio::spawn(approx_distinct_column(
+            "c".into(),
+            Running::Never,
+            in_.rx,
+            out.tx,
+            Channel::bounded(1).tx,
+            err.tx,
+        ));
+
+        in_.tx.send(sample_data()).await?;
+        in_.tx.send(sample_data().slice(1, 2)).await?;
+        in_.tx.send(sample_data().project(&[0])?).await?; // missing column
+        in_.tx.close();
+
+        let _ = tokio::join!(counter);
+
+        assert_eq!(out.rx.recv_all().await?, vec![ScalarValue::Int64(Some(4))]);
+        assert_eq!(err.rx.recv_all().await?.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sketch_distinct_column_invalid_precision() -> Result<(), Box<dyn Error>> {
+        let in_ = Channel::bounded(10);
+        let (mut out, mut err) = (Channel::oneshot(), Channel::bounded(10));
+        let sketcher = tokio::spawn(sketch_distinct_column(0.into(), 40, in_.rx, out.tx, err.tx));
+
+        // Reported without waiting for the input to close:
+        let _ = tokio::join!(sketcher);
+
+        assert!(out.rx.recv_all().await?.is_empty());
+        assert_eq!(err.rx.recv_all().await?.len(), 1);
+        drop(in_.tx);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_merge_distinct_sketches() -> Result<(), Box<dyn Error>> {
+        let (mut lhs_in, mut rhs_in) = (Channel::bounded(10), Channel::bounded(10));
+        let (mut lhs_out, mut rhs_out) = (Channel::oneshot(), Channel::oneshot());
+        let (mut merged_in, mut merged_out) = (Channel::bounded(10), Channel::oneshot());
+        let mut err = Channel::bounded(10);
+        let lhs = tokio::spawn(sketch_distinct_column(
+            0.into(),
+            12,
+            lhs_in.rx,
+            lhs_out.tx,
+            Channel::bounded(1).tx,
+        ));
+        let rhs = tokio::spawn(sketch_distinct_column(
+            0.into(),
+            12,
+            rhs_in.rx,
+            rhs_out.tx,
+            Channel::bounded(1).tx,
+        ));
+        let merger = tokio::spawn(merge_distinct_sketches(merged_in.rx, merged_out.tx, err.tx));
+
+        lhs_in.tx.send(sample_data()).await?; // 1..=5
+        lhs_in.tx.close();
+        rhs_in
+            .tx
+            .send(record_batch!(("a", Int32, [4, 5, 6, 7]))?)
+            .await?;
+        rhs_in.tx.close();
+        let _ = tokio::join!(lhs, rhs);
+
+        let mut sketches = vec![];
+        sketches.extend(lhs_out.rx.recv_all().await?);
+        sketches.extend(rhs_out.rx.recv_all().await?);
+        for sketch in sketches {
+            merged_in.tx.send(sketch).await?;
+        }
+        merged_in
+            .tx
+            .send(DistinctSketch::with_precision(10))
+            .await?; // mismatched
+        merged_in.tx.close();
+
+        let _ = tokio::join!(merger);
+
+        let merged = merged_out.rx.recv_all().await?;
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].precision(), 12);
+        assert_eq!(merged[0].count(), 7);
+        assert_eq!(err.rx.recv_all().await?.len(), 1);
+
+        Ok(())
+    }
+
+    fn sample_data() -> RecordBatch {
+        record_batch!(
+            ("a", Int32, [1, 2, 3, 4, 5]),
+            ("b", Float64, [Some(4.0), None, Some(5.0), None, None]),
+            ("c", Utf8, ["alpha", "beta", "gamma", "", ""])
+        )
+        .unwrap()
+    }
+}
@@ -1,69 +1,157 @@
 // This is free and unencumbered software released into the public domain.
 
-use super::sum_array;
+use super::{
+    ColumnRef, Running,
+    running::{Event, Ticker},
+    sum_array,
+};
+use alloc::vec::Vec;
 use arrow_array::RecordBatch;
-use async_flow::{Inputs, Output, Port, Result};
+use arrow_schema::DataType;
+use async_flow::{Inputs, Output, Outputs, Port, Result};
 use datafusion_common::ScalarValue;
 use flows_derive::block;
+use flows_error::BlockError;
 
 /// A block that outputs the average of all values in a given column.
 ///
-/// Panics in case the specified column index is out of bounds.
-/// Outputs `ScalarValue::Null` in case the specified column has a non-numeric
-/// datatype.
+/// The average is the sum of all non-null values divided by their count, so
+/// each value is weighted equally regardless of the batch it arrived in.
+/// Batches that lack the specified column, or whose column has a non-numeric
+/// datatype, are reported on the `errors` port.
+/// Outputs `ScalarValue::Null` in case the column has no non-null values.
+/// Running averages are output on the `running` port as specified by `every`.
 #[block]
 pub async fn avg_column(
-    column: usize,
+    column: ColumnRef,
+    every: Running,
     mut inputs: Inputs<RecordBatch>,
     output: Output<ScalarValue>,
+    running: Outputs<ScalarValue>,
+    errors: Outputs<BlockError>,
 ) -> Result {
-    let mut tally: ScalarValue = ScalarValue::Null;
-    let mut count: usize = 0;
+    let mut state = (ScalarValue::Null, 0);
+    let mut ticker = Ticker::start(every, "avg_column", &errors).await?;
 
-    while let Some(input) = inputs.recv().await? {
-        if input.num_rows() == 0 {
-            continue; // skip empty batches
+    while let Some(event) = ticker.recv(&mut inputs).await? {
+        let input = match event {
+            Event::Input(input) => input,
+            Event::Tick => {
+                if running.is_connected()
+                    && let Ok(value) = avg_result(state.clone())
+                {
+                    running.send(value).await?;
+                }
+                continue;
+            },
+        };
+
+        if let Err(error) = avg_batch(&mut state, &input, &column)
+            && errors.is_connected()
+        {
+            errors
+                .send(BlockError::from_error("avg_column", error))
+                .await?;
         }
+    }
+
+    match avg_result(state) {
+        Ok(result) if !output.is_closed() => output.send(result).await?,
+        Err(error) if errors.is_connected() => {
+            errors
+                .send(BlockError::from_error("avg_column", error))
+                .await?
+        },
+        _ => {},
+    }
+
+    Ok(())
+}
+
+/// A block that outputs the average of all values in a given column for each
+/// input window of batches.
+///
+/// Batches that lack the specified column, or whose column has a non-numeric
+/// datatype, are reported on the `errors` port.
+/// Outputs `ScalarValue::Null` in case the column has no non-null values.
+#[block]
+pub async fn avg_column_windows(
+    column: ColumnRef,
+    mut inputs: Inputs<Vec<RecordBatch>>,
+    outputs: Outputs<ScalarValue>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    while let Some(window) = inputs.recv().await? {
+        let mut state = (ScalarValue::Null, 0);
 
-        let column_array = input.column(column);
-        let column_len = column_array.len() - column_array.null_count();
-        if column_len == 0 {
-            continue; // skip null-only batches
+        for input in &window {
+            if let Err(error) = avg_batch(&mut state, input, &column)
+                && errors.is_connected()
+            {
+                errors
+                    .send(BlockError::from_error("avg_column_windows", error))
+                    .await?;
+            }
         }
 
-        let Some(column_sum) = sum_array(column_array) else {
-            continue; // skip unsupported datatypes
-        };
+        match avg_result(state) {
+            Ok(result) if !outputs.is_closed() => outputs.send(result).await?,
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("avg_column_windows", error))
+                    .await?
+            },
+            _ => continue,
+        }
+    }
 
-        let column_avg = avg(column_sum, column_len).unwrap();
+    Ok(())
+}
 
-        tally = if tally.is_null() {
-            column_avg
-        } else {
-            tally.add(column_avg).unwrap()
-        };
-        count += 1;
+fn avg_batch(
+    (sum, count): &mut (ScalarValue, usize),
+    input: &RecordBatch,
+    column: &ColumnRef,
+) -> datafusion_common::Result<()> {
+    if input.num_rows() == 0 {
+        return Ok(()); // skip empty batches
     }
 
-    let result = if count == 0 {
-        ScalarValue::Null
-    } else {
-        avg(tally, count).unwrap()
+    // Summed as floating point, as the sum may well overflow the column's
+    // datatype even though the average can't:
+    let column_array = column.resolve(input)?;
+    let column_sum = match column_array.data_type().is_numeric() {
+        true => sum_array(&arrow_cast::cast(column_array, &DataType::Float64)?)?,
+        false => sum_array(column_array)?, // reports the datatype
     };
 
-    if !output.is_closed() {
-        output.send(result).await?;
+    let column_len = column_array.len() - column_array.null_count();
+    if column_len == 0 {
+        return Ok(()); // skip null-only batches
     }
 
+    *sum = if sum.is_null() {
+        column_sum
+    } else {
+        sum.add(column_sum)?
+    };
+    *count += column_len;
     Ok(())
 }
 
-pub fn avg(sum: ScalarValue, len: usize) -> Option<ScalarValue> {
+fn avg_result((sum, count): (ScalarValue, usize)) -> datafusion_common::Result<ScalarValue> {
+    if count == 0 {
+        Ok(ScalarValue::Null)
+    } else {
+        avg(sum, count)
+    }
+}
+
+pub fn avg(sum: ScalarValue, len: usize) -> datafusion_common::Result<ScalarValue> {
     assert!(len > 0);
-    use arrow_schema::DataType::*;
-    let sum = sum.cast_to(&Float64).unwrap();
+    let sum = sum.cast_to(&DataType::Float64)?;
     let len = ScalarValue::Float64(Some(len as f64));
-    sum.div(len).ok()
+    sum.div(len)
 }
 
 #[cfg(test)]
@@ -78,7 +166,15 @@ mod tests {
     async fn test_avg_column_i32() -> Result<(), Box<dyn Error>> {
         let mut in_ = Channel::bounded(10);
         let mut out = Channel::oneshot();
-        let averager = tokio::spawn(avg_column(0, in_.rx, out.tx));
+        let mut err = Channel::bounded(10);
+        let averager = tokio::spawn(avg_column(
+            0.into(),
+            Running::Never,
+            in_.rx,
+            out.tx,
+            Channel::bounded(1).tx,
+            err.tx,
+        ));
 
         in_.tx.send(sample_data()).await?;
         in_.tx.send(sample_data()).await?;
@@ -90,6 +186,124 @@ mod tests {
         assert_eq!(outputs.len(), 1);
         assert_eq!(outputs[0], ScalarValue::from(3.0));
 
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_avg_column_weighted() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::oneshot();
+        let mut err = Channel::bounded(10);
+        let averager = tokio::spawn(avg_column(
+            1.into(),
+            Running::Never,
+            in_.rx,
+            out.tx,
+            Channel::bounded(1).tx,
+            err.tx,
+        ));
+
+        in_.tx.send(sample_data()).await?; // 4.0 and 5.0
+        in_.tx.send(sample_data().slice(0, 1)).await?; // 4.0
+        in_.tx.close();
+
+        let _ = tokio::join!(averager);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs, vec![ScalarValue::from(13.0 / 3.0)]);
+
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_avg_column_overflow() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::oneshot();
+        let mut err = Channel::bounded(10);
+        let averager = tokio::spawn(avg_column(
+            0.into(),
+            Running::Never,
+            in_.rx,
+            out.tx,
+            Channel::bounded(1).tx,
+            err.tx,
+        ));
+
+        in_.tx
+            .send(record_batch!(("a", Int8, [100, 100, 100]))?)
+            .await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(averager);
+
+        assert_eq!(out.rx.recv_all().await?, vec![ScalarValue::from(100.0)]);
+
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_avg_column_running_interval() -> Result<(), Box<dyn Error>> {
+        use core::time::Duration;
+        use tokio::time::sleep;
+
+        let mut in_ = Channel::bounded(10);
+        let (mut out, mut run) = (Channel::oneshot(), Channel::bounded(10));
+        let mut err = Channel::bounded(10);
+        let averager = tokio::spawn(avg_column(
+            0.into(),
+            Running::Interval(Duration::from_secs(10)),
+            in_.rx,
+            out.tx,
+            run.tx,
+            err.tx,
+        ));
+
+        in_.tx.send(sample_data()).await?; // 1..=5
+        sleep(Duration::from_secs(15)).await;
+        sleep(Duration::from_secs(10)).await; // idle, so no running output
+        in_.tx.send(sample_data().slice(4, 1)).await?; // 5
+        in_.tx.close();
+
+        let _ = tokio::join!(averager);
+
+        assert_eq!(run.rx.recv_all().await?, vec![ScalarValue::from(3.0)]);
+        assert_eq!(
+            out.rx.recv_all().await?,
+            vec![ScalarValue::from(20.0 / 6.0)]
+        );
+
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_avg_column_windows() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::bounded(10);
+        let mut err = Channel::bounded(10);
+        let averager = tokio::spawn(avg_column_windows(0.into(), in_.rx, out.tx, err.tx));
+
+        in_.tx.send(vec![sample_data(), sample_data()]).await?;
+        in_.tx.send(vec![sample_data()]).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(averager);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(
+            outputs,
+            vec![ScalarValue::from(3.0), ScalarValue::from(3.0)]
+        );
+
+        assert!(err.rx.recv_all().await?.is_empty());
+
         Ok(())
     }
 
@@ -0,0 +1,99 @@
+// This is free and unencumbered software released into the public domain.
+
+use alloc::{format, string::String};
+use arrow_array::{ArrayRef, RecordBatch};
+use core::fmt;
+use datafusion_common::{DataFusionError, Result};
+
+/// A reference to a column of record batches, by name or by index, which is
+/// resolved against the schema of each batch.
+///
+/// Serializes as just the column's name or index, e.g., `"amount"` or `2`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+pub enum ColumnRef {
+    /// The column of the given name.
+    Name(String),
+
+    /// The column at the given (zero-based) index.
+    Index(usize),
+}
+
+impl ColumnRef {
+    /// Returns the referenced column of the given batch, or an error in case
+    /// the batch has no such column.
+    pub fn resolve<'a>(&self, batch: &'a RecordBatch) -> Result<&'a ArrayRef> {
+        match self {
+            Self::Name(name) => match batch.column_by_name(name) {
+                Some(column) => Ok(column),
+                None => Err(DataFusionError::Execution(format!(
+                    "column `{name}` not found"
+                ))),
+            },
+            Self::Index(index) => match batch.columns().get(*index) {
+                Some(column) => Ok(column),
+                None => Err(DataFusionError::Execution(format!(
+                    "column index {index} is out of bounds"
+                ))),
+            },
+        }
+    }
+}
+
+impl fmt::Display for ColumnRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name(name) => write!(f, "{name}"),
+            Self::Index(index) => write!(f, "#{index}"),
+        }
+    }
+}
+
+impl From<&str> for ColumnRef {
+    fn from(name: &str) -> Self {
+        Self::Name(name.into())
+    }
+}
+
+impl From<String> for ColumnRef {
+    fn from(name: String) -> Self {
+        Self::Name(name)
+    }
+}
+
+impl From<usize> for ColumnRef {
+    fn from(index: usize) -> Self {
+        Self::Index(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use arrow_array::record_batch;
+    use core::error::Error;
+
+    #[test]
+    fn test_column_ref_resolve() -> Result<(), Box<dyn Error>> {
+        let batch = record_batch!(("a", Int32, [1, 2]), ("b", Utf8, ["x", "y"]))?;
+        assert_eq!(ColumnRef::from("b").resolve(&batch)?, batch.column(1));
+        assert_eq!(ColumnRef::from(0).resolve(&batch)?, batch.column(0));
+        assert!(ColumnRef::from("c").resolve(&batch).is_err());
+        assert!(ColumnRef::from(2).resolve(&batch).is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_column_ref_serde() -> Result<(), Box<dyn Error>> {
+        use alloc::vec::Vec;
+
+        let columns = vec![ColumnRef::from("amount"), ColumnRef::from(2)];
+        let json = serde_json::to_string(&columns)?;
+        assert_eq!(json, r#"["amount",2]"#);
+        assert_eq!(serde_json::from_str::<Vec<ColumnRef>>(&json)?, columns);
+        Ok(())
+    }
+}
@@ -0,0 +1,237 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{
+    ColumnRef, Running,
+    running::{Event, Ticker},
+};
+use alloc::{boxed::Box, format, vec::Vec};
+use arrow_array::{Array, ArrayRef, RecordBatch};
+use arrow_cast::CastOptions;
+use arrow_row::{RowConverter, SortField};
+use arrow_schema::DataType;
+use async_flow::{Inputs, Output, Outputs, Port, Result};
+use datafusion_common::{DataFusionError, ScalarValue};
+use flows_derive::block;
+use flows_error::BlockError;
+use hashbrown::HashSet;
+
+/// A block that outputs the number of distinct non-null values in a given
+/// column, as an `Int64`.
+///
+/// The count is exact, so every distinct value is retained until the end of
+/// the stream. The first batch determines the column's datatype, and the
+/// column of later batches is cast to it.
+///
+/// Batches that lack the specified column, or whose column can't be cast
+/// without changing any value (e.g., fractions to an integer datatype), are
+/// reported on the `errors` port.
+/// Running counts are output on the `running` port as specified by `every`.
+#[block]
+pub async fn count_distinct_column(
+    column: ColumnRef,
+    every: Running,
+    mut inputs: Inputs<RecordBatch>,
+    output: Output<ScalarValue>,
+    running: Outputs<ScalarValue>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    let mut distinct = Distinct::default();
+    let mut ticker = Ticker::start(every, "count_distinct_column", &errors).await?;
+
+    while let Some(event) = ticker.recv(&mut inputs).await? {
+        let input = match event {
+            Event::Input(input) => input,
+            Event::Tick => {
+                if running.is_connected() {
+                    running.send(distinct.count()).await?;
+                }
+                continue;
+            },
+        };
+
+        if let Err(error) = distinct.insert(&input, &column)
+            && errors.is_connected()
+        {
+            errors
+                .send(BlockError::from_error("count_distinct_column", error))
+                .await?;
+        }
+    }
+
+    if !output.is_closed() {
+        output.send(distinct.count()).await?;
+    }
+
+    Ok(())
+}
+
+/// A block that outputs the number of distinct non-null values in a given
+/// column for each input window of batches, as an `Int64`.
+///
+/// Batches that lack the specified column, or whose column can't be cast
+/// without changing any value, are reported on the `errors` port.
+#[block]
+pub async fn count_distinct_column_windows(
+    column: ColumnRef,
+    mut inputs: Inputs<Vec<RecordBatch>>,
+    outputs: Outputs<ScalarValue>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    while let Some(window) = inputs.recv().await? {
+        let mut distinct = Distinct::default();
+
+        for input in &window {
+            if let Err(error) = distinct.insert(input, &column)
+                && errors.is_connected()
+            {
+                errors
+                    .send(BlockError::from_error(
+                        "count_distinct_column_windows",
+                        error,
+                    ))
+                    .await?;
+            }
+        }
+
+        if !outputs.is_closed() {
+            outputs.send(distinct.count()).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The distinct values of a column, in their row format.
+#[derive(Default)]
+struct Distinct {
+    converter: Option<(RowConverter, DataType)>,
+    values: HashSet<Box<[u8]>>,
+}
+
+impl Distinct {
+    fn insert(&mut self, input: &RecordBatch, column: &ColumnRef) -> datafusion_common::Result<()> {
+        if input.num_rows() == 0 {
+            return Ok(()); // skip empty batches
+        }
+
+        let column_array = column.resolve(input)?;
+        let (converter, data_type) = match &mut self.converter {
+            Some(converter) => converter,
+            None => {
+                let data_type = column_array.data_type().clone();
+                let converter = RowConverter::new(alloc::vec![SortField::new(data_type.clone())])?;
+                self.converter.insert((converter, data_type))
+            },
+        };
+        let column_array = match column_array.data_type() == &*data_type {
+            true => column_array.clone(),
+            false => cast_exactly(column_array, data_type)?,
+        };
+
+        let rows = converter.convert_columns(core::slice::from_ref(&column_array))?;
+        for (index, row) in rows.iter().enumerate() {
+            if column_array.is_valid(index) && !self.values.contains(row.as_ref()) {
+                self.values.insert(row.as_ref().into());
+            }
+        }
+        Ok(())
+    }
+
+    fn count(&self) -> ScalarValue {
+        ScalarValue::Int64(Some(self.values.len() as i64))
+    }
+}
+
+/// Casts the given array to the given datatype, with values that don't
+/// convert, or that numerically change in conversion, being an error, lest
+/// distinct values be counted as one.
+fn cast_exactly(array: &ArrayRef, data_type: &DataType) -> datafusion_common::Result<ArrayRef> {
+    let options = CastOptions {
+        safe: false,
+        ..Default::default()
+    };
+    let converted = arrow_cast::cast_with_options(array, data_type, &options)?;
+    if array.data_type().is_numeric() && data_type.is_numeric() {
+        let restored = arrow_cast::cast_with_options(&converted, array.data_type(), &options)?;
+        if restored.as_ref() != array.as_ref() {
+            return Err(DataFusionError::Execution(format!(
+                "values of type {} don't convert exactly to {data_type}",
+                array.data_type()
+            )));
+        }
+    }
+    Ok(converted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use arrow_array::record_batch;
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_count_distinct_column() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::oneshot();
+        let mut err = Channel::bounded(10);
+        let counter = tokio::spawn(count_distinct_column(
+            0.into(),
+            Running::Never,
+            in_.rx,
+            out.tx,
+            Channel::bounded(1).tx,
+            err.tx,
+        ));
+
+        in_.tx
+            .send(record_batch!((
+                "a",
+                Utf8,
+                [Some("x"), Some("y"), None, Some("x")]
+            ))?)
+            .await?;
+        in_.tx
+            .send(record_batch!(("a", LargeUtf8, ["y", "z"]))?)
+            .await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(counter);
+
+        assert_eq!(out.rx.recv_all().await?, vec![ScalarValue::from(3i64)]);
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_count_distinct_column_inexact() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::oneshot();
+        let mut err = Channel::bounded(10);
+        let counter = tokio::spawn(count_distinct_column(
+            0.into(),
+            Running::Never,
+            in_.rx,
+            out.tx,
+            Channel::bounded(1).tx,
+            err.tx,
+        ));
+
+        in_.tx.send(record_batch!(("a", Int32, [1, 2]))?).await?;
+        in_.tx.send(record_batch!(("a", Int64, [2, 3]))?).await?;
+        in_.tx
+            .send(record_batch!(("a", Float64, [1.5, 1.7]))?)
+            .await?; // fractions
+        in_.tx.send(record_batch!(("a", Int64, [1 << 40]))?).await?; // out of range
+        in_.tx.close();
+
+        let _ = tokio::join!(counter);
+
+        assert_eq!(out.rx.recv_all().await?, vec![ScalarValue::from(3i64)]);
+        assert_eq!(err.rx.recv_all().await?.len(), 2);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,496 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{
+    Running, max_array, min_array,
+    running::{Event, Ticker},
+};
+use alloc::{
+    format,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use arrow_array::{
+    Array, ArrayRef, Float64Array, RecordBatch, StringArray, cast::AsArray, types::Float64Type,
+};
+use arrow_cast::CastOptions;
+use arrow_schema::{DataType, Field, Schema};
+use async_flow::{Inputs, Output, Outputs, Port, Result};
+use datafusion_common::{DataFusionError, ScalarValue};
+use datafusion_expr::Accumulator;
+use datafusion_functions_aggregate::variance::VarianceAccumulator;
+use datafusion_functions_aggregate_common::stats::StatsType;
+use flows_derive::block;
+use flows_error::BlockError;
+
+/// The statistics computed by the `describe_columns` block, in the order of
+/// the rows of its output batch.
+pub const DESCRIBE_STATISTICS: [&str; 7] =
+    ["count", "null_count", "min", "max", "sum", "mean", "stddev"];
+
+/// A block that outputs summary statistics of the values in the given
+/// columns, or in every column if none are given, akin to pandas'
+/// `describe()`.
+///
+/// All statistics are computed in a single pass, retaining only a few
+/// scalars per column across batches. The output batch has a `statistic`
+/// column naming each row (see `DESCRIBE_STATISTICS`), followed by a column
+/// for each described column. Numeric columns are described as `Float64`,
+/// with the sample standard deviation. Other columns are described as
+/// `Utf8`, with only their counts, minimum and maximum.
+///
+/// The first batch determines the described columns, if none are given, as
+/// well as their datatypes, and the columns of later batches are cast to
+/// them. Batches that lack a described column, or whose values don't convert
+/// to those datatypes, are reported on the `errors` port.
+/// Running statistics are output on the `running` port as specified by
+/// `every`, once the described columns are known.
+#[block]
+pub async fn describe_columns(
+    columns: Vec<String>,
+    every: Running,
+    mut inputs: Inputs<RecordBatch>,
+    output: Output<RecordBatch>,
+    running: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    let mut describer = Describer::new(&columns);
+    let mut ticker = Ticker::start(every, "describe_columns", &errors).await?;
+
+    while let Some(event) = ticker.recv(&mut inputs).await? {
+        let input = match event {
+            Event::Input(input) => input,
+            Event::Tick => {
+                if !running.is_connected() || !describer.is_started() {
+                    continue;
+                }
+                match describer.summarize() {
+                    Ok(batch) => running.send(batch).await?,
+                    Err(error) if errors.is_connected() => {
+                        errors
+                            .send(BlockError::from_error("describe_columns", error))
+                            .await?
+                    },
+                    Err(_) => {},
+                }
+                continue;
+            },
+        };
+
+        if let Err(error) = describer.update(&input)
+            && errors.is_connected()
+        {
+            errors
+                .send(BlockError::from_error("describe_columns", error))
+                .await?;
+        }
+    }
+
+    match describer.summarize() {
+        Ok(batch) if !output.is_closed() => output.send(batch).await?,
+        Err(error) if errors.is_connected() => {
+            errors
+                .send(BlockError::from_error("describe_columns", error))
+                .await?
+        },
+        _ => {},
+    }
+
+    Ok(())
+}
+
+/// A block that outputs summary statistics of the values in the given
+/// columns, or in every column if none are given, for each input window of
+/// batches.
+///
+/// See `describe_columns` for the layout of the output batches. Batches that
+/// lack a described column, or whose values don't convert, are reported on
+/// the `errors` port.
+#[block]
+pub async fn describe_columns_windows(
+    columns: Vec<String>,
+    mut inputs: Inputs<Vec<RecordBatch>>,
+    outputs: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    while let Some(window) = inputs.recv().await? {
+        let mut describer = Describer::new(&columns);
+
+        for input in &window {
+            if let Err(error) = describer.update(input)
+                && errors.is_connected()
+            {
+                errors
+                    .send(BlockError::from_error("describe_columns_windows", error))
+                    .await?;
+            }
+        }
+
+        match describer.summarize() {
+            Ok(batch) if !outputs.is_closed() => outputs.send(batch).await?,
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("describe_columns_windows", error))
+                    .await?
+            },
+            _ => continue,
+        }
+    }
+
+    Ok(())
+}
+
+/// The running statistics of the described columns.
+struct Describer {
+    columns: Option<Vec<ColumnSummary>>,
+    names: Vec<String>,
+}
+
+impl Describer {
+    fn new(names: &[String]) -> Self {
+        Self {
+            columns: None,
+            names: names.to_vec(),
+        }
+    }
+
+    /// Returns whether the described columns are known yet.
+    fn is_started(&self) -> bool {
+        self.columns.is_some()
+    }
+
+    fn update(&mut self, input: &RecordBatch) -> datafusion_common::Result<()> {
+        let columns = match &mut self.columns {
+            Some(columns) => columns,
+            None if self.names.is_empty() => self.columns.insert(
+                input
+                    .schema()
+                    .fields()
+                    .iter()
+                    .map(|field| ColumnSummary::new(field.name(), field.data_type()))
+                    .collect(),
+            ),
+            None => {
+                let schema = input.schema();
+                let mut columns = Vec::with_capacity(self.names.len());
+                for name in &self.names {
+                    let Ok(field) = schema.field_with_name(name) else {
+                        return Err(DataFusionError::Execution(format!(
+                            "column `{name}` not found"
+                        )));
+                    };
+                    columns.push(ColumnSummary::new(name, field.data_type()));
+                }
+                self.columns.insert(columns)
+            },
+        };
+
+        // Resolve all the described columns before updating any of them, so
+        // that a rejected batch leaves the statistics untouched:
+        let options = CastOptions {
+            safe: false,
+            ..Default::default()
+        };
+        let mut arrays = Vec::with_capacity(columns.len());
+        for column in columns.iter() {
+            let Some(array) = input.column_by_name(&column.name) else {
+                return Err(DataFusionError::Execution(format!(
+                    "column `{}` not found",
+                    column.name
+                )));
+            };
+            arrays.push(match array.data_type() == &column.data_type {
+                true => array.clone(),
+                false => arrow_cast::cast_with_options(array, &column.data_type, &options)?,
+            });
+        }
+
+        for (column, array) in columns.iter_mut().zip(&arrays) {
+            column.update(array)?;
+        }
+        Ok(())
+    }
+
+    fn summarize(&mut self) -> datafusion_common::Result<RecordBatch> {
+        let columns = self.columns.as_deref_mut().unwrap_or_default();
+
+        let mut fields = Vec::with_capacity(columns.len() + 1);
+        let mut arrays = Vec::with_capacity(columns.len() + 1);
+        fields.push(Field::new("statistic", DataType::Utf8, false));
+        arrays.push(Arc::new(StringArray::from(DESCRIBE_STATISTICS.to_vec())) as ArrayRef);
+        for column in columns {
+            let array = column.summarize()?;
+            fields.push(Field::new(&column.name, array.data_type().clone(), true));
+            arrays.push(array);
+        }
+
+        Ok(RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)?)
+    }
+}
+
+/// The running statistics of a single described column.
+struct ColumnSummary {
+    name: String,
+    data_type: DataType,
+    null_count: u64,
+    row_count: u64,
+    min: ScalarValue,
+    max: ScalarValue,
+    /// The sum of the values, accumulated as floating point so as not to
+    /// overflow the column's datatype.
+    sum: f64,
+    moments: VarianceAccumulator,
+}
+
+impl ColumnSummary {
+    fn new(name: &str, data_type: &DataType) -> Self {
+        Self {
+            name: name.into(),
+            data_type: data_type.clone(),
+            null_count: 0,
+            row_count: 0,
+            min: ScalarValue::Null,
+            max: ScalarValue::Null,
+            sum: 0.0,
+            moments: VarianceAccumulator::try_new(StatsType::Sample)
+                .expect("sample statistics are supported"),
+        }
+    }
+
+    fn is_numeric(&self) -> bool {
+        self.data_type.is_numeric()
+    }
+
+    fn count(&self) -> u64 {
+        self.row_count - self.null_count
+    }
+
+    fn update(&mut self, array: &ArrayRef) -> datafusion_common::Result<()> {
+        self.row_count += array.len() as u64;
+        self.null_count += array.logical_null_count() as u64;
+        if array.len() == array.logical_null_count() {
+            return Ok(()); // skip null-only arrays
+        }
+
+        // Columns of unordered datatypes (e.g., lists) have no extremes:
+        if let Ok(min) = min_array(array)
+            && !min.is_null()
+            && (self.min.is_null() || min < self.min)
+        {
+            self.min = min;
+        }
+        if let Ok(max) = max_array(array)
+            && !max.is_null()
+            && (self.max.is_null() || max > self.max)
+        {
+            self.max = max;
+        }
+
+        if self.is_numeric() {
+            let values = arrow_cast::cast(array, &DataType::Float64)?;
+            self.sum +=
+                arrow_arith::aggregate::sum(values.as_primitive::<Float64Type>()).unwrap_or(0.0);
+            self.moments.update_batch(core::slice::from_ref(array))?;
+        }
+        Ok(())
+    }
+
+    fn summarize(&mut self) -> datafusion_common::Result<ArrayRef> {
+        let (count, null_count) = (self.count(), self.null_count);
+
+        if !self.is_numeric() {
+            let display = |value: &ScalarValue| (!value.is_null()).then(|| value.to_string());
+            return Ok(Arc::new(StringArray::from(alloc::vec![
+                Some(count.to_string()),
+                Some(null_count.to_string()),
+                display(&self.min),
+                display(&self.max),
+                None,
+                None,
+                None,
+            ])));
+        }
+
+        let float = |value: &ScalarValue| -> datafusion_common::Result<Option<f64>> {
+            match value.cast_to(&DataType::Float64)? {
+                ScalarValue::Float64(value) => Ok(value),
+                _ => Ok(None),
+            }
+        };
+        let mean = (count > 0).then(|| self.moments.get_mean());
+        let stddev = match self.moments.evaluate()? {
+            ScalarValue::Float64(Some(variance)) => Some(libm::sqrt(variance)),
+            _ => None,
+        };
+        Ok(Arc::new(Float64Array::from(alloc::vec![
+            Some(count as f64),
+            Some(null_count as f64),
+            float(&self.min)?,
+            float(&self.max)?,
+            (count > 0).then_some(self.sum),
+            mean,
+            stddev,
+        ])))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use arrow_array::record_batch;
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_describe_columns() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::oneshot();
+        let mut err = Channel::bounded(10);
+        let block = tokio::spawn(describe_columns(
+            vec![],
+            Running::Never,
+            in_.rx,
+            out.tx,
+            Channel::bounded(1).tx,
+            err.tx,
+        ));
+
+        in_.tx.send(sample_data().slice(0, 2)).await?;
+        in_.tx.send(sample_data().slice(2, 3)).await?;
+        in_.tx.send(sample_data().project(&[0])?).await?; // missing columns
+        in_.tx
+            .send(record_batch!(
+                ("a", Int64, [1 << 40]),
+                ("b", Float64, [1.0]),
+                ("c", Utf8, ["delta"])
+            )?)
+            .await?; // overflows `a`
+        in_.tx.close();
+
+        let _ = tokio::join!(block);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs.len(), 1);
+        let summary = &outputs[0];
+        assert_eq!(summary.num_columns(), 4);
+        assert_eq!(summary.num_rows(), DESCRIBE_STATISTICS.len());
+
+        let a = summary.column(1).as_primitive::<Float64Type>();
+        assert_eq!(&a.values()[..6], &[5.0, 0.0, 1.0, 5.0, 15.0, 3.0]);
+        assert!((a.value(6) - 2.5f64.sqrt()).abs() < 1e-9);
+
+        let b = summary.column(2).as_primitive::<Float64Type>();
+        assert_eq!(&b.values()[..6], &[2.0, 3.0, 4.0, 5.0, 9.0, 4.5]);
+
+        let c = summary.column(3).as_string::<i32>();
+        assert_eq!(
+            c.iter().collect::<Vec<_>>(),
+            [Some("5"), Some("0"), Some(""), Some("gamma"), None, None, None]
+        );
+
+        assert_eq!(err.rx.recv_all().await?.len(), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_describe_columns_running() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let (mut out, mut run) = (Channel::oneshot(), Channel::bounded(10));
+        let mut err = Channel::bounded(10);
+        let columns = vec!["a".into()];
+        let block = tokio::spawn(describe_columns(
+            columns,
+            Running::Batches(1),
+            in_.rx,
+            out.tx,
+            run.tx,
+            err.tx,
+        ));
+
+        in_.tx.send(sample_data().slice(0, 2)).await?;
+        in_.tx.send(sample_data().slice(2, 3)).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(block);
+
+        let running = run.rx.recv_all().await?;
+        let counts: Vec<f64> = running
+            .iter()
+            .map(|batch| batch.column(1).as_primitive::<Float64Type>().value(0))
+            .collect();
+        assert_eq!(counts, [2.0, 5.0]);
+        assert_eq!(out.rx.recv().await?, Some(running[1].clone()));
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_describe_columns_windows() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::bounded(10);
+        let mut err = Channel::bounded(10);
+        let columns = vec!["a".into()];
+        let block = tokio::spawn(describe_columns_windows(columns, in_.rx, out.tx, err.tx));
+
+        in_.tx.send(vec![sample_data().slice(0, 1)]).await?;
+        in_.tx.send(vec![sample_data(), sample_data()]).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(block);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs.len(), 2);
+        let counts: Vec<f64> = outputs
+            .iter()
+            .map(|batch| batch.column(1).as_primitive::<Float64Type>().value(0))
+            .collect();
+        assert_eq!(counts, [1.0, 10.0]);
+        assert!(outputs[0].column(1).is_null(6)); // a single value has no stddev
+
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_describe_columns_overflow() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::oneshot();
+        let mut err = Channel::bounded(10);
+        let block = tokio::spawn(describe_columns(
+            vec![],
+            Running::Never,
+            in_.rx,
+            out.tx,
+            Channel::bounded(1).tx,
+            err.tx,
+        ));
+
+        in_.tx
+            .send(record_batch!(("a", Int8, [100, 100, 100]))?)
+            .await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(block);
+
+        let outputs = out.rx.recv_all().await?;
+        let a = outputs[0].column(1).as_primitive::<Float64Type>();
+        assert_eq!(&a.values()[..6], &[3.0, 0.0, 100.0, 100.0, 300.0, 100.0]);
+
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    fn sample_data() -> RecordBatch {
+        record_batch!(
+            ("a", Int32, [1, 2, 3, 4, 5]),
+            ("b", Float64, [Some(4.0), None, Some(5.0), None, None]),
+            ("c", Utf8, ["alpha", "beta", "gamma", "", ""])
+        )
+        .unwrap()
+    }
+}
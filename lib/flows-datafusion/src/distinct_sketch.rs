@@ -0,0 +1,228 @@
+// This is free and unencumbered software released into the public domain.
+
+use alloc::{format, vec, vec::Vec};
+use arrow_array::{Array, ArrayRef};
+use arrow_row::{RowConverter, SortField};
+use datafusion_common::{DataFusionError, Result};
+
+/// The default precision of a [`DistinctSketch`], for a standard error of
+/// about 0.8% in 16 KiB.
+pub const DEFAULT_DISTINCT_PRECISION: u8 = 14;
+
+/// A HyperLogLog sketch of a set of values for estimating the number of
+/// distinct values in bounded memory.
+///
+/// Values are hashed in their row format with a fixed hash function, so
+/// sketches built in separate processes can be merged, e.g., to combine the
+/// partial results of parallel partitions of a stream. Note that values of
+/// different datatypes hash differently, so partitions should agree on the
+/// datatype.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "DistinctSketchData"))]
+pub struct DistinctSketch {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl Default for DistinctSketch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DistinctSketch {
+    /// Creates an empty sketch of the default precision.
+    pub fn new() -> Self {
+        Self::with_precision(DEFAULT_DISTINCT_PRECISION)
+    }
+
+    /// Creates an empty sketch of `2^precision` registers, with more
+    /// registers trading memory for accuracy.
+    ///
+    /// Panics in case the specified precision isn't between 4 and 18.
+    pub fn with_precision(precision: u8) -> Self {
+        assert!((4..=18).contains(&precision));
+        Self {
+            precision,
+            registers: vec![0; 1 << precision],
+        }
+    }
+
+    /// Returns the precision of the sketch.
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    /// Adds the non-null values of the given array.
+    pub fn update(&mut self, array: &ArrayRef) -> Result<()> {
+        let converter = RowConverter::new(vec![SortField::new(array.data_type().clone())])?;
+        let rows = converter.convert_columns(core::slice::from_ref(array))?;
+        for (index, row) in rows.iter().enumerate() {
+            if array.is_valid(index) {
+                self.insert(hash(row.as_ref()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the values summarized by the given sketch, which must be of the
+    /// same precision.
+    pub fn merge(&mut self, other: &DistinctSketch) -> Result<()> {
+        if self.precision != other.precision || self.registers.len() != other.registers.len() {
+            return Err(DataFusionError::Execution(format!(
+                "can't merge sketches of precisions {} and {}",
+                self.precision, other.precision
+            )));
+        }
+        for (register, other) in self.registers.iter_mut().zip(&other.registers) {
+            *register = (*register).max(*other);
+        }
+        Ok(())
+    }
+
+    /// Returns the estimated number of distinct values added to the sketch.
+    pub fn count(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let (mut sum, mut zeros) = (0.0, 0);
+        for register in &self.registers {
+            sum += 1.0 / (1u64 << register) as f64;
+            zeros += (*register == 0) as usize;
+        }
+
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let estimate = alpha * m * m / sum;
+        // Small cardinalities are better estimated by linear counting:
+        let estimate = match estimate <= 2.5 * m && zeros > 0 {
+            true => m * libm::log(m / zeros as f64),
+            false => estimate,
+        };
+        libm::round(estimate) as u64
+    }
+
+    fn insert(&mut self, hash: u64) {
+        let index = (hash >> (64 - self.precision)) as usize;
+        let rank = (hash << self.precision).leading_zeros() as u8 + 1;
+        let register = &mut self.registers[index];
+        *register = (*register).max(rank.min(64 - self.precision + 1));
+    }
+}
+
+/// Returns an error in case the given precision isn't between 4 and 18.
+pub(crate) fn check_precision(precision: u8) -> Result<()> {
+    match (4..=18).contains(&precision) {
+        true => Ok(()),
+        false => Err(DataFusionError::Execution(format!(
+            "the precision {precision} isn't between 4 and 18"
+        ))),
+    }
+}
+
+/// The serialized form of a [`DistinctSketch`], validated on deserialization.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct DistinctSketchData {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<DistinctSketchData> for DistinctSketch {
+    type Error = alloc::string::String;
+
+    fn try_from(data: DistinctSketchData) -> core::result::Result<Self, Self::Error> {
+        let DistinctSketchData {
+            precision,
+            registers,
+        } = data;
+        if !(4..=18).contains(&precision) {
+            return Err(format!("the precision {precision} isn't between 4 and 18"));
+        }
+        if registers.len() != 1 << precision {
+            return Err(format!(
+                "a sketch of precision {precision} has {} registers, not {}",
+                registers.len(),
+                1 << precision
+            ));
+        }
+        if registers.iter().any(|&rank| rank > 64 - precision + 1) {
+            return Err(format!(
+                "a sketch of precision {precision} has an invalid register"
+            ));
+        }
+        Ok(Self {
+            precision,
+            registers,
+        })
+    }
+}
+
+/// Hashes the given bytes with 64-bit FNV-1a, followed by the finalizer of
+/// MurmurHash3 so that its high bits are well mixed, consistently across
+/// processes and platforms.
+pub(crate) fn hash(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    hash ^ (hash >> 33)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, sync::Arc};
+    use arrow_array::{Int64Array, StringArray};
+    use core::error::Error;
+
+    #[test]
+    fn test_distinct_sketch() -> Result<(), Box<dyn Error>> {
+        let (mut lhs, mut rhs) = (DistinctSketch::new(), DistinctSketch::new());
+        lhs.update(&(Arc::new(Int64Array::from_iter_values(0..60_000)) as ArrayRef))?;
+        rhs.update(&(Arc::new(Int64Array::from_iter_values(40_000..100_000)) as ArrayRef))?;
+        assert!(lhs.count().abs_diff(60_000) < 1_500);
+
+        lhs.merge(&rhs)?;
+        assert!(lhs.count().abs_diff(100_000) < 2_500);
+        assert!(lhs.merge(&DistinctSketch::with_precision(10)).is_err());
+
+        let mut small = DistinctSketch::new();
+        small.update(
+            &(Arc::new(StringArray::from(vec![
+                Some("a"),
+                None,
+                Some("b"),
+                Some("a"),
+            ])) as ArrayRef),
+        )?;
+        assert_eq!(small.count(), 2);
+        assert_eq!(DistinctSketch::new().count(), 0);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_distinct_sketch_serde() -> Result<(), Box<dyn Error>> {
+        let mut sketch = DistinctSketch::with_precision(4);
+        sketch.update(&(Arc::new(Int64Array::from_iter_values(0..10)) as ArrayRef))?;
+        let json = serde_json::to_string(&sketch)?;
+        assert_eq!(serde_json::from_str::<DistinctSketch>(&json)?, sketch);
+
+        let invalid = [
+            r#"{"precision":3,"registers":[0,0,0,0,0,0,0,0]}"#,
+            r#"{"precision":4,"registers":[0,0,0]}"#,
+            r#"{"precision":4,"registers":[99,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0]}"#,
+        ];
+        for json in invalid {
+            assert!(serde_json::from_str::<DistinctSketch>(json).is_err());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,209 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::inputs_partition::InputsPartition;
+use alloc::{sync::Arc, vec::Vec};
+use arrow_array::RecordBatch;
+use async_flow::{Inputs, Outputs, Port, Result};
+use datafusion::{
+    catalog::streaming::StreamingTable,
+    common::{TableReference, tree_node::Transformed},
+    datasource::provider_as_source,
+    execution::{SendableRecordBatchStream, context::SessionContext},
+    logical_expr::LogicalPlan,
+    physical_plan::{
+        ExecutionPlan, ExecutionPlanProperties, execute_stream, streaming::StreamingTableExec,
+    },
+    prelude::SessionConfig,
+};
+use datafusion_common::{DataFusionError, exec_err, plan_err};
+use flows_error::BlockError;
+use futures::StreamExt;
+use hashbrown::{HashMap, HashSet};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+
+/// Executes the given logical plan, with its scans of the given tables
+/// reading the batches received on the corresponding input ports, and sends
+/// the non-empty result batches on `outputs`.
+///
+/// Errors, including input batches that don't conform to their table's
+/// schema, are reported on `errors`. An error from the plan's execution ends
+/// it, as does finding `outputs` closed.
+pub(crate) async fn execute_plan(
+    block: &'static str,
+    plan: LogicalPlan,
+    tables: Vec<(TableReference, Inputs<RecordBatch>)>,
+    outputs: &Outputs<RecordBatch>,
+    errors: &Outputs<BlockError>,
+) -> Result {
+    let (rejects_tx, rejects) = unbounded_channel();
+    let stream = async {
+        let plan = bind_tables(plan, tables, &rejects_tx)?;
+        let context = session_context();
+        let plan = context.state().create_physical_plan(&plan).await?;
+        execute_stream(plan, context.task_ctx())
+    }
+    .await;
+    drop(rejects_tx);
+
+    send_results(block, stream, rejects, outputs, errors).await
+}
+
+/// Executes the given physical operator, with each of its children replaced
+/// by the batches received on the input port of the same position, and sends
+/// the non-empty result batches on `outputs`.
+///
+/// Errors are reported on `errors`, as with `execute_plan`.
+pub(crate) async fn execute_operator(
+    block: &'static str,
+    plan: Arc<dyn ExecutionPlan>,
+    inputs: Vec<Inputs<RecordBatch>>,
+    outputs: &Outputs<RecordBatch>,
+    errors: &Outputs<BlockError>,
+) -> Result {
+    let (rejects_tx, rejects) = unbounded_channel();
+    let stream = bind_children(plan, inputs, &rejects_tx)
+        .and_then(|plan| execute_stream(plan, Default::default()));
+    drop(rejects_tx);
+
+    send_results(block, stream, rejects, outputs, errors).await
+}
+
+/// Returns a session for planning the execution of input ports.
+///
+/// Each input port is a single partition, and planning for a single target
+/// partition preserves the order of the result batches.
+pub(crate) fn session_context() -> SessionContext {
+    SessionContext::new_with_config(SessionConfig::new().with_target_partitions(1))
+}
+
+/// Returns the given table reference resolved against the default catalog
+/// and schema of a session, so that references to the same table compare
+/// equal however they are qualified.
+pub(crate) fn resolve_table(reference: &TableReference) -> TableReference {
+    let config = SessionConfig::new();
+    let catalog = &config.options().catalog;
+    let resolved = reference
+        .clone()
+        .resolve(&catalog.default_catalog, &catalog.default_schema);
+    TableReference::full(resolved.catalog, resolved.schema, resolved.table)
+}
+
+/// Replaces the given logical plan's scans of the given tables with
+/// streaming tables reading from the corresponding input ports.
+pub(crate) fn bind_tables(
+    plan: LogicalPlan,
+    tables: Vec<(TableReference, Inputs<RecordBatch>)>,
+    rejects: &UnboundedSender<DataFusionError>,
+) -> datafusion_common::Result<LogicalPlan> {
+    let mut tables: HashMap<TableReference, Inputs<RecordBatch>> = tables
+        .into_iter()
+        .map(|(reference, inputs)| (resolve_table(&reference), inputs))
+        .collect();
+    let mut scanned = HashSet::new();
+
+    let plan = plan.transform_up_with_subqueries(|node| match node {
+        LogicalPlan::TableScan(mut scan) => {
+            let reference = resolve_table(&scan.table_name);
+            let Some(inputs) = tables.remove(&reference) else {
+                if scanned.contains(&reference) {
+                    return plan_err!("table `{reference}` can only be scanned once");
+                }
+                return Ok(Transformed::no(LogicalPlan::TableScan(scan)));
+            };
+            scanned.insert(reference);
+            let schema = scan.source.schema();
+            let partition = InputsPartition::new(schema.clone(), inputs, Some(rejects.clone()));
+            let table = StreamingTable::try_new(schema, alloc::vec![Arc::new(partition)])?;
+            scan.source = provider_as_source(Arc::new(table));
+            Ok(Transformed::yes(LogicalPlan::TableScan(scan)))
+        },
+        node => Ok(Transformed::no(node)),
+    })?;
+    Ok(plan.data)
+}
+
+/// Replaces the children of the given physical operator with streaming
+/// operators reading from the input ports of the same positions.
+pub(crate) fn bind_children(
+    plan: Arc<dyn ExecutionPlan>,
+    inputs: Vec<Inputs<RecordBatch>>,
+    rejects: &UnboundedSender<DataFusionError>,
+) -> datafusion_common::Result<Arc<dyn ExecutionPlan>> {
+    let children = plan.children();
+    if children.len() != inputs.len() {
+        return exec_err!(
+            "expected {} inputs for operator `{}`, got {}",
+            children.len(),
+            plan.name(),
+            inputs.len()
+        );
+    }
+
+    let children = children
+        .into_iter()
+        .zip(inputs)
+        .map(|(child, inputs)| {
+            let schema = child.schema();
+            let partition = InputsPartition::new(schema.clone(), inputs, Some(rejects.clone()));
+            let child = StreamingTableExec::try_new(
+                schema,
+                alloc::vec![Arc::new(partition)],
+                None,
+                child.output_ordering().cloned(),
+                false,
+                None,
+            )?;
+            Ok(Arc::new(child) as Arc<dyn ExecutionPlan>)
+        })
+        .collect::<datafusion_common::Result<Vec<_>>>()?;
+    plan.with_new_children(children)
+}
+
+async fn send_results(
+    block: &'static str,
+    stream: datafusion_common::Result<SendableRecordBatchStream>,
+    mut rejects: UnboundedReceiver<DataFusionError>,
+    outputs: &Outputs<RecordBatch>,
+    errors: &Outputs<BlockError>,
+) -> Result {
+    let mut stream = match stream {
+        Ok(stream) => stream,
+        Err(error) => {
+            if errors.is_connected() {
+                errors.send(BlockError::from_error(block, error)).await?;
+            }
+            return Ok(());
+        },
+    };
+
+    while let Some(result) = stream.next().await {
+        report_rejects(block, &mut rejects, errors).await?;
+        match result {
+            Ok(_) if outputs.is_closed() => break,
+            Ok(batch) if batch.num_rows() > 0 => outputs.send(batch).await?,
+            Err(error) => {
+                if errors.is_connected() {
+                    errors.send(BlockError::from_error(block, error)).await?;
+                }
+                break;
+            },
+            _ => continue,
+        }
+    }
+    report_rejects(block, &mut rejects, errors).await?;
+
+    Ok(())
+}
+
+async fn report_rejects(
+    block: &'static str,
+    rejects: &mut UnboundedReceiver<DataFusionError>,
+    errors: &Outputs<BlockError>,
+) -> Result {
+    while let Ok(error) = rejects.try_recv() {
+        if errors.is_connected() {
+            errors.send(BlockError::from_error(block, error)).await?;
+        }
+    }
+    Ok(())
+}
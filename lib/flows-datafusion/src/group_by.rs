@@ -0,0 +1,303 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{
+    Aggregate, Running,
+    grouper::Grouper,
+    running::{Event, Ticker},
+};
+use alloc::{string::String, vec::Vec};
+use arrow_array::RecordBatch;
+use async_flow::{Inputs, Output, Outputs, Port, Result};
+use flows_derive::block;
+use flows_error::BlockError;
+
+/// A block that groups the rows of all input batches by the values of the
+/// given key columns and outputs the given aggregates of each group, i.e.,
+/// `GROUP BY`, as a single batch at the end of the stream.
+///
+/// The output batch consists of the key columns followed by a column for
+/// each aggregate, with a row for each group in order of first appearance.
+/// With no key columns, all rows form a single group. Only the aggregates'
+/// accumulators are retained across batches, not the rows themselves. The
+/// first batch determines the datatypes of the key and aggregated columns.
+/// Batches that can't be aggregated (e.g., due to a missing column, or to
+/// values that don't fit those datatypes) are reported on the `errors` port.
+/// Running batches of the groups so far are output on the `running` port as
+/// specified by `every`, once the datatypes of the columns are known.
+#[block]
+pub async fn group_by(
+    keys: Vec<String>,
+    aggregates: Vec<Aggregate>,
+    every: Running,
+    mut inputs: Inputs<RecordBatch>,
+    output: Output<RecordBatch>,
+    running: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    let mut grouper = Grouper::new(&keys, &aggregates);
+    let mut ticker = Ticker::start(every, "group_by", &errors).await?;
+
+    while let Some(event) = ticker.recv(&mut inputs).await? {
+        let input = match event {
+            Event::Input(input) => input,
+            Event::Tick => {
+                if !running.is_connected() {
+                    continue;
+                }
+                match grouper.snapshot() {
+                    Ok(Some(batch)) => running.send(batch).await?,
+                    Err(error) if errors.is_connected() => {
+                        errors
+                            .send(BlockError::from_error("group_by", error))
+                            .await?
+                    },
+                    _ => {},
+                }
+                continue;
+            },
+        };
+
+        if let Err(error) = grouper.update(&input)
+            && errors.is_connected()
+        {
+            errors
+                .send(BlockError::from_error("group_by", error))
+                .await?;
+        }
+    }
+
+    match grouper.finish() {
+        Ok(Some(batch)) if !output.is_closed() => output.send(batch).await?,
+        Err(error) if errors.is_connected() => {
+            errors
+                .send(BlockError::from_error("group_by", error))
+                .await?
+        },
+        _ => {},
+    }
+
+    Ok(())
+}
+
+/// A block that groups the rows of each input window of batches by the
+/// values of the given key columns and outputs the given aggregates of each
+/// group, as a batch per window.
+///
+/// See `group_by` for the layout of the output batches. Windows without
+/// rows result in an empty batch, once the datatypes of the columns are
+/// known. Batches that can't be aggregated are reported on the `errors`
+/// port.
+#[block]
+pub async fn group_by_windows(
+    keys: Vec<String>,
+    aggregates: Vec<Aggregate>,
+    mut inputs: Inputs<Vec<RecordBatch>>,
+    outputs: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    let mut grouper = Grouper::new(&keys, &aggregates);
+
+    while let Some(window) = inputs.recv().await? {
+        for input in &window {
+            if let Err(error) = grouper.update(input)
+                && errors.is_connected()
+            {
+                errors
+                    .send(BlockError::from_error("group_by_windows", error))
+                    .await?;
+            }
+        }
+
+        match grouper.finish() {
+            Ok(Some(batch)) if !outputs.is_closed() => outputs.send(batch).await?,
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("group_by_windows", error))
+                    .await?
+            },
+            _ => continue,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use arrow_array::{
+        cast::AsArray,
+        record_batch,
+        types::{Float64Type, Int64Type},
+    };
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_group_by() -> Result<(), Box<dyn Error>> {
+        let (mut in_, mut out) = (Channel::bounded(10), Channel::oneshot());
+        let mut err = Channel::bounded(10);
+        let aggregates = vec![
+            Aggregate::sum("amount"),
+            Aggregate::avg("amount").with_alias("mean"),
+            Aggregate::count("amount"),
+            Aggregate::new(
+                datafusion_functions_aggregate::median::median_udaf(),
+                "amount",
+            ),
+        ];
+        let grouper = tokio::spawn(group_by(
+            vec!["region".into()],
+            aggregates,
+            Running::Never,
+            in_.rx,
+            out.tx,
+            Channel::bounded(1).tx,
+            err.tx,
+        ));
+
+        in_.tx
+            .send(record_batch!(
+                ("region", Utf8, ["north", "south", "north"]),
+                ("amount", Int32, [Some(1), Some(2), None])
+            )?)
+            .await?;
+        in_.tx
+            .send(record_batch!(
+                ("amount", Int32, [4, 8]),
+                ("region", Utf8, ["south", "north"])
+            )?)
+            .await?;
+        in_.tx.send(record_batch!(("region", Utf8, ["x"]))?).await?; // lacks `amount`
+        in_.tx
+            .send(record_batch!(
+                ("region", Utf8, ["north"]),
+                ("amount", Int64, [1 << 40])
+            )?)
+            .await?; // overflows `amount`
+        in_.tx.close();
+
+        let _ = tokio::join!(grouper);
+
+        let output = out.rx.recv().await?.unwrap();
+        let names: Vec<&str> = output
+            .schema_ref()
+            .fields()
+            .iter()
+            .map(|f| f.name().as_str())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "region",
+                "sum(amount)",
+                "mean",
+                "count(amount)",
+                "median(amount)"
+            ]
+        );
+        let regions = output.column(0).as_string::<i32>();
+        assert_eq!((regions.value(0), regions.value(1)), ("north", "south"));
+        assert_eq!(
+            output.column(1).as_primitive::<Int64Type>().values(),
+            &[9, 6]
+        );
+        assert_eq!(
+            output.column(2).as_primitive::<Float64Type>().values(),
+            &[4.5, 3.0]
+        );
+        assert_eq!(
+            output.column(3).as_primitive::<Int64Type>().values(),
+            &[2, 2]
+        );
+        assert_eq!(err.rx.recv_all().await?.len(), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_group_by_running() -> Result<(), Box<dyn Error>> {
+        let (mut in_, mut out) = (Channel::bounded(10), Channel::oneshot());
+        let (mut run, mut err) = (Channel::bounded(10), Channel::bounded(10));
+        let grouper = tokio::spawn(group_by(
+            vec!["k".into()],
+            vec![Aggregate::sum("n"), Aggregate::avg("n")],
+            Running::Batches(1),
+            in_.rx,
+            out.tx,
+            run.tx,
+            err.tx,
+        ));
+
+        in_.tx
+            .send(record_batch!(("k", Int64, [1, 2]), ("n", Int64, [1, 2]))?)
+            .await?;
+        in_.tx
+            .send(record_batch!(("k", Int64, [1, 3]), ("n", Int64, [3, 4]))?)
+            .await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(grouper);
+
+        let sums = |batch: &RecordBatch| {
+            batch
+                .column(1)
+                .as_primitive::<Int64Type>()
+                .values()
+                .to_vec()
+        };
+        let running = run.rx.recv_all().await?;
+        assert_eq!(running.len(), 2);
+        assert_eq!(
+            (sums(&running[0]), sums(&running[1])),
+            (vec![1, 2], vec![4, 2, 4])
+        );
+        assert_eq!(
+            running[1].column(2).as_primitive::<Float64Type>().values(),
+            &[2.0, 2.0, 4.0]
+        );
+        let output = out.rx.recv().await?.unwrap();
+        assert_eq!(output, running[1]);
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_group_by_windows() -> Result<(), Box<dyn Error>> {
+        let (mut in_, mut out) = (Channel::bounded(10), Channel::bounded(10));
+        let mut err = Channel::bounded(10);
+        let grouper = tokio::spawn(group_by_windows(
+            vec![],
+            vec![Aggregate::max("n")],
+            in_.rx,
+            out.tx,
+            err.tx,
+        ));
+
+        let batch = record_batch!(("n", Int64, [1, 5, 3]))?;
+        in_.tx.send(vec![batch.clone(), batch.slice(0, 1)]).await?;
+        in_.tx.send(vec![batch.slice(0, 1)]).await?;
+        in_.tx.send(vec![]).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(grouper);
+
+        let outputs = out.rx.recv_all().await?;
+        let maxes: Vec<&[i64]> = outputs
+            .iter()
+            .map(|batch| {
+                batch
+                    .column(0)
+                    .as_primitive::<Int64Type>()
+                    .values()
+                    .as_ref()
+            })
+            .collect();
+        assert_eq!(maxes, [&[5][..], &[1], &[]]);
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+}
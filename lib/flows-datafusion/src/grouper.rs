@@ -0,0 +1,265 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::Aggregate;
+use alloc::{boxed::Box, format, string::String, sync::Arc, vec, vec::Vec};
+use arrow_array::{ArrayRef, RecordBatch, RecordBatchOptions};
+use arrow_cast::CastOptions;
+use arrow_row::{RowConverter, Rows, SortField};
+use arrow_schema::{DataType, Field, FieldRef, Schema, SchemaRef};
+use datafusion_common::{DataFusionError, Result};
+use datafusion_expr::{
+    AggregateUDF, EmitTo, GroupsAccumulator, function::AccumulatorArgs,
+    type_coercion::functions::fields_with_udf,
+};
+use datafusion_physical_expr::{GroupsAccumulatorAdapter, PhysicalExpr, expressions::Column};
+use hashbrown::HashMap;
+
+/// Maintains the accumulators of a set of aggregates for each group of rows
+/// with the same values in the key columns.
+///
+/// The first batch determines the datatypes of the key and aggregated
+/// columns, and the columns of later batches are cast to them, with values
+/// that don't fit failing their batch.
+pub(crate) struct Grouper<'a> {
+    keys: &'a [String],
+    aggregates: &'a [Aggregate],
+    state: Option<State>,
+}
+
+struct State {
+    schema: SchemaRef,
+    converter: RowConverter,
+    groups: HashMap<Box<[u8]>, usize>,
+    group_keys: Rows,
+    num_groups: usize,
+    inputs: Vec<FieldRef>,
+    specs: Vec<AccumulatorSpec>,
+    accumulators: Vec<Box<dyn GroupsAccumulator>>,
+}
+
+/// The arguments used to create the accumulators of an aggregate.
+#[derive(Clone)]
+struct AccumulatorSpec {
+    function: Arc<AggregateUDF>,
+    name: String,
+    return_field: FieldRef,
+    schema: Schema,
+    exprs: Vec<Arc<dyn PhysicalExpr>>,
+}
+
+impl<'a> Grouper<'a> {
+    pub(crate) fn new(keys: &'a [String], aggregates: &'a [Aggregate]) -> Self {
+        Self {
+            keys,
+            aggregates,
+            state: None,
+        }
+    }
+
+    /// Adds the rows of the given batch to their groups' accumulators.
+    pub(crate) fn update(&mut self, batch: &RecordBatch) -> Result<()> {
+        let state = match &mut self.state {
+            Some(state) => state,
+            None => self
+                .state
+                .insert(State::new(self.keys, self.aggregates, batch)?),
+        };
+        if batch.num_rows() == 0 {
+            return Ok(()); // skip empty batches
+        }
+
+        let key_fields = &state.schema.fields()[..self.keys.len()];
+        let key_columns = self
+            .keys
+            .iter()
+            .zip(key_fields)
+            .map(|(key, field)| column(batch, key, field.data_type()))
+            .collect::<Result<Vec<_>>>()?;
+        let values = self
+            .aggregates
+            .iter()
+            .zip(&state.inputs)
+            .map(|(aggregate, input)| column(batch, &aggregate.column, input.data_type()))
+            .collect::<Result<Vec<_>>>()?;
+
+        let group_indices = if key_columns.is_empty() {
+            state.num_groups = 1;
+            vec![0; batch.num_rows()]
+        } else {
+            let rows = state.converter.convert_columns(&key_columns)?;
+            rows.iter()
+                .map(|row| match state.groups.get(row.as_ref()) {
+                    Some(&index) => index,
+                    None => {
+                        let index = state.num_groups;
+                        state.groups.insert(row.as_ref().into(), index);
+                        state.group_keys.push(row);
+                        state.num_groups += 1;
+                        index
+                    },
+                })
+                .collect()
+        };
+
+        for (accumulator, values) in state.accumulators.iter_mut().zip(values) {
+            accumulator.update_batch(&[values], &group_indices, None, state.num_groups)?;
+        }
+        Ok(())
+    }
+
+    /// Returns a batch with the key columns and aggregate values of each
+    /// group, in order of first appearance, and starts over with no groups.
+    /// Returns `None` if no batches have been added.
+    pub(crate) fn finish(&mut self) -> Result<Option<RecordBatch>> {
+        self.emit(true)
+    }
+
+    /// Returns a batch as by `finish`, but keeps on with the current groups.
+    pub(crate) fn snapshot(&mut self) -> Result<Option<RecordBatch>> {
+        self.emit(false)
+    }
+
+    fn emit(&mut self, reset: bool) -> Result<Option<RecordBatch>> {
+        let Some(state) = &mut self.state else {
+            return Ok(None);
+        };
+
+        let num_rows = state.num_groups;
+        if num_rows == 0 {
+            return Ok(Some(RecordBatch::new_empty(state.schema.clone())));
+        }
+
+        let mut columns = match self.keys.is_empty() {
+            true => Vec::new(),
+            false => state.converter.convert_rows(&state.group_keys)?,
+        };
+        for (accumulator, spec) in state.accumulators.iter_mut().zip(&state.specs) {
+            if reset {
+                columns.push(accumulator.evaluate(EmitTo::All)?);
+                continue;
+            }
+            // Evaluating consumes the accumulator's state, so the state is
+            // merged back into it, and into a copy to evaluate instead:
+            let states = accumulator.state(EmitTo::All)?;
+            let group_indices: Vec<usize> = (0..num_rows).collect();
+            accumulator.merge_batch(&states, &group_indices, None, num_rows)?;
+            let mut copy = spec.create()?;
+            copy.merge_batch(&states, &group_indices, None, num_rows)?;
+            columns.push(copy.evaluate(EmitTo::All)?);
+        }
+        if reset {
+            state.groups.clear();
+            state.group_keys = state.converter.empty_rows(0, 0);
+            state.num_groups = 0;
+        }
+
+        let options = RecordBatchOptions::new().with_row_count(Some(num_rows));
+        Ok(Some(RecordBatch::try_new_with_options(
+            state.schema.clone(),
+            columns,
+            &options,
+        )?))
+    }
+}
+
+impl State {
+    fn new(keys: &[String], aggregates: &[Aggregate], batch: &RecordBatch) -> Result<Self> {
+        let schema = batch.schema_ref();
+        let mut fields = keys
+            .iter()
+            .map(|key| Ok(Arc::new(schema.field_with_name(key)?.clone())))
+            .collect::<Result<Vec<FieldRef>>>()?;
+        let converter = RowConverter::new(
+            fields
+                .iter()
+                .map(|field| SortField::new(field.data_type().clone()))
+                .collect(),
+        )?;
+
+        let mut inputs = Vec::with_capacity(aggregates.len());
+        let mut specs = Vec::with_capacity(aggregates.len());
+        let mut accumulators = Vec::with_capacity(aggregates.len());
+        for aggregate in aggregates {
+            let field = Arc::new(schema.field_with_name(&aggregate.column)?.clone());
+            let input = fields_with_udf(&[field], aggregate.function.as_ref())?.remove(0);
+            let spec = AccumulatorSpec::new(aggregate, input.clone())?;
+            fields.push(Arc::new(Field::new(
+                aggregate.output_name(),
+                spec.return_field.data_type().clone(),
+                true,
+            )));
+            inputs.push(input);
+            accumulators.push(spec.create()?);
+            specs.push(spec);
+        }
+
+        Ok(Self {
+            schema: Arc::new(Schema::new(fields)),
+            group_keys: converter.empty_rows(0, 0),
+            converter,
+            groups: HashMap::new(),
+            num_groups: 0,
+            inputs,
+            specs,
+            accumulators,
+        })
+    }
+}
+
+impl AccumulatorSpec {
+    fn new(aggregate: &Aggregate, input: FieldRef) -> Result<Self> {
+        let function = aggregate.function.clone();
+        let return_field = function.return_field(core::slice::from_ref(&input))?;
+        Ok(Self {
+            name: aggregate.output_name(),
+            return_field,
+            exprs: vec![Arc::new(Column::new(input.name(), 0))],
+            schema: Schema::new(vec![input]),
+            function,
+        })
+    }
+
+    fn args(&self) -> AccumulatorArgs<'_> {
+        AccumulatorArgs {
+            return_field: self.return_field.clone(),
+            schema: &self.schema,
+            ignore_nulls: false,
+            order_bys: &[],
+            is_reversed: false,
+            name: &self.name,
+            is_distinct: false,
+            exprs: &self.exprs,
+            expr_fields: self.schema.fields(),
+        }
+    }
+
+    /// Creates the aggregate's groups accumulator, falling back to one
+    /// accumulator per group for functions that lack one.
+    fn create(&self) -> Result<Box<dyn GroupsAccumulator>> {
+        if self.function.groups_accumulator_supported(self.args()) {
+            return self.function.create_groups_accumulator(self.args());
+        }
+        let spec = self.clone();
+        Ok(Box::new(GroupsAccumulatorAdapter::new(move || {
+            spec.function.accumulator(spec.args())
+        })))
+    }
+}
+
+/// Returns the given column of the given batch, cast to the given datatype,
+/// with values that don't convert being an error rather than null.
+fn column(batch: &RecordBatch, name: &str, data_type: &DataType) -> Result<ArrayRef> {
+    let Some(column) = batch.column_by_name(name) else {
+        return Err(DataFusionError::Execution(format!(
+            "column '{name}' not found"
+        )));
+    };
+    let options = CastOptions {
+        safe: false,
+        ..Default::default()
+    };
+    match column.data_type() == data_type {
+        true => Ok(column.clone()),
+        false => Ok(arrow_cast::cast_with_options(column, data_type, &options)?),
+    }
+}
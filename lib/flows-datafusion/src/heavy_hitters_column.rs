@@ -0,0 +1,340 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{
+    ColumnRef, HeavyHittersSketch, Running,
+    heavy_hitters_sketch::check_capacity,
+    running::{Event, Ticker},
+};
+use arrow_array::RecordBatch;
+use async_flow::{Inputs, Output, Outputs, Port, Result};
+use flows_derive::block;
+use flows_error::BlockError;
+
+/// A block that outputs the (at most) `limit` most frequent values in a given
+/// column, as a batch with columns `value`, `count` and `error`, in
+/// descending order of count.
+///
+/// The values are summarized in a [`HeavyHittersSketch`] monitoring
+/// `capacity` values, so memory use stays bounded regardless of the number of
+/// distinct values, and the counts are estimates within the given error.
+/// Batches that lack the specified column, or whose column can't be cast to
+/// the datatype of the first one, are reported on the `errors` port. A zero
+/// capacity is reported there as well, after which the block exits.
+/// Running batches are output on the `running` port as specified by `every`.
+#[block]
+#[allow(clippy::too_many_arguments)]
+pub async fn heavy_hitters_column(
+    column: ColumnRef,
+    capacity: usize,
+    limit: usize,
+    every: Running,
+    mut inputs: Inputs<RecordBatch>,
+    output: Output<RecordBatch>,
+    running: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    if let Err(error) = check_capacity(capacity) {
+        if errors.is_connected() {
+            errors
+                .send(BlockError::from_error("heavy_hitters_column", error))
+                .await?;
+        }
+        return Ok(());
+    }
+
+    let mut sketch = HeavyHittersSketch::with_capacity(capacity);
+    let mut ticker = Ticker::start(every, "heavy_hitters_column", &errors).await?;
+
+    while let Some(event) = ticker.recv(&mut inputs).await? {
+        let input = match event {
+            Event::Input(input) => input,
+            Event::Tick => {
+                if !running.is_connected() {
+                    continue;
+                }
+                match sketch.top(limit) {
+                    Ok(batch) => running.send(batch).await?,
+                    Err(error) if errors.is_connected() => {
+                        errors
+                            .send(BlockError::from_error("heavy_hitters_column", error))
+                            .await?
+                    },
+                    Err(_) => {},
+                }
+                continue;
+            },
+        };
+
+        if let Err(error) = sketch_batch(&mut sketch, &input, &column)
+            && errors.is_connected()
+        {
+            errors
+                .send(BlockError::from_error("heavy_hitters_column", error))
+                .await?;
+        }
+    }
+
+    match sketch.top(limit) {
+        Ok(batch) if !output.is_closed() => output.send(batch).await?,
+        Err(error) if errors.is_connected() => {
+            errors
+                .send(BlockError::from_error("heavy_hitters_column", error))
+                .await?
+        },
+        _ => {},
+    }
+
+    Ok(())
+}
+
+/// A block that outputs a [`HeavyHittersSketch`] of the values in a given
+/// column, monitoring `capacity` values, e.g., as the partial result of a
+/// partition of a stream to merge with `merge_heavy_hitters_sketches`.
+///
+/// Batches that lack the specified column, or whose column can't be cast to
+/// the datatype of the first one, are reported on the `errors` port. A zero
+/// capacity is reported there as well, after which the block exits.
+#[block]
+pub async fn sketch_heavy_hitters_column(
+    column: ColumnRef,
+    capacity: usize,
+    mut inputs: Inputs<RecordBatch>,
+    output: Output<HeavyHittersSketch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    if let Err(error) = check_capacity(capacity) {
+        if errors.is_connected() {
+            errors
+                .send(BlockError::from_error("sketch_heavy_hitters_column", error))
+                .await?;
+        }
+        return Ok(());
+    }
+
+    let mut sketch = HeavyHittersSketch::with_capacity(capacity);
+
+    while let Some(input) = inputs.recv().await? {
+        if let Err(error) = sketch_batch(&mut sketch, &input, &column)
+            && errors.is_connected()
+        {
+            errors
+                .send(BlockError::from_error("sketch_heavy_hitters_column", error))
+                .await?;
+        }
+    }
+
+    if !output.is_closed() {
+        output.send(sketch).await?;
+    }
+
+    Ok(())
+}
+
+/// A block that merges all input sketches into one, of the capacity of the
+/// first one, which is output once the input closes.
+///
+/// Sketches of values of a different datatype are reported on the `errors`
+/// port. Outputs an empty sketch of the default capacity in case there were
+/// no input sketches.
+#[block]
+pub async fn merge_heavy_hitters_sketches(
+    mut inputs: Inputs<HeavyHittersSketch>,
+    output: Output<HeavyHittersSketch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    let mut result: Option<HeavyHittersSketch> = None;
+
+    while let Some(input) = inputs.recv().await? {
+        let Some(result) = &mut result else {
+            result = Some(input);
+            continue;
+        };
+        if let Err(error) = result.merge(&input)
+            && errors.is_connected()
+        {
+            errors
+                .send(BlockError::from_error(
+                    "merge_heavy_hitters_sketches",
+                    error,
+                ))
+                .await?;
+        }
+    }
+
+    if !output.is_closed() {
+        output.send(result.unwrap_or_default()).await?;
+    }
+
+    Ok(())
+}
+
+fn sketch_batch(
+    sketch: &mut HeavyHittersSketch,
+    input: &RecordBatch,
+    column: &ColumnRef,
+) -> datafusion_common::Result<()> {
+    if input.num_rows() == 0 {
+        return Ok(()); // skip empty batches
+    }
+
+    let column_array = column.resolve(input)?;
+    sketch.update(column_array)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec, vec::Vec};
+    use arrow_array::{cast::AsArray, record_batch, types::UInt64Type};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_heavy_hitters_column() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::oneshot();
+        let mut err = Channel::bounded(10);
+        let block = tokio::spawn(heavy_hitters_column(
+            "word".into(),
+            10,
+            2,
+            Running::Never,
+            in_.rx,
+            out.tx,
+            Channel::bounded(1).tx,
+            err.tx,
+        ));
+
+        in_.tx.send(sample_data()).await?;
+        in_.tx.send(sample_data().slice(0, 1)).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(block);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs.len(), 1);
+        let values: Vec<&str> = outputs[0]
+            .column(0)
+            .as_string::<i32>()
+            .iter()
+            .flatten()
+            .collect();
+        assert_eq!(values, ["to", "be"]);
+        assert_eq!(
+            outputs[0].column(1).as_primitive::<UInt64Type>().values(),
+            &[3, 2]
+        );
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_heavy_hitters_column_running() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let (mut out, mut run) = (Channel::oneshot(), Channel::bounded(10));
+        let mut err = Channel::bounded(10);
+        let block = tokio::spawn(heavy_hitters_column(
+            "word".into(),
+            10,
+            1,
+            Running::Batches(1),
+            in_.rx,
+            out.tx,
+            run.tx,
+            err.tx,
+        ));
+
+        in_.tx.send(sample_data().slice(0, 3)).await?;
+        in_.tx.send(sample_data().slice(3, 4)).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(block);
+
+        let running = run.rx.recv_all().await?;
+        let tops: Vec<(&str, u64)> = running
+            .iter()
+            .map(|batch| {
+                let value = batch.column(0).as_string::<i32>().value(0);
+                (value, batch.column(1).as_primitive::<UInt64Type>().value(0))
+            })
+            .collect();
+        assert_eq!(tops, [("be", 1), ("be", 2)]);
+        assert_eq!(out.rx.recv().await?, Some(running[1].clone()));
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_heavy_hitters_column_zero_capacity() -> Result<(), Box<dyn Error>> {
+        let in_ = Channel::bounded(10);
+        let (mut out, mut err) = (Channel::oneshot(), Channel::bounded(10));
+        let block = tokio::spawn(heavy_hitters_column(
+            "word".into(),
+            0,
+            2,
+            Running::Never,
+            in_.rx,
+            out.tx,
+            Channel::bounded(1).tx,
+            err.tx,
+        ));
+
+        // Reported without waiting for the input to close:
+        let _ = tokio::join!(block);
+
+        assert!(out.rx.recv_all().await?.is_empty());
+        assert_eq!(err.rx.recv_all().await?.len(), 1);
+        drop(in_.tx);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_merge_heavy_hitters_sketches() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::oneshot();
+        let mut err = Channel::bounded(10);
+        let merger = tokio::spawn(merge_heavy_hitters_sketches(in_.rx, out.tx, err.tx));
+
+        let (mut lhs, mut rhs) = (HeavyHittersSketch::new(), HeavyHittersSketch::new());
+        lhs.update(sample_data().column(0))?;
+        rhs.update(sample_data().slice(0, 1).column(0))?;
+        let mut other = HeavyHittersSketch::new();
+        other.update(record_batch!(("n", Int32, [1]))?.column(0))?;
+
+        in_.tx.send(lhs).await?;
+        in_.tx.send(rhs).await?;
+        in_.tx.send(other).await?; // mismatched datatype
+        in_.tx.close();
+
+        let _ = tokio::join!(merger);
+
+        let merged = out.rx.recv_all().await?;
+        assert_eq!(merged.len(), 1);
+        let top = merged[0].top(1)?;
+        assert_eq!(top.column(0).as_string::<i32>().value(0), "to");
+        assert_eq!(top.column(1).as_primitive::<UInt64Type>().value(0), 3);
+        assert_eq!(err.rx.recv_all().await?.len(), 1);
+
+        Ok(())
+    }
+
+    fn sample_data() -> RecordBatch {
+        record_batch!((
+            "word",
+            Utf8,
+            [
+                Some("to"),
+                Some("be"),
+                None,
+                Some("or"),
+                Some("not"),
+                Some("to"),
+                Some("be")
+            ]
+        ))
+        .unwrap()
+    }
+}
@@ -0,0 +1,574 @@
+// This is free and unencumbered software released into the public domain.
+
+use alloc::{format, sync::Arc, vec, vec::Vec};
+use arrow_array::{Array, ArrayRef, RecordBatch, UInt64Array};
+use arrow_cast::CastOptions;
+use arrow_row::{RowConverter, SortField};
+use arrow_schema::{DataType, Field, Schema};
+use core::cmp::Ordering;
+use datafusion_common::{DataFusionError, Result};
+use hashbrown::HashMap;
+
+/// The default capacity of a [`HeavyHittersSketch`].
+pub const DEFAULT_HEAVY_HITTERS_CAPACITY: usize = 100;
+
+/// A Space-Saving sketch of a stream of values for estimating its most
+/// frequent values (i.e., heavy hitters) and their counts in bounded memory.
+///
+/// The sketch monitors at most `capacity` values. A value's estimated count
+/// overestimates its true count by at most its error, which is bounded by
+/// the total count divided by the capacity, so any value more frequent than
+/// that is guaranteed to be monitored.
+///
+/// Values are retained in their row format, and serialized in the Arrow IPC
+/// format, which unlike the row format is stable, so sketches built in
+/// separate processes can be merged, e.g., to combine the partial results of
+/// parallel partitions of a stream. The first non-empty array determines the
+/// datatype of the values, with dictionaries decoded to their values, and
+/// later arrays are cast to it, with values that don't fit failing their
+/// array. Deserialized sketches are validated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "HeavyHittersSketchData"))]
+pub struct HeavyHittersSketch {
+    capacity: usize,
+    data_type: Option<DataType>,
+    counters: Vec<Counter>,
+}
+
+/// A monitored value of a [`HeavyHittersSketch`], in its row format.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Counter {
+    value: Vec<u8>,
+    count: u64,
+    error: u64,
+}
+
+impl Counter {
+    /// Orders counters in descending order of count, and then in ascending
+    /// order of value.
+    fn cmp_frequency(&self, other: &Counter) -> Ordering {
+        other
+            .count
+            .cmp(&self.count)
+            .then_with(|| self.value.cmp(&other.value))
+    }
+}
+
+impl Default for HeavyHittersSketch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HeavyHittersSketch {
+    /// Creates an empty sketch of the default capacity.
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_HEAVY_HITTERS_CAPACITY)
+    }
+
+    /// Creates an empty sketch monitoring at most the given number of values,
+    /// with more values trading memory for accuracy.
+    ///
+    /// Panics in case the specified capacity is zero.
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert!(capacity > 0);
+        Self {
+            capacity,
+            data_type: None,
+            counters: Vec::new(),
+        }
+    }
+
+    /// Returns the maximum number of monitored values.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the datatype of the values, once known.
+    pub fn data_type(&self) -> Option<&DataType> {
+        self.data_type.as_ref()
+    }
+
+    /// Adds the non-null values of the given array.
+    pub fn update(&mut self, array: &ArrayRef) -> Result<()> {
+        if array.len() == array.null_count() {
+            return Ok(()); // skip null-only arrays
+        }
+
+        let data_type = match &self.data_type {
+            Some(data_type) => data_type,
+            None => self.data_type.insert(row_data_type(array.data_type())?),
+        };
+        let options = CastOptions {
+            safe: false,
+            ..Default::default()
+        };
+        let array = match array.data_type() == data_type {
+            true => array.clone(),
+            false => arrow_cast::cast_with_options(array, data_type, &options)?,
+        };
+
+        // Count the batch's values first, so that each distinct value
+        // updates the counters just once, in descending order of count (and
+        // then of value) for the result not to depend on the hash order:
+        let converter = RowConverter::new(vec![SortField::new(data_type.clone())])?;
+        let rows = converter.convert_columns(core::slice::from_ref(&array))?;
+        let mut counts: HashMap<&[u8], u64> = HashMap::new();
+        for (index, row) in rows.iter().enumerate() {
+            if array.is_valid(index) {
+                *counts.entry(row.data()).or_default() += 1;
+            }
+        }
+
+        let mut counts: Vec<(&[u8], u64)> = counts.into_iter().collect();
+        counts.sort_unstable_by(|lhs, rhs| rhs.1.cmp(&lhs.1).then_with(|| lhs.0.cmp(rhs.0)));
+        for (value, count) in counts {
+            self.insert(value, count, 0);
+        }
+        Ok(())
+    }
+
+    /// Adds the values summarized by the given sketch, whose values must be
+    /// of the same datatype.
+    ///
+    /// Values monitored by only one of the sketches could have occurred up to
+    /// the smallest count of the other sketch, if it is full, which is added
+    /// to their count and error.
+    pub fn merge(&mut self, other: &HeavyHittersSketch) -> Result<()> {
+        match (&self.data_type, &other.data_type) {
+            (_, None) => return Ok(()),
+            (None, Some(data_type)) => self.data_type = Some(data_type.clone()),
+            (Some(lhs), Some(rhs)) if lhs != rhs => {
+                return Err(DataFusionError::Execution(format!(
+                    "can't merge sketches of values of types {lhs} and {rhs}"
+                )));
+            },
+            _ => {},
+        }
+
+        let (self_min, other_min) = (self.min_count(), other.min_count());
+        let mut counters = Vec::with_capacity(self.counters.len() + other.counters.len());
+        for counter in &self.counters {
+            let (count, error) = match other.find(&counter.value) {
+                Ok(index) => (other.counters[index].count, other.counters[index].error),
+                Err(_) => (other_min, other_min),
+            };
+            counters.push(Counter {
+                value: counter.value.clone(),
+                count: counter.count + count,
+                error: counter.error + error,
+            });
+        }
+        for counter in &other.counters {
+            if self.find(&counter.value).is_err() {
+                counters.push(Counter {
+                    value: counter.value.clone(),
+                    count: counter.count + self_min,
+                    error: counter.error + self_min,
+                });
+            }
+        }
+
+        // Retain the most frequent values, in the order of their row format:
+        counters.sort_by(Counter::cmp_frequency);
+        counters.truncate(self.capacity);
+        counters.sort_by(|lhs, rhs| lhs.value.cmp(&rhs.value));
+        self.counters = counters;
+        Ok(())
+    }
+
+    /// Returns a batch of (at most) the given number of most frequent values,
+    /// with columns `value`, `count` and `error`, in descending order of
+    /// count and then ascending order of value.
+    pub fn top(&self, limit: usize) -> Result<RecordBatch> {
+        let data_type = self.data_type.clone().unwrap_or(DataType::Null);
+        let mut counters: Vec<&Counter> = self.counters.iter().collect();
+        counters.sort_by(|lhs, rhs| lhs.cmp_frequency(rhs));
+        counters.truncate(limit);
+
+        let values = self.values(&counters)?;
+        let counts = UInt64Array::from_iter_values(counters.iter().map(|counter| counter.count));
+        let errors = UInt64Array::from_iter_values(counters.iter().map(|counter| counter.error));
+
+        let schema = Schema::new(vec![
+            Field::new("value", data_type, true),
+            Field::new("count", DataType::UInt64, false),
+            Field::new("error", DataType::UInt64, false),
+        ]);
+        let columns: Vec<ArrayRef> = vec![values, Arc::new(counts), Arc::new(errors)];
+        Ok(RecordBatch::try_new(Arc::new(schema), columns)?)
+    }
+
+    /// Returns the values of the given counters, decoded from their row
+    /// format.
+    fn values(&self, counters: &[&Counter]) -> Result<ArrayRef> {
+        let data_type = self.data_type.clone().unwrap_or(DataType::Null);
+        if counters.is_empty() {
+            return Ok(arrow_array::new_empty_array(&data_type));
+        }
+        let converter = RowConverter::new(vec![SortField::new(data_type)])?;
+        let parser = converter.parser();
+        let rows = counters.iter().map(|counter| parser.parse(&counter.value));
+        Ok(converter.convert_rows(rows)?.remove(0))
+    }
+
+    /// Counts the given value the given number of times, evicting the least
+    /// frequent value in case the sketch is full.
+    fn insert(&mut self, value: &[u8], count: u64, error: u64) {
+        match self.find(value) {
+            Ok(index) => self.counters[index].count += count,
+            Err(index) if self.counters.len() < self.capacity => {
+                let value = value.into();
+                self.counters.insert(
+                    index,
+                    Counter {
+                        value,
+                        count,
+                        error,
+                    },
+                );
+            },
+            Err(_) => {
+                let (min_index, min) = self
+                    .counters
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, counter)| counter.count)
+                    .map(|(index, counter)| (index, counter.count))
+                    .expect("the sketch is full");
+                self.counters.remove(min_index);
+                let index = self.find(value).unwrap_err();
+                let (count, error) = (min + count, min + error);
+                let value = value.into();
+                self.counters.insert(
+                    index,
+                    Counter {
+                        value,
+                        count,
+                        error,
+                    },
+                );
+            },
+        }
+    }
+
+    /// Returns the smallest count of a full sketch, as an upper bound on the
+    /// count of any value it doesn't monitor, or else zero.
+    fn min_count(&self) -> u64 {
+        match self.counters.len() < self.capacity {
+            true => 0,
+            false => self
+                .counters
+                .iter()
+                .map(|counter| counter.count)
+                .min()
+                .unwrap_or(0),
+        }
+    }
+
+    fn find(&self, value: &[u8]) -> core::result::Result<usize, usize> {
+        self.counters
+            .binary_search_by(|counter| counter.value.as_slice().cmp(value))
+    }
+}
+
+/// Returns the datatype that values of the given datatype are decoded to
+/// from their row format, e.g., the values' datatype of a dictionary.
+fn row_data_type(data_type: &DataType) -> Result<DataType> {
+    let converter = RowConverter::new(vec![SortField::new(data_type.clone())])?;
+    let rows = converter.convert_columns(&[arrow_array::new_empty_array(data_type)])?;
+    Ok(converter.convert_rows(&rows)?.remove(0).data_type().clone())
+}
+
+/// Returns an error in case the given capacity is zero.
+pub(crate) fn check_capacity(capacity: usize) -> Result<()> {
+    match capacity > 0 {
+        true => Ok(()),
+        false => Err(DataFusionError::Execution(
+            "the capacity of a heavy hitters sketch is zero".into(),
+        )),
+    }
+}
+
+/// The serialized form of a [`HeavyHittersSketch`], validated on
+/// deserialization.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct HeavyHittersSketchData {
+    capacity: usize,
+    data_type: Option<DataType>,
+    /// The monitored values, as a column `value` in the Arrow IPC streaming
+    /// format, or empty without a datatype.
+    values: Vec<u8>,
+    counts: Vec<u64>,
+    errors: Vec<u64>,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for HeavyHittersSketch {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        use serde::ser::Error;
+        let mut values = Vec::new();
+        if let Some(data_type) = &self.data_type {
+            let counters: Vec<&Counter> = self.counters.iter().collect();
+            let schema = Arc::new(Schema::new(vec![Field::new(
+                "value",
+                data_type.clone(),
+                true,
+            )]));
+            let batch = RecordBatch::try_new(
+                schema.clone(),
+                vec![self.values(&counters).map_err(S::Error::custom)?],
+            )
+            .map_err(S::Error::custom)?;
+            let mut writer = arrow_ipc::writer::StreamWriter::try_new(&mut values, &schema)
+                .map_err(S::Error::custom)?;
+            writer.write(&batch).map_err(S::Error::custom)?;
+            writer.finish().map_err(S::Error::custom)?;
+        }
+        HeavyHittersSketchData {
+            capacity: self.capacity,
+            data_type: self.data_type.clone(),
+            values,
+            counts: self.counters.iter().map(|counter| counter.count).collect(),
+            errors: self.counters.iter().map(|counter| counter.error).collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<HeavyHittersSketchData> for HeavyHittersSketch {
+    type Error = alloc::string::String;
+
+    fn try_from(data: HeavyHittersSketchData) -> core::result::Result<Self, Self::Error> {
+        use alloc::string::ToString;
+        let HeavyHittersSketchData {
+            capacity,
+            data_type,
+            values,
+            counts,
+            errors,
+        } = data;
+        if capacity == 0 {
+            return Err("the capacity is zero".into());
+        }
+        if counts.len() != errors.len() {
+            return Err(format!(
+                "a sketch has {} counts but {} errors",
+                counts.len(),
+                errors.len()
+            ));
+        }
+        if counts.len() > capacity {
+            return Err(format!(
+                "a sketch of capacity {capacity} has {} counters",
+                counts.len()
+            ));
+        }
+        if counts
+            .iter()
+            .zip(&errors)
+            .any(|(count, error)| error > count)
+        {
+            return Err("a counter's error exceeds its count".into());
+        }
+
+        let Some(data_type) = data_type else {
+            return match values.is_empty() && counts.is_empty() {
+                true => Ok(Self::with_capacity(capacity)),
+                false => Err("a sketch without a datatype has counters".into()),
+            };
+        };
+        if row_data_type(&data_type).map_err(|error| error.to_string())? != data_type {
+            return Err(format!(
+                "values of type {data_type} aren't retained as such"
+            ));
+        }
+
+        let converter = RowConverter::new(vec![SortField::new(data_type.clone())])
+            .map_err(|error| error.to_string())?;
+        let reader = arrow_ipc::reader::StreamReader::try_new(values.as_slice(), None)
+            .map_err(|error| error.to_string())?;
+        let mut counters = Vec::with_capacity(counts.len());
+        for batch in reader {
+            let batch = batch.map_err(|error| error.to_string())?;
+            let [array] = batch.columns() else {
+                return Err("the values aren't a single column".into());
+            };
+            if array.data_type() != &data_type || array.null_count() > 0 {
+                return Err(format!(
+                    "the values aren't non-null values of type {data_type}"
+                ));
+            }
+            let rows = converter
+                .convert_columns(core::slice::from_ref(array))
+                .map_err(|error| error.to_string())?;
+            for row in rows.iter() {
+                let index = counters.len();
+                let (Some(&count), Some(&error)) = (counts.get(index), errors.get(index)) else {
+                    return Err("a sketch has more values than counts".into());
+                };
+                counters.push(Counter {
+                    value: row.as_ref().into(),
+                    count,
+                    error,
+                });
+            }
+        }
+        if counters.len() != counts.len() {
+            return Err("a sketch has fewer values than counts".into());
+        }
+
+        counters.sort_by(|lhs, rhs| lhs.value.cmp(&rhs.value));
+        if counters
+            .windows(2)
+            .any(|pair| pair[0].value == pair[1].value)
+        {
+            return Err("a sketch has duplicate values".into());
+        }
+
+        Ok(Self {
+            capacity,
+            data_type: Some(data_type),
+            counters,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::boxed::Box;
+    use arrow_array::{
+        DictionaryArray, Int32Array, StringArray,
+        cast::AsArray,
+        types::{Int32Type, UInt64Type},
+    };
+    use core::error::Error;
+
+    #[test]
+    fn test_heavy_hitters_sketch() -> Result<(), Box<dyn Error>> {
+        let mut lhs = HeavyHittersSketch::with_capacity(3);
+        let mut rhs = HeavyHittersSketch::with_capacity(3);
+        let values = |values: &[&str]| Arc::new(StringArray::from(values.to_vec())) as ArrayRef;
+        lhs.update(&values(&["a", "b", "a", "c", "a", "d"]))?;
+        rhs.update(&values(&["b", "a", "b", "b"]))?;
+
+        let top = lhs.top(1)?;
+        assert_eq!(top.column(0).as_string::<i32>().value(0), "a");
+        assert!(top.column(1).as_primitive::<UInt64Type>().value(0) >= 3);
+
+        lhs.merge(&rhs)?;
+        let top = lhs.top(2)?;
+        let values: Vec<&str> = top.column(0).as_string::<i32>().iter().flatten().collect();
+        assert_eq!(values, ["a", "b"]);
+        let counts = top.column(1).as_primitive::<UInt64Type>().values();
+        assert!(counts[0] >= 4 && counts[1] >= 4);
+
+        let empty = HeavyHittersSketch::new().top(10)?;
+        assert_eq!(empty.num_rows(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_heavy_hitters_sketch_ties() -> Result<(), Box<dyn Error>> {
+        let values = |values: &[i32]| Arc::new(Int32Array::from(values.to_vec())) as ArrayRef;
+        for _ in 0..10 {
+            let mut sketch = HeavyHittersSketch::with_capacity(2);
+            sketch.update(&values(&[5, 4, 3, 2, 1, 3]))?;
+            let top = sketch.top(2)?;
+            assert_eq!(top.column(0).as_primitive::<Int32Type>().values(), &[4, 5]);
+            assert_eq!(top.column(1).as_primitive::<UInt64Type>().values(), &[3, 3]);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_heavy_hitters_sketch_dictionary() -> Result<(), Box<dyn Error>> {
+        let mut sketch = HeavyHittersSketch::new();
+        let words: DictionaryArray<Int32Type> = ["a", "b", "a"].into_iter().collect();
+        sketch.update(&(Arc::new(words) as ArrayRef))?;
+        sketch.update(&(Arc::new(StringArray::from(vec!["b", "b"])) as ArrayRef))?;
+        assert_eq!(sketch.data_type(), Some(&DataType::Utf8));
+
+        let top = sketch.top(1)?;
+        assert_eq!(top.column(0).as_string::<i32>().value(0), "b");
+        assert_eq!(top.column(1).as_primitive::<UInt64Type>().value(0), 3);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_heavy_hitters_sketch_serde() -> Result<(), Box<dyn Error>> {
+        use arrow_array::{ListArray, types::Int64Type};
+        use serde_json::{Value, from_value, json, to_value};
+
+        let mut strings = HeavyHittersSketch::with_capacity(3);
+        strings.update(&(Arc::new(StringArray::from(vec!["a", "b", "a", ""])) as ArrayRef))?;
+        strings.update(&(Arc::new(StringArray::from(vec!["x".repeat(100)])) as ArrayRef))?;
+        let mut lists = HeavyHittersSketch::new();
+        let list = ListArray::from_iter_primitive::<Int64Type, _, _>([
+            Some(vec![Some(1), None]),
+            Some(vec![]),
+            Some(vec![Some(1), None]),
+        ]);
+        lists.update(&(Arc::new(list) as ArrayRef))?;
+        for sketch in [strings.clone(), lists, HeavyHittersSketch::new()] {
+            let json = serde_json::to_string(&sketch)?;
+            assert_eq!(serde_json::from_str::<HeavyHittersSketch>(&json)?, sketch);
+        }
+
+        let data = to_value(&strings)?;
+        let with = |key: &str, value: Value| {
+            let mut data = data.clone();
+            data[key] = value;
+            from_value::<HeavyHittersSketch>(data)
+        };
+        let values = |array: ArrayRef| -> Result<Value, Box<dyn Error>> {
+            let schema = Arc::new(Schema::new(vec![Field::new(
+                "value",
+                array.data_type().clone(),
+                true,
+            )]));
+            let mut bytes = Vec::new();
+            let mut writer = arrow_ipc::writer::StreamWriter::try_new(&mut bytes, &schema)?;
+            writer.write(&RecordBatch::try_new(schema, vec![array])?)?;
+            writer.finish()?;
+            Ok(to_value(bytes)?)
+        };
+        assert!(with("capacity", json!(0)).is_err());
+        assert!(with("capacity", json!(2)).is_err()); // too many counters
+        assert!(with("counts", json!([1, 1])).is_err()); // mismatched errors
+        assert!(with("errors", json!([0, 0, 5])).is_err()); // exceeds the count
+        assert!(with("data_type", Value::Null).is_err()); // no datatype
+        assert!(with("data_type", json!("Int64")).is_err()); // mismatched datatype
+        assert!(with("values", json!([1, 2, 3])).is_err()); // invalid IPC
+        let strings = |values: Vec<Option<&str>>| Arc::new(StringArray::from(values)) as ArrayRef;
+        assert!(with("values", values(strings(vec![Some("a"), Some("b")]))?).is_err()); // too few
+        assert!(with("values", values(strings(vec![Some("a"), Some("b"), None]))?).is_err()); // null
+        assert!(
+            with(
+                "values",
+                values(strings(vec![Some("a"), Some("b"), Some("a")]))?
+            )
+            .is_err()
+        ); // duplicate
+        assert!(
+            with(
+                "values",
+                values(strings(vec![Some("c"), Some("b"), Some("a")]))?
+            )
+            .is_ok()
+        );
+
+        Ok(())
+    }
+}
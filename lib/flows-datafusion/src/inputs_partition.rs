@@ -0,0 +1,113 @@
+// This is free and unencumbered software released into the public domain.
+
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use arrow_array::RecordBatch;
+use arrow_schema::SchemaRef;
+use async_flow::Inputs;
+use datafusion::{
+    execution::{SendableRecordBatchStream, TaskContext},
+    physical_plan::{stream::RecordBatchStreamAdapter, streaming::PartitionStream},
+};
+use datafusion_common::{DataFusionError, Result, exec_err};
+use std::sync::Mutex;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// A DataFusion partition that streams the batches received on an input
+/// port, conformed to the partition's schema.
+///
+/// Batches that can't be conformed are sent to `rejects`, if given, instead
+/// of failing the stream. The port can only be streamed once.
+pub(crate) struct InputsPartition {
+    schema: SchemaRef,
+    inputs: Mutex<Option<Inputs<RecordBatch>>>,
+    rejects: Option<UnboundedSender<DataFusionError>>,
+}
+
+impl core::fmt::Debug for InputsPartition {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("InputsPartition")
+            .field("schema", &self.schema)
+            .finish()
+    }
+}
+
+impl InputsPartition {
+    pub(crate) fn new(
+        schema: SchemaRef,
+        inputs: Inputs<RecordBatch>,
+        rejects: Option<UnboundedSender<DataFusionError>>,
+    ) -> Self {
+        Self {
+            schema,
+            inputs: Mutex::new(Some(inputs)),
+            rejects,
+        }
+    }
+}
+
+impl PartitionStream for InputsPartition {
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    fn execute(&self, _ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+        let schema = self.schema.clone();
+        let inputs = self.inputs.lock().ok().and_then(|mut inputs| inputs.take());
+        let Some(inputs) = inputs else {
+            let error = exec_err!("the input port has already been streamed");
+            return Box::pin(RecordBatchStreamAdapter::new(
+                schema,
+                futures::stream::once(async { error }),
+            ));
+        };
+
+        let rejects = self.rejects.clone();
+        let stream =
+            futures::stream::unfold((inputs, schema.clone()), move |(mut inputs, schema)| {
+                let rejects = rejects.clone();
+                async move {
+                    loop {
+                        match inputs.recv().await {
+                            Ok(Some(batch)) => match (conform(batch, &schema), &rejects) {
+                                (Ok(batch), _) => return Some((Ok(batch), (inputs, schema))),
+                                (Err(error), Some(rejects)) => {
+                                    let _ = rejects.send(error);
+                                },
+                                (Err(error), None) => return Some((Err(error), (inputs, schema))),
+                            },
+                            Ok(None) => return None,
+                            Err(error) => {
+                                let error = DataFusionError::External(Box::new(error));
+                                return Some((Err(error), (inputs, schema)));
+                            },
+                        }
+                    }
+                }
+            });
+        Box::pin(RecordBatchStreamAdapter::new(self.schema.clone(), stream))
+    }
+}
+
+/// Conforms the given batch to the given schema, selecting its columns by
+/// name, which must have the expected datatypes.
+fn conform(batch: RecordBatch, schema: &SchemaRef) -> Result<RecordBatch> {
+    if batch.schema_ref() == schema {
+        return Ok(batch);
+    }
+
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| match batch.column_by_name(field.name()) {
+            Some(column) if column.data_type() == field.data_type() => Ok(column.clone()),
+            Some(column) => exec_err!(
+                "column `{}` has datatype {}, expected {}",
+                field.name(),
+                column.data_type(),
+                field.data_type()
+            ),
+            None => exec_err!("column `{}` is missing", field.name()),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(RecordBatch::try_new(schema.clone(), columns)?)
+}
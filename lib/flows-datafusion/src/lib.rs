@@ -11,14 +11,84 @@ extern crate std;
 
 pub use flows_arrow::*;
 
+mod accumulate;
+
+mod aggregate;
+pub use aggregate::*;
+
+mod approx_distinct_column;
+pub use approx_distinct_column::*;
+
 mod avg_column;
 pub use avg_column::*;
 
+mod column_ref;
+pub use column_ref::*;
+
+mod count_distinct_column;
+pub use count_distinct_column::*;
+
+mod describe_columns;
+pub use describe_columns::*;
+
+mod distinct_sketch;
+pub use distinct_sketch::*;
+
+#[cfg(feature = "sql")]
+mod execute;
+
+mod group_by;
+pub use group_by::*;
+
+mod grouper;
+
+mod heavy_hitters_column;
+pub use heavy_hitters_column::*;
+
+mod heavy_hitters_sketch;
+pub use heavy_hitters_sketch::*;
+
+#[cfg(feature = "sql")]
+mod inputs_partition;
+
 mod max_column;
 pub use max_column::*;
 
+mod median_column;
+pub use median_column::*;
+
 mod min_column;
 pub use min_column::*;
 
+mod percentile_column;
+pub use percentile_column::*;
+
+mod percentile_sketch;
+pub use percentile_sketch::*;
+
+#[cfg(feature = "sql")]
+mod query_plan;
+#[cfg(feature = "sql")]
+pub use query_plan::*;
+
+#[cfg(feature = "sql")]
+mod sql_query;
+#[cfg(feature = "sql")]
+pub use sql_query::*;
+
+mod running;
+pub use running::*;
+
+mod stddev_column;
+pub use stddev_column::*;
+
+#[cfg(feature = "sql")]
+mod stream_table;
+#[cfg(feature = "sql")]
+pub use stream_table::*;
+
 mod sum_column;
 pub use sum_column::*;
+
+mod variance_column;
+pub use variance_column::*;
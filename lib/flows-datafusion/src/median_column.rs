@@ -0,0 +1,130 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{
+    ColumnRef, PercentileSketch, Running,
+    percentile_column::{percentile_result, sketch_batch},
+    running::{Event, Ticker},
+};
+use alloc::vec::Vec;
+use arrow_array::RecordBatch;
+use async_flow::{Inputs, Output, Outputs, Port, Result};
+use datafusion_common::ScalarValue;
+use flows_derive::block;
+use flows_error::BlockError;
+
+/// A block that outputs an approximation of the median of the values in a
+/// given column.
+///
+/// See `percentile_column` for how the values are summarized.
+/// Batches that lack the specified column, or whose column is non-numeric, are
+/// reported on the `errors` port.
+/// Outputs `ScalarValue::Null` in case the column has no non-null values.
+/// Running medians are output on the `running` port as specified by `every`.
+#[block]
+pub async fn median_column(
+    column: ColumnRef,
+    every: Running,
+    mut inputs: Inputs<RecordBatch>,
+    output: Output<ScalarValue>,
+    running: Outputs<ScalarValue>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    let mut sketch = PercentileSketch::new();
+    let mut ticker = Ticker::start(every, "median_column", &errors).await?;
+
+    while let Some(event) = ticker.recv(&mut inputs).await? {
+        let input = match event {
+            Event::Input(input) => input,
+            Event::Tick => {
+                if running.is_connected()
+                    && let Ok(value) = percentile_result(&sketch, 0.5)
+                {
+                    running.send(value).await?;
+                }
+                continue;
+            },
+        };
+
+        if let Err(error) = sketch_batch(&mut sketch, &input, &column)
+            && errors.is_connected()
+        {
+            errors
+                .send(BlockError::from_error("median_column", error))
+                .await?;
+        }
+    }
+
+    if let Ok(result) = percentile_result(&sketch, 0.5)
+        && !output.is_closed()
+    {
+        output.send(result).await?;
+    }
+
+    Ok(())
+}
+
+/// A block that outputs an approximation of the median of the values in a
+/// given column for each input window of batches.
+///
+/// Batches that lack the specified column, or whose column is non-numeric, are
+/// reported on the `errors` port.
+/// Outputs `ScalarValue::Null` in case the column has no non-null values.
+#[block]
+pub async fn median_column_windows(
+    column: ColumnRef,
+    mut inputs: Inputs<Vec<RecordBatch>>,
+    outputs: Outputs<ScalarValue>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    while let Some(window) = inputs.recv().await? {
+        let mut sketch = PercentileSketch::new();
+
+        for input in &window {
+            if let Err(error) = sketch_batch(&mut sketch, input, &column)
+                && errors.is_connected()
+            {
+                errors
+                    .send(BlockError::from_error("median_column_windows", error))
+                    .await?;
+            }
+        }
+
+        if let Ok(result) = percentile_result(&sketch, 0.5)
+            && !outputs.is_closed()
+        {
+            outputs.send(result).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use arrow_array::record_batch;
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_median_column_windows() -> Result<(), Box<dyn Error>> {
+        let (mut in_, mut out) = (Channel::bounded(10), Channel::bounded(10));
+        let err = Channel::bounded(10);
+        let median = tokio::spawn(median_column_windows(0.into(), in_.rx, out.tx, err.tx));
+
+        let batch = record_batch!(("a", Int32, [7, 1, 3]))?;
+        in_.tx.send(vec![batch.clone()]).await?;
+        in_.tx.send(vec![batch.slice(0, 0)]).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(median);
+
+        assert_eq!(
+            out.rx.recv_all().await?,
+            vec![ScalarValue::from(3.0), ScalarValue::Null]
+        );
+
+        Ok(())
+    }
+}
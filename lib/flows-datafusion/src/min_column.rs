@@ -1,35 +1,54 @@
 // This is free and unencumbered software released into the public domain.
 
+use super::{
+    ColumnRef, Running,
+    running::{Event, Ticker},
+};
+use alloc::{format, vec::Vec};
 use arrow_array::{ArrayRef, RecordBatch};
-use async_flow::{Inputs, Output, Port, Result};
-use datafusion_common::ScalarValue;
+use async_flow::{Inputs, Output, Outputs, Port, Result};
+use datafusion_common::{DataFusionError, ScalarValue};
 use flows_derive::block;
+use flows_error::BlockError;
 
 /// A block that outputs the minimum of the values in a given column.
 ///
-/// Panics in case the specified column index is out of bounds.
-/// Outputs `ScalarValue::Null` in case the specified column has a non-numeric
-/// datatype.
+/// Supports numeric (including decimal), temporal, string, binary and boolean
+/// columns. The first batch with non-null values determines the datatype of
+/// the minimum. Batches that lack the specified column, whose column has an
+/// unsupported datatype, or whose minimum doesn't convert exactly to that
+/// datatype, are reported on the `errors` port.
+/// Outputs `ScalarValue::Null` in case the column has no non-null values.
+/// Running minimums are output on the `running` port as specified by `every`.
 #[block]
 pub async fn min_column(
-    column: usize,
+    column: ColumnRef,
+    every: Running,
     mut inputs: Inputs<RecordBatch>,
     output: Output<ScalarValue>,
+    running: Outputs<ScalarValue>,
+    errors: Outputs<BlockError>,
 ) -> Result {
     let mut result: ScalarValue = ScalarValue::Null;
+    let mut ticker = Ticker::start(every, "min_column", &errors).await?;
 
-    while let Some(input) = inputs.recv().await? {
-        if input.num_rows() == 0 {
-            continue; // skip empty batches
-        }
-
-        let column_array = input.column(column);
-        let Some(column_min) = min_array(column_array) else {
-            continue; // skip unsupported datatypes
+    while let Some(event) = ticker.recv(&mut inputs).await? {
+        let input = match event {
+            Event::Input(input) => input,
+            Event::Tick => {
+                if running.is_connected() {
+                    running.send(result.clone()).await?;
+                }
+                continue;
+            },
         };
 
-        if result.is_null() || column_min < result {
-            result = column_min;
+        if let Err(error) = min_batch(&mut result, &input, &column)
+            && errors.is_connected()
+        {
+            errors
+                .send(BlockError::from_error("min_column", error))
+                .await?;
         }
     }
 
@@ -40,32 +59,116 @@ pub async fn min_column(
     Ok(())
 }
 
-pub fn min_array(array: &ArrayRef) -> Option<ScalarValue> {
+/// A block that outputs the minimum of the values in a given column for each
+/// input window of batches.
+///
+/// Batches that lack the specified column, whose column has an unsupported
+/// datatype, or whose minimum doesn't convert exactly to the datatype of the
+/// window's first minimum, are reported on the `errors` port.
+/// Outputs `ScalarValue::Null` in case the column has no non-null values.
+#[block]
+pub async fn min_column_windows(
+    column: ColumnRef,
+    mut inputs: Inputs<Vec<RecordBatch>>,
+    outputs: Outputs<ScalarValue>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    while let Some(window) = inputs.recv().await? {
+        let mut result: ScalarValue = ScalarValue::Null;
+
+        for input in &window {
+            if let Err(error) = min_batch(&mut result, input, &column)
+                && errors.is_connected()
+            {
+                errors
+                    .send(BlockError::from_error("min_column_windows", error))
+                    .await?;
+            }
+        }
+
+        if !outputs.is_closed() {
+            outputs.send(result).await?;
+        }
+    }
+
+    Ok(())
+}
+
+fn min_batch(
+    result: &mut ScalarValue,
+    input: &RecordBatch,
+    column: &ColumnRef,
+) -> datafusion_common::Result<()> {
+    if input.num_rows() == 0 {
+        return Ok(()); // skip empty batches
+    }
+
+    let column_array = column.resolve(input)?;
+    let column_min = min_array(column_array)?;
+    if column_min.is_null() {
+        return Ok(()); // skip null-only batches
+    }
+
+    let column_min = coerce_scalar(column_min, result)?;
+    if result.is_null() || column_min < *result {
+        *result = column_min;
+    }
+    Ok(())
+}
+
+/// Converts the given value to the datatype of the given running result,
+/// unless the result is null, or returns an error in case the value doesn't
+/// convert exactly to that datatype.
+pub(crate) fn coerce_scalar(
+    value: ScalarValue,
+    result: &ScalarValue,
+) -> datafusion_common::Result<ScalarValue> {
+    let (value_type, result_type) = (value.data_type(), result.data_type());
+    if result.is_null() || value_type == result_type {
+        return Ok(value);
+    }
+    let converted = value.cast_to(&result_type)?;
+    match converted.cast_to(&value_type)? == value {
+        true => Ok(converted),
+        false => Err(DataFusionError::Execution(format!(
+            "values of type {value_type} don't convert exactly to {result_type}"
+        ))),
+    }
+}
+
+/// Returns the minimum of the values of the given array, or an error in case
+/// the array has an unsupported datatype.
+pub fn min_array(array: &ArrayRef) -> datafusion_common::Result<ScalarValue> {
     use arrow_arith::aggregate::min;
     use arrow_array::{cast::AsArray, types::*};
     use arrow_schema::DataType::*;
-    Some(match array.data_type() {
-        Int8 => ScalarValue::from(min(array.as_primitive::<Int8Type>())),
-        Int16 => ScalarValue::from(min(array.as_primitive::<Int16Type>())),
-        Int32 => ScalarValue::from(min(array.as_primitive::<Int32Type>())),
-        Int64 => ScalarValue::from(min(array.as_primitive::<Int64Type>())),
-        UInt8 => ScalarValue::from(min(array.as_primitive::<UInt8Type>())),
-        UInt16 => ScalarValue::from(min(array.as_primitive::<UInt16Type>())),
-        UInt32 => ScalarValue::from(min(array.as_primitive::<UInt32Type>())),
-        UInt64 => ScalarValue::from(min(array.as_primitive::<UInt64Type>())),
-        Float16 => ScalarValue::from(min(array.as_primitive::<Float16Type>())),
-        Float32 => ScalarValue::from(min(array.as_primitive::<Float32Type>())),
-        Float64 => ScalarValue::from(min(array.as_primitive::<Float64Type>())),
-        // TODO: Decimal32, Decimal64, Decimal128, Decimal256
-        _ => return None,
-    })
+    use datafusion_functions_aggregate_common::min_max::min_batch;
+    match array.data_type() {
+        Decimal32(precision, scale) => Ok(ScalarValue::Decimal32(
+            min(array.as_primitive::<Decimal32Type>()),
+            *precision,
+            *scale,
+        )),
+        Decimal64(precision, scale) => Ok(ScalarValue::Decimal64(
+            min(array.as_primitive::<Decimal64Type>()),
+            *precision,
+            *scale,
+        )),
+        Dictionary(_, value_type) => min_array(&arrow_cast::cast(array, value_type)?),
+        data_type if data_type.is_numeric() || data_type.is_temporal() => min_batch(array),
+        Boolean | Utf8 | LargeUtf8 | Utf8View | Binary | LargeBinary | BinaryView
+        | FixedSizeBinary(_) => min_batch(array),
+        data_type => Err(DataFusionError::Execution(format!(
+            "can't take the minimum of values of type {data_type}"
+        ))),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use alloc::{boxed::Box, vec};
-    use arrow_array::record_batch;
+    use alloc::{boxed::Box, sync::Arc, vec};
+    use arrow_array::{DictionaryArray, Int8Array, Int32Array, record_batch};
     use async_flow::{Channel, InputPort};
     use core::error::Error;
 
@@ -73,7 +176,15 @@ mod tests {
     async fn test_min_column_i32() -> Result<(), Box<dyn Error>> {
         let mut in_ = Channel::bounded(10);
         let mut out = Channel::oneshot();
-        let minner = tokio::spawn(min_column(0, in_.rx, out.tx));
+        let mut err = Channel::bounded(10);
+        let minner = tokio::spawn(min_column(
+            0.into(),
+            Running::Never,
+            in_.rx,
+            out.tx,
+            Channel::bounded(1).tx,
+            err.tx,
+        ));
 
         in_.tx.send(sample_data()).await?;
         in_.tx.send(sample_data()).await?;
@@ -85,6 +196,115 @@ mod tests {
         assert_eq!(outputs.len(), 1);
         assert_eq!(outputs[0], ScalarValue::from(1i32));
 
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_min_column_windows() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::bounded(10);
+        let mut err = Channel::bounded(10);
+        let minner = tokio::spawn(min_column_windows(0.into(), in_.rx, out.tx, err.tx));
+
+        in_.tx.send(vec![sample_data(), sample_data()]).await?;
+        in_.tx.send(vec![sample_data()]).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(minner);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(
+            outputs,
+            vec![ScalarValue::from(1i32), ScalarValue::from(1i32)]
+        );
+
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_min_column_non_numeric() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::bounded(10);
+        let mut err = Channel::bounded(10);
+        let minner = tokio::spawn(min_column_windows(0.into(), in_.rx, out.tx, err.tx));
+
+        let dates = RecordBatch::try_from_iter([(
+            "d",
+            alloc::sync::Arc::new(arrow_array::Date32Array::from(vec![
+                Some(19_000),
+                None,
+                Some(18_000),
+            ])) as ArrayRef,
+        )])?;
+        let flags = record_batch!(("f", Boolean, [true, false, true]))?;
+        let nulls = RecordBatch::try_from_iter([(
+            "n",
+            alloc::sync::Arc::new(arrow_array::NullArray::new(1)) as ArrayRef,
+        )])?;
+        in_.tx.send(vec![sample_data().project(&[2])?]).await?;
+        in_.tx.send(vec![dates]).await?;
+        in_.tx.send(vec![flags]).await?;
+        in_.tx.send(vec![nulls]).await?; // unsupported
+        in_.tx.close();
+
+        let _ = tokio::join!(minner);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(
+            outputs,
+            vec![
+                ScalarValue::from(""),
+                ScalarValue::Date32(Some(18_000)),
+                ScalarValue::from(false),
+                ScalarValue::Null,
+            ]
+        );
+
+        let errors = err.rx.recv_all().await?;
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].block(), "min_column_windows");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_min_column_mixed_types() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::oneshot();
+        let mut err = Channel::bounded(10);
+        let miner = tokio::spawn(min_column(
+            0.into(),
+            Running::Never,
+            in_.rx,
+            out.tx,
+            Channel::bounded(1).tx,
+            err.tx,
+        ));
+
+        let keys = Int8Array::from(vec![0, 1, 2, 3]);
+        let values = Arc::new(Int32Array::from(vec![-10, 3, 4, 10]));
+        let dictionary: ArrayRef = Arc::new(DictionaryArray::new(keys, values).slice(1, 2)); // 3, 4
+        in_.tx
+            .send(RecordBatch::try_from_iter([("a", dictionary)])?)
+            .await?;
+        in_.tx.send(record_batch!(("a", Int64, [2, 6]))?).await?;
+        in_.tx
+            .send(record_batch!(("a", Float64, [1.5, 6.5]))?)
+            .await?; // inexact
+        in_.tx
+            .send(record_batch!(("a", Int64, [-(1 << 40), 1 << 40]))?)
+            .await?; // overflows
+        in_.tx.close();
+
+        let _ = tokio::join!(miner);
+
+        assert_eq!(out.rx.recv_all().await?, vec![ScalarValue::from(2i32)]);
+        assert_eq!(err.rx.recv_all().await?.len(), 2);
+
         Ok(())
     }
 
@@ -0,0 +1,228 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{
+    ColumnRef, PercentileSketch, Running,
+    percentile_sketch::check_percentile,
+    running::{Event, Ticker},
+};
+use alloc::vec::Vec;
+use arrow_array::RecordBatch;
+use async_flow::{Inputs, Output, Outputs, Port, Result};
+use datafusion_common::ScalarValue;
+use flows_derive::block;
+use flows_error::BlockError;
+
+/// A block that outputs an approximation of the given percentile, as a
+/// fraction between 0 and 1, of the values in a given column.
+///
+/// The values are summarized in a [`PercentileSketch`], so memory use stays
+/// bounded regardless of the number of input batches.
+///
+/// Batches that lack the specified column, or whose column is non-numeric, are
+/// reported on the `errors` port. A percentile outside of the valid range is
+/// reported there as well, after which the block exits.
+/// Outputs `ScalarValue::Null` in case the column has no non-null values.
+/// Running percentiles are output on the `running` port as specified by
+/// `every`.
+#[block]
+pub async fn percentile_column(
+    column: ColumnRef,
+    percentile: f64,
+    every: Running,
+    mut inputs: Inputs<RecordBatch>,
+    output: Output<ScalarValue>,
+    running: Outputs<ScalarValue>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    if let Err(error) = check_percentile(percentile) {
+        if errors.is_connected() {
+            errors
+                .send(BlockError::from_error("percentile_column", error))
+                .await?;
+        }
+        return Ok(());
+    }
+
+    let mut sketch = PercentileSketch::new();
+    let mut ticker = Ticker::start(every, "percentile_column", &errors).await?;
+
+    while let Some(event) = ticker.recv(&mut inputs).await? {
+        let input = match event {
+            Event::Input(input) => input,
+            Event::Tick => {
+                if running.is_connected()
+                    && let Ok(value) = percentile_result(&sketch, percentile)
+                {
+                    running.send(value).await?;
+                }
+                continue;
+            },
+        };
+
+        if let Err(error) = sketch_batch(&mut sketch, &input, &column)
+            && errors.is_connected()
+        {
+            errors
+                .send(BlockError::from_error("percentile_column", error))
+                .await?;
+        }
+    }
+
+    match percentile_result(&sketch, percentile) {
+        Ok(result) if !output.is_closed() => output.send(result).await?,
+        Err(error) if errors.is_connected() => {
+            errors
+                .send(BlockError::from_error("percentile_column", error))
+                .await?
+        },
+        _ => {},
+    }
+
+    Ok(())
+}
+
+/// A block that outputs an approximation of the given percentile, as a
+/// fraction between 0 and 1, of the values in a given column for each input
+/// window of batches.
+///
+/// Batches that lack the specified column, or whose column is non-numeric, are
+/// reported on the `errors` port. A percentile outside of the valid range is
+/// reported there as well, after which the block exits.
+/// Outputs `ScalarValue::Null` in case the column has no non-null values.
+#[block]
+pub async fn percentile_column_windows(
+    column: ColumnRef,
+    percentile: f64,
+    mut inputs: Inputs<Vec<RecordBatch>>,
+    outputs: Outputs<ScalarValue>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    if let Err(error) = check_percentile(percentile) {
+        if errors.is_connected() {
+            errors
+                .send(BlockError::from_error("percentile_column_windows", error))
+                .await?;
+        }
+        return Ok(());
+    }
+
+    while let Some(window) = inputs.recv().await? {
+        let mut sketch = PercentileSketch::new();
+
+        for input in &window {
+            if let Err(error) = sketch_batch(&mut sketch, input, &column)
+                && errors.is_connected()
+            {
+                errors
+                    .send(BlockError::from_error("percentile_column_windows", error))
+                    .await?;
+            }
+        }
+
+        match percentile_result(&sketch, percentile) {
+            Ok(result) if !outputs.is_closed() => outputs.send(result).await?,
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("percentile_column_windows", error))
+                    .await?
+            },
+            _ => continue,
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn sketch_batch(
+    sketch: &mut PercentileSketch,
+    input: &RecordBatch,
+    column: &ColumnRef,
+) -> datafusion_common::Result<()> {
+    if input.num_rows() == 0 {
+        return Ok(()); // skip empty batches
+    }
+
+    let column_array = column.resolve(input)?;
+    sketch.update(column_array)
+}
+
+pub(crate) fn percentile_result(
+    sketch: &PercentileSketch,
+    percentile: f64,
+) -> datafusion_common::Result<ScalarValue> {
+    Ok(match sketch.percentile(percentile)? {
+        Some(value) => ScalarValue::from(value),
+        None => ScalarValue::Null,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use arrow_array::record_batch;
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_percentile_column() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::oneshot();
+        let mut err = Channel::bounded(10);
+        let block = tokio::spawn(percentile_column(
+            0.into(),
+            1.0,
+            Running::Never,
+            in_.rx,
+            out.tx,
+            Channel::bounded(1).tx,
+            err.tx,
+        ));
+
+        in_.tx.send(sample_data()).await?;
+        in_.tx.send(record_batch!(("c", Utf8, ["x"]))?).await?; // non-numeric
+        in_.tx.close();
+
+        let _ = tokio::join!(block);
+
+        assert_eq!(out.rx.recv_all().await?, vec![ScalarValue::from(5.0)]);
+        assert_eq!(err.rx.recv_all().await?.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_percentile_column_out_of_range() -> Result<(), Box<dyn Error>> {
+        let in_ = Channel::bounded(10);
+        let (mut out, mut run) = (Channel::oneshot(), Channel::bounded(10));
+        let mut err = Channel::bounded(10);
+        let block = tokio::spawn(percentile_column(
+            0.into(),
+            1.5,
+            Running::Batches(1),
+            in_.rx,
+            out.tx,
+            run.tx,
+            err.tx,
+        ));
+
+        // Reported without waiting for the input to close:
+        let _ = tokio::join!(block);
+
+        assert!(out.rx.recv_all().await?.is_empty());
+        assert!(run.rx.recv_all().await?.is_empty());
+        assert_eq!(err.rx.recv_all().await?.len(), 1);
+        drop(in_.tx);
+
+        Ok(())
+    }
+
+    fn sample_data() -> RecordBatch {
+        record_batch!(
+            ("a", Int32, [1, 2, 3, 4, 5]),
+            ("b", Float64, [Some(4.0), None, Some(5.0), None, None]),
+            ("c", Utf8, ["alpha", "beta", "gamma", "", ""])
+        )
+        .unwrap()
+    }
+}
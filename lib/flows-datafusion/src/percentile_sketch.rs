@@ -0,0 +1,116 @@
+// This is free and unencumbered software released into the public domain.
+
+use alloc::{format, vec::Vec};
+use arrow_array::{ArrayRef, cast::AsArray, types::Float64Type};
+use arrow_schema::DataType;
+use datafusion_common::{DataFusionError, Result};
+use datafusion_functions_aggregate_common::tdigest::{DEFAULT_MAX_SIZE, TDigest};
+
+/// A sketch of the distribution of numeric values for estimating their
+/// percentiles in bounded memory, based on DataFusion's t-digest.
+///
+/// Sketches of disjoint sets of values can be merged, e.g., to combine the
+/// partial results of parallel partitions of a stream.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PercentileSketch {
+    digest: TDigest,
+}
+
+impl Default for PercentileSketch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PercentileSketch {
+    /// Creates an empty sketch of the default size.
+    pub fn new() -> Self {
+        Self::with_max_size(DEFAULT_MAX_SIZE)
+    }
+
+    /// Creates an empty sketch of (at most) the given number of centroids,
+    /// with more centroids trading memory for accuracy.
+    pub fn with_max_size(max_size: usize) -> Self {
+        Self {
+            digest: TDigest::new(max_size),
+        }
+    }
+
+    /// Returns the number of values added to the sketch.
+    pub fn count(&self) -> u64 {
+        self.digest.count()
+    }
+
+    /// Adds the non-null, non-NaN values of the given numeric array.
+    pub fn update(&mut self, array: &ArrayRef) -> Result<()> {
+        if !array.data_type().is_numeric() {
+            return Err(DataFusionError::Execution(format!(
+                "expected a numeric column, got {}",
+                array.data_type()
+            )));
+        }
+        let array = arrow_cast::cast(array, &DataType::Float64)?;
+        let values: Vec<f64> = array
+            .as_primitive::<Float64Type>()
+            .iter()
+            .flatten()
+            .filter(|value| !value.is_nan())
+            .collect();
+        if !values.is_empty() {
+            self.digest = self.digest.merge_unsorted_f64(values);
+        }
+        Ok(())
+    }
+
+    /// Adds the values summarized by the given sketch.
+    pub fn merge(&mut self, other: &PercentileSketch) {
+        if other.count() > 0 {
+            self.digest = TDigest::merge_digests([&self.digest, &other.digest]);
+        }
+    }
+
+    /// Returns the estimated value at the given percentile, as a fraction
+    /// between 0 and 1, or `None` if the sketch is empty.
+    pub fn percentile(&self, percentile: f64) -> Result<Option<f64>> {
+        check_percentile(percentile)?;
+        Ok(match self.count() {
+            0 => None,
+            _ => Some(self.digest.estimate_quantile(percentile)),
+        })
+    }
+}
+
+/// Returns an error in case the given percentile isn't between 0 and 1.
+pub(crate) fn check_percentile(percentile: f64) -> Result<()> {
+    match (0.0..=1.0).contains(&percentile) {
+        true => Ok(()),
+        false => Err(DataFusionError::Execution(format!(
+            "percentile {percentile} is not between 0 and 1"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, sync::Arc};
+    use arrow_array::Int32Array;
+    use core::error::Error;
+
+    #[test]
+    fn test_percentile_sketch() -> Result<(), Box<dyn Error>> {
+        let (mut lhs, mut rhs) = (PercentileSketch::new(), PercentileSketch::new());
+        lhs.update(&(Arc::new(Int32Array::from_iter_values(1..=500)) as ArrayRef))?;
+        rhs.update(&(Arc::new(Int32Array::from_iter_values(501..=1000)) as ArrayRef))?;
+        assert_eq!(rhs.percentile(0.0)?, Some(501.0));
+
+        lhs.merge(&rhs);
+        assert_eq!(lhs.count(), 1000);
+        let median = lhs.percentile(0.5)?.unwrap();
+        assert!((490.0..=510.0).contains(&median));
+        assert!(lhs.percentile(1.5).is_err());
+        assert_eq!(PercentileSketch::new().percentile(0.5)?, None);
+
+        Ok(())
+    }
+}
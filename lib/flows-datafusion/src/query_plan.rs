@@ -0,0 +1,384 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{
+    SqlQuery,
+    execute::{bind_tables, execute_operator, execute_plan, resolve_table, session_context},
+};
+use alloc::{string::String, sync::Arc, vec::Vec};
+use arrow_array::RecordBatch;
+use arrow_schema::SchemaRef;
+use async_flow::{Inputs, Outputs, Port, Result, System};
+use datafusion::{
+    common::{TableReference, tree_node::TreeNodeRecursion},
+    datasource::empty::EmptyTable,
+    execution::context::{SQLOptions, SessionContext},
+    logical_expr::LogicalPlan,
+    physical_plan::ExecutionPlan,
+};
+use datafusion_common::plan_err;
+use flows_derive::block;
+use flows_error::BlockError;
+use futures::{StreamExt, stream};
+use tokio::sync::mpsc::unbounded_channel;
+
+/// A DataFusion logical plan over one or more named tables, e.g., as built
+/// by a query builder or consumed from Substrait.
+///
+/// Only queries are supported, not DDL, DML or other statements, and each
+/// table can only be scanned once, as it's read from a single input.
+#[derive(Clone, Debug)]
+pub struct QueryPlan {
+    tables: Vec<(TableReference, SchemaRef)>,
+    plan: LogicalPlan,
+}
+
+impl QueryPlan {
+    /// Wraps the given logical plan, whose tables are the tables it scans,
+    /// in order of appearance.
+    ///
+    /// Plans that scan a table more than once, e.g., self-joins, are an
+    /// error. Tables are told apart by their full references, with `a.t` and
+    /// `b.t` being different tables.
+    pub fn new(plan: LogicalPlan) -> datafusion_common::Result<Self> {
+        SQLOptions::new()
+            .with_allow_ddl(false)
+            .with_allow_dml(false)
+            .with_allow_statements(false)
+            .verify_plan(&plan)?;
+
+        let tables = scanned_tables(&plan)?;
+        Ok(Self { tables, plan })
+    }
+
+    /// Consumes the given Substrait plan over the given named tables of the
+    /// given schemas.
+    #[cfg(feature = "substrait")]
+    pub fn from_substrait<S: Into<String>>(
+        plan: &datafusion_substrait::substrait::proto::Plan,
+        tables: impl IntoIterator<Item = (S, SchemaRef)>,
+    ) -> datafusion_common::Result<Self> {
+        use datafusion_substrait::logical_plan::consumer::from_substrait_plan;
+        use futures::FutureExt;
+
+        let tables: Vec<(String, SchemaRef)> = tables
+            .into_iter()
+            .map(|(name, schema)| (name.into(), schema))
+            .collect();
+        let context = catalog_context(&tables)?;
+
+        // Consuming only awaits the in-memory catalog, so completes at once:
+        let Some(plan) = from_substrait_plan(&context.state(), plan).now_or_never() else {
+            return plan_err!("failed to consume the Substrait plan");
+        };
+        Self::new(plan?)
+    }
+
+    /// Returns the plan's tables and their schemas.
+    pub fn tables(&self) -> &[(TableReference, SchemaRef)] {
+        &self.tables
+    }
+
+    /// Returns the schema of the plan's result batches.
+    pub fn schema(&self) -> SchemaRef {
+        self.plan.schema().inner().clone()
+    }
+
+    /// Returns the (unoptimized) logical plan.
+    pub fn plan(&self) -> &LogicalPlan {
+        &self.plan
+    }
+}
+
+impl From<SqlQuery> for QueryPlan {
+    fn from(query: SqlQuery) -> Self {
+        Self {
+            tables: query
+                .tables()
+                .iter()
+                .map(|(name, schema)| (TableReference::bare(name.as_str()), schema.clone()))
+                .collect(),
+            plan: query.plan().clone(),
+        }
+    }
+}
+
+/// Returns the tables scanned by the given logical plan, in order of
+/// appearance, or an error in case a table is scanned more than once.
+pub(crate) fn scanned_tables(
+    plan: &LogicalPlan,
+) -> datafusion_common::Result<Vec<(TableReference, SchemaRef)>> {
+    let mut tables: Vec<(TableReference, SchemaRef)> = Vec::new();
+    plan.apply_with_subqueries(|node| {
+        if let LogicalPlan::TableScan(scan) = node {
+            let reference = resolve_table(&scan.table_name);
+            if tables
+                .iter()
+                .any(|(table, _)| resolve_table(table) == reference)
+            {
+                return plan_err!("table `{}` can only be scanned once", scan.table_name);
+            }
+            tables.push((scan.table_name.clone(), scan.source.schema()));
+        }
+        Ok(TreeNodeRecursion::Continue)
+    })?;
+    Ok(tables)
+}
+
+/// Returns a session whose catalog has the given named tables of the given
+/// schemas, without any data, for planning queries over them.
+pub(crate) fn catalog_context(
+    tables: &[(String, SchemaRef)],
+) -> datafusion_common::Result<SessionContext> {
+    let context = SessionContext::new();
+    for (name, schema) in tables {
+        let table = EmptyTable::new(schema.clone());
+        context.register_table(TableReference::bare(name.as_str()), Arc::new(table))?;
+    }
+    Ok(context)
+}
+
+/// A block that executes a logical plan over multiple inputs, each the
+/// plan's table of the same position, and outputs the result batches.
+///
+/// See `sql_query` for how input batches and errors are handled.
+#[block]
+pub async fn query_plan(
+    plan: QueryPlan,
+    inputs: Vec<Inputs<RecordBatch>>,
+    outputs: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    if inputs.len() != plan.tables().len() {
+        if errors.is_connected() {
+            let message = "expected an input for each of the plan's tables";
+            errors.send(BlockError::new("query_plan", message)).await?;
+        }
+        return Ok(());
+    }
+
+    let tables = plan
+        .tables()
+        .iter()
+        .map(|(name, _)| name.clone())
+        .zip(inputs)
+        .collect();
+    execute_plan("query_plan", plan.plan, tables, &outputs, &errors).await
+}
+
+/// A block that executes a single operator of a physical plan, with each of
+/// its children replaced by the input of the same position, and outputs the
+/// result batches.
+///
+/// Operators with multiple output partitions have their partitions merged.
+/// Errors during the operator's execution, which end it, are reported on the
+/// `errors` port.
+#[block]
+pub async fn execution_plan(
+    plan: Arc<dyn ExecutionPlan>,
+    inputs: Vec<Inputs<RecordBatch>>,
+    outputs: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    execute_operator("execution_plan", plan, inputs, &outputs, &errors).await
+}
+
+/// Plans the physical execution of the given logical plan over the given
+/// inputs, each the plan's table of the same position, and spawns it on the
+/// given system as an `execution_plan` block per physical operator.
+///
+/// The root operator's result batches are sent on `outputs`, and the errors
+/// of all operators are reported on `errors`. An operator that fails ends
+/// its output early, so its downstream operators complete with partial
+/// results.
+pub async fn split_plan(
+    system: &mut System,
+    plan: QueryPlan,
+    inputs: Vec<Inputs<RecordBatch>>,
+    outputs: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> datafusion_common::Result<()> {
+    if inputs.len() != plan.tables().len() {
+        return plan_err!("expected an input for each of the plan's tables");
+    }
+
+    let tables = plan
+        .tables()
+        .iter()
+        .map(|(name, _)| name.clone())
+        .zip(inputs)
+        .collect();
+    let (rejects_tx, rejects) = unbounded_channel();
+    let logical_plan = bind_tables(plan.plan, tables, &rejects_tx)?;
+    let physical_plan = session_context()
+        .state()
+        .create_physical_plan(&logical_plan)
+        .await?;
+    let mut operator_errors = Vec::new();
+    spawn_operator(system, physical_plan, outputs, &mut operator_errors);
+
+    // Input batches that don't conform to their table's schema are reported
+    // along with the errors of the operators:
+    let rejects = stream::unfold(rejects, |mut rejects| async move {
+        let error = rejects.recv().await?;
+        Some((BlockError::from_error("execution_plan", error), rejects))
+    });
+    let mut merged = stream::select_all(
+        operator_errors
+            .into_iter()
+            .map(|inputs| {
+                stream::unfold(inputs, |mut inputs| async move {
+                    let error = inputs.recv().await.ok()??;
+                    Some((error, inputs))
+                })
+                .boxed()
+            })
+            .chain([rejects.boxed()]),
+    );
+    system.spawn(async move {
+        while let Some(error) = merged.next().await {
+            if errors.is_connected() {
+                errors.send(error).await?;
+            }
+        }
+        Ok(())
+    });
+
+    Ok(())
+}
+
+fn spawn_operator(
+    system: &mut System,
+    plan: Arc<dyn ExecutionPlan>,
+    outputs: Outputs<RecordBatch>,
+    errors: &mut Vec<Inputs<BlockError>>,
+) {
+    let inputs = plan
+        .children()
+        .into_iter()
+        .map(|child| {
+            let (child_outputs, inputs) = System::bounded(1).into_inner();
+            spawn_operator(system, child.clone(), child_outputs, errors);
+            inputs
+        })
+        .collect();
+    let (operator_errors, inputs_errors) = System::bounded(1).into_inner();
+    errors.push(inputs_errors);
+    system.spawn(execution_plan(plan, inputs, outputs, operator_errors));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use arrow_array::{cast::AsArray, record_batch, types::Int64Type};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_split_plan() -> Result<(), Box<dyn Error>> {
+        let batch = record_batch!(("n", Int64, [3, 1, 4, 1, 5]))?;
+        let query = SqlQuery::new(
+            "SELECT n * 2 AS m FROM input WHERE n > 1 ORDER BY n",
+            batch.schema(),
+        )?;
+        let plan = QueryPlan::from(query);
+        assert_eq!(plan.tables().len(), 1);
+
+        let (mut in_, mut out) = (Channel::bounded(10), Channel::bounded(10));
+        let mut err = Channel::bounded(10);
+        let mut system = System::new();
+        split_plan(&mut system, plan, vec![in_.rx], out.tx, err.tx).await?;
+
+        in_.tx.send(batch.clone()).await?;
+        in_.tx.send(record_batch!(("n", Utf8, ["x"]))?).await?; // wrong type
+        in_.tx.send(batch.slice(4, 1)).await?;
+        in_.tx.close();
+
+        system.execute().await?;
+
+        let outputs = out.rx.recv_all().await?;
+        let values: Vec<i64> = outputs
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column(0)
+                    .as_primitive::<Int64Type>()
+                    .values()
+                    .to_vec()
+            })
+            .collect();
+        assert_eq!(values, [6, 8, 10, 10]);
+        assert_eq!(err.rx.recv_all().await?.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_query_plan_tables() -> Result<(), Box<dyn Error>> {
+        use datafusion::{datasource::provider_as_source, logical_expr::LogicalPlanBuilder};
+
+        let batch = record_batch!(("n", Int64, [3, 1, 4]))?;
+        let source = provider_as_source(Arc::new(EmptyTable::new(batch.schema())));
+        let scan = |table: TableReference| LogicalPlanBuilder::scan(table, source.clone(), None);
+        let (a, b) = (
+            TableReference::partial("a", "t"),
+            TableReference::partial("b", "t"),
+        );
+
+        let self_union = scan(a.clone())?.union(scan(a.clone())?.build()?)?.build()?;
+        assert!(QueryPlan::new(self_union).is_err());
+
+        let plan = QueryPlan::new(scan(a.clone())?.union(scan(b.clone())?.build()?)?.build()?)?;
+        assert_eq!(plan.tables().len(), 2);
+        assert_eq!((&plan.tables()[0].0, &plan.tables()[1].0), (&a, &b));
+
+        let (mut a_in, mut b_in) = (Channel::bounded(10), Channel::bounded(10));
+        let (mut out, mut err) = (Channel::bounded(10), Channel::bounded(10));
+        let block = tokio::spawn(query_plan(plan, vec![a_in.rx, b_in.rx], out.tx, err.tx));
+
+        a_in.tx.send(batch.clone()).await?;
+        a_in.tx.close();
+        b_in.tx.send(batch.slice(0, 1)).await?;
+        b_in.tx.close();
+
+        let _ = tokio::join!(block);
+
+        let outputs = out.rx.recv_all().await?;
+        let num_rows: usize = outputs.iter().map(RecordBatch::num_rows).sum();
+        assert_eq!(num_rows, 4);
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "substrait")]
+    #[tokio::test]
+    async fn test_query_plan_substrait() -> Result<(), Box<dyn Error>> {
+        use datafusion_substrait::logical_plan::producer::to_substrait_plan;
+
+        let batch = record_batch!(("n", Int64, [3, 1, 4]))?;
+        let query = SqlQuery::new("SELECT sum(n) AS total FROM input", batch.schema())?;
+        let context = catalog_context(query.tables())?;
+        let substrait = to_substrait_plan(query.plan(), &context.state())?;
+        let plan = QueryPlan::from_substrait(&substrait, [("input", batch.schema())])?;
+
+        let (mut in_, mut out) = (Channel::bounded(10), Channel::bounded(10));
+        let mut err = Channel::bounded(10);
+        let block = tokio::spawn(query_plan(plan, vec![in_.rx], out.tx, err.tx));
+
+        in_.tx.send(batch).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(block);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(
+            outputs[0].column(0).as_primitive::<Int64Type>().values(),
+            &[8]
+        );
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+// This is free and unencumbered software released into the public domain.
+
+use async_flow::{Inputs, Outputs, Port, Result};
+use core::time::Duration;
+use flows_error::BlockError;
+use tokio::time::{Instant, timeout_at};
+
+/// How often an aggregate block outputs the running value of its aggregate
+/// on its `running` port, besides the final value once its input closes.
+///
+/// Running values are only output once further batches have been received
+/// since the previous one, so an idle stream doesn't repeat them. A zero
+/// number of batches or interval is reported on the block's `errors` port,
+/// and no running values are output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Running {
+    /// Outputs no running values.
+    #[default]
+    Never,
+
+    /// Outputs the running value after every given number of input batches.
+    Batches(usize),
+
+    /// Outputs the running value at every given interval.
+    Interval(Duration),
+}
+
+/// An event received by an aggregate block.
+pub(crate) enum Event<T> {
+    /// An input message was received.
+    Input(T),
+
+    /// The running value is due.
+    Tick,
+}
+
+/// Interleaves the input messages of an aggregate block with the ticks for
+/// its running values.
+pub(crate) struct Ticker {
+    every: Running,
+    count: usize,
+    deadline: Option<Instant>,
+}
+
+impl Ticker {
+    /// Returns a ticker for the given running values, or else reports a zero
+    /// number of batches or interval on the given `errors` port and returns a
+    /// ticker for no running values.
+    pub(crate) async fn start(
+        every: Running,
+        block: &'static str,
+        errors: &Outputs<BlockError>,
+    ) -> Result<Self> {
+        let message = match every {
+            Running::Batches(0) => "the number of batches for running values is zero",
+            Running::Interval(interval) if interval.is_zero() => {
+                "the interval for running values is zero"
+            },
+            _ => return Ok(Self::new(every)),
+        };
+        if errors.is_connected() {
+            errors.send(BlockError::new(block, message)).await?;
+        }
+        Ok(Self::new(Running::Never))
+    }
+
+    fn new(every: Running) -> Self {
+        Self {
+            every,
+            count: 0,
+            deadline: None,
+        }
+    }
+
+    /// Receives the next input message, or else returns a tick once the
+    /// running value is due. Returns `None` once the input closes.
+    pub(crate) async fn recv<T>(&mut self, inputs: &mut Inputs<T>) -> Result<Option<Event<T>>> {
+        let interval = match self.every {
+            Running::Never => return Ok(inputs.recv().await?.map(Event::Input)),
+            Running::Batches(count) if self.count == count => {
+                self.count = 0;
+                return Ok(Some(Event::Tick));
+            },
+            Running::Batches(_) => return Ok(self.received(inputs.recv().await?)),
+            Running::Interval(interval) => interval,
+        };
+
+        loop {
+            let deadline = *self
+                .deadline
+                .get_or_insert_with(|| Instant::now() + interval);
+            match timeout_at(deadline, inputs.recv()).await {
+                Ok(input) => return Ok(self.received(input?)),
+                Err(_) => {
+                    self.deadline = Some(deadline + interval);
+                    if self.count > 0 {
+                        self.count = 0;
+                        return Ok(Some(Event::Tick));
+                    }
+                },
+            }
+        }
+    }
+
+    fn received<T>(&mut self, input: Option<T>) -> Option<Event<T>> {
+        self.count += input.is_some() as usize;
+        input.map(Event::Input)
+    }
+}
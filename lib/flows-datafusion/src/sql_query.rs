@@ -0,0 +1,248 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{
+    execute::execute_plan,
+    query_plan::{catalog_context, scanned_tables},
+};
+use alloc::{string::String, vec::Vec};
+use arrow_array::RecordBatch;
+use arrow_schema::SchemaRef;
+use async_flow::{Inputs, Outputs, Port, Result};
+use datafusion::{
+    common::TableReference, execution::context::SQLOptions, logical_expr::LogicalPlan,
+};
+use datafusion_common::plan_err;
+use flows_derive::block;
+use flows_error::BlockError;
+use futures::FutureExt;
+
+/// A SQL query over one or more named tables of known schemas.
+///
+/// The query is planned with DataFusion's SQL frontend when constructed, so
+/// syntax errors, unknown tables or columns and type mismatches are reported
+/// before any batches flow. Only queries are supported, not DDL, DML or
+/// other statements, and each table can only be scanned once (e.g., not by
+/// a self-join), as it's read from a single input.
+#[derive(Clone, Debug)]
+pub struct SqlQuery {
+    sql: String,
+    tables: Vec<(String, SchemaRef)>,
+    plan: LogicalPlan,
+}
+
+impl SqlQuery {
+    /// The name of the table of a query over a single input.
+    pub const INPUT: &str = "input";
+
+    /// Plans a query over a single table, named `input`, of the given schema.
+    pub fn new(sql: impl Into<String>, schema: SchemaRef) -> datafusion_common::Result<Self> {
+        Self::with_tables(sql, [(Self::INPUT, schema)])
+    }
+
+    /// Plans a query over the given named tables of the given schemas.
+    ///
+    /// Table names are case-sensitive, whereas unquoted identifiers in the
+    /// query are normalized to lowercase.
+    pub fn with_tables<S: Into<String>>(
+        sql: impl Into<String>,
+        tables: impl IntoIterator<Item = (S, SchemaRef)>,
+    ) -> datafusion_common::Result<Self> {
+        let sql = sql.into();
+        let tables: Vec<(String, SchemaRef)> = tables
+            .into_iter()
+            .map(|(name, schema)| (name.into(), schema))
+            .collect();
+
+        let context = catalog_context(&tables)?;
+
+        // Planning only awaits the in-memory catalog, so completes at once:
+        let Some(plan) = context.state().create_logical_plan(&sql).now_or_never() else {
+            return plan_err!("failed to plan the query: {sql}");
+        };
+        let plan = plan?;
+        SQLOptions::new()
+            .with_allow_ddl(false)
+            .with_allow_dml(false)
+            .with_allow_statements(false)
+            .verify_plan(&plan)?;
+        scanned_tables(&plan)?;
+
+        Ok(Self { sql, tables, plan })
+    }
+
+    /// Returns the query's SQL text.
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    /// Returns the query's tables and their schemas.
+    pub fn tables(&self) -> &[(String, SchemaRef)] {
+        &self.tables
+    }
+
+    /// Returns the schema of the query's result batches.
+    pub fn schema(&self) -> SchemaRef {
+        self.plan.schema().inner().clone()
+    }
+
+    /// Returns the query's (unoptimized) logical plan.
+    pub fn plan(&self) -> &LogicalPlan {
+        &self.plan
+    }
+}
+
+/// A block that executes a SQL query over its input batches, as the query's
+/// single table, and outputs the result batches.
+///
+/// Results are streamed as they are produced, so a filter or projection
+/// outputs a batch for each input batch, whereas, e.g., an aggregation
+/// outputs its results at the end of the stream.
+///
+/// Input batches are conformed to the table's schema by column name, and
+/// batches that lack a column, or whose column has a different datatype, are
+/// reported on the `errors` port. So are errors during the query's execution,
+/// which end it.
+#[block]
+pub async fn sql_query(
+    query: SqlQuery,
+    inputs: Inputs<RecordBatch>,
+    outputs: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    let [(name, _)] = query.tables() else {
+        if errors.is_connected() {
+            let message = "expected a query over a single table";
+            errors.send(BlockError::new("sql_query", message)).await?;
+        }
+        return Ok(());
+    };
+
+    let tables = alloc::vec![(TableReference::bare(name.as_str()), inputs)];
+    execute_plan("sql_query", query.plan, tables, &outputs, &errors).await
+}
+
+/// A block that executes a SQL query over multiple inputs, each the query's
+/// table of the same position, and outputs the result batches.
+///
+/// See `sql_query` for how input batches and errors are handled.
+#[block]
+pub async fn sql_query_tables(
+    query: SqlQuery,
+    inputs: Vec<Inputs<RecordBatch>>,
+    outputs: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    if inputs.len() != query.tables().len() {
+        if errors.is_connected() {
+            let message = "expected an input for each of the query's tables";
+            errors
+                .send(BlockError::new("sql_query_tables", message))
+                .await?;
+        }
+        return Ok(());
+    }
+
+    let tables = query
+        .tables()
+        .iter()
+        .map(|(name, _)| TableReference::bare(name.as_str()))
+        .zip(inputs)
+        .collect();
+    execute_plan("sql_query_tables", query.plan, tables, &outputs, &errors).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use arrow_array::{cast::AsArray, record_batch, types::Int64Type};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_sql_query() -> Result<(), Box<dyn Error>> {
+        let batch = record_batch!(
+            ("region", Utf8, ["north", "south", "north"]),
+            ("amount", Int64, [1, 2, 4])
+        )?;
+        let query = SqlQuery::new(
+            "SELECT region, sum(amount) AS total FROM input WHERE amount > 1 \
+             GROUP BY region ORDER BY region",
+            batch.schema(),
+        )?;
+        assert_eq!(query.schema().field(1).name(), "total");
+        assert!(SqlQuery::new("SELECT nope FROM input", batch.schema()).is_err());
+        assert!(SqlQuery::new("DROP TABLE input", batch.schema()).is_err());
+        let self_join = "SELECT * FROM input a JOIN input b ON a.region = b.region";
+        assert!(SqlQuery::new(self_join, batch.schema()).is_err());
+
+        let (mut in_, mut out) = (Channel::bounded(10), Channel::bounded(10));
+        let mut err = Channel::bounded(10);
+        let block = tokio::spawn(sql_query(query, in_.rx, out.tx, err.tx));
+
+        in_.tx.send(batch.clone()).await?;
+        in_.tx.send(record_batch!(("region", Utf8, ["x"]))?).await?; // lacks `amount`
+        in_.tx.send(batch).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(block);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs.len(), 1);
+        let regions = outputs[0].column(0).as_string::<i32>();
+        assert_eq!((regions.value(0), regions.value(1)), ("north", "south"));
+        assert_eq!(
+            outputs[0].column(1).as_primitive::<Int64Type>().values(),
+            &[8, 4]
+        );
+        assert_eq!(err.rx.recv_all().await?.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sql_query_tables() -> Result<(), Box<dyn Error>> {
+        let orders = record_batch!(("customer", Int64, [1, 2, 1]), ("amount", Int64, [5, 6, 7]))?;
+        let customers = record_batch!(("id", Int64, [1, 2]), ("name", Utf8, ["ann", "bob"]))?;
+        let query = SqlQuery::with_tables(
+            "SELECT name, amount FROM orders JOIN customers ON customer = id \
+             WHERE name = 'ann' ORDER BY amount",
+            [
+                ("orders", orders.schema()),
+                ("customers", customers.schema()),
+            ],
+        )?;
+
+        let (mut orders_in, mut customers_in) = (Channel::bounded(10), Channel::bounded(10));
+        let (mut out, mut err) = (Channel::bounded(10), Channel::bounded(10));
+        let block = tokio::spawn(sql_query_tables(
+            query,
+            vec![orders_in.rx, customers_in.rx],
+            out.tx,
+            err.tx,
+        ));
+
+        orders_in.tx.send(orders).await?;
+        orders_in.tx.close();
+        customers_in.tx.send(customers).await?;
+        customers_in.tx.close();
+
+        let _ = tokio::join!(block);
+
+        let outputs = out.rx.recv_all().await?;
+        let amounts: Vec<i64> = outputs
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column(1)
+                    .as_primitive::<Int64Type>()
+                    .values()
+                    .to_vec()
+            })
+            .collect();
+        assert_eq!(amounts, [5, 7]);
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{
+    ColumnRef, Running,
+    accumulate::accumulate_batch,
+    running::{Event, Ticker},
+};
+use alloc::vec::Vec;
+use arrow_array::RecordBatch;
+use async_flow::{Inputs, Output, Outputs, Port, Result};
+use datafusion_common::ScalarValue;
+use datafusion_expr::Accumulator;
+use datafusion_functions_aggregate::stddev::StddevAccumulator;
+use datafusion_functions_aggregate_common::stats::StatsType;
+use flows_derive::block;
+use flows_error::BlockError;
+
+/// A block that outputs the sample standard deviation of the values in a given column.
+///
+/// The sample standard deviation is computed in a single pass with Welford's
+/// algorithm, and is null given fewer than two non-null values.
+///
+/// Batches that lack the specified column, or whose column has a non-numeric
+/// datatype, are reported on the `errors` port.
+/// Running standard deviations are output on the `running` port as specified by
+/// `every`.
+#[block]
+pub async fn stddev_column(
+    column: ColumnRef,
+    every: Running,
+    mut inputs: Inputs<RecordBatch>,
+    output: Output<ScalarValue>,
+    running: Outputs<ScalarValue>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    let mut accumulator = new_accumulator();
+    let mut ticker = Ticker::start(every, "stddev_column", &errors).await?;
+
+    while let Some(event) = ticker.recv(&mut inputs).await? {
+        let input = match event {
+            Event::Input(input) => input,
+            Event::Tick => {
+                if running.is_connected()
+                    && let Ok(value) = stddev_result(&mut accumulator)
+                {
+                    running.send(value).await?;
+                }
+                continue;
+            },
+        };
+
+        if let Err(error) = accumulate_batch(&mut accumulator, &input, &column)
+            && errors.is_connected()
+        {
+            errors
+                .send(BlockError::from_error("stddev_column", error))
+                .await?;
+        }
+    }
+
+    match stddev_result(&mut accumulator) {
+        Ok(result) if !output.is_closed() => output.send(result).await?,
+        Err(error) if errors.is_connected() => {
+            errors
+                .send(BlockError::from_error("stddev_column", error))
+                .await?
+        },
+        _ => {},
+    }
+
+    Ok(())
+}
+
+/// A block that outputs the sample standard deviation of the values in a given column
+/// for each input window of batches.
+///
+/// Batches that lack the specified column, or whose column has a non-numeric
+/// datatype, are reported on the `errors` port.
+#[block]
+pub async fn stddev_column_windows(
+    column: ColumnRef,
+    mut inputs: Inputs<Vec<RecordBatch>>,
+    outputs: Outputs<ScalarValue>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    while let Some(window) = inputs.recv().await? {
+        let mut accumulator = new_accumulator();
+
+        for input in &window {
+            if let Err(error) = accumulate_batch(&mut accumulator, input, &column)
+                && errors.is_connected()
+            {
+                errors
+                    .send(BlockError::from_error("stddev_column_windows", error))
+                    .await?;
+            }
+        }
+
+        match stddev_result(&mut accumulator) {
+            Ok(result) if !outputs.is_closed() => outputs.send(result).await?,
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("stddev_column_windows", error))
+                    .await?
+            },
+            _ => continue,
+        }
+    }
+
+    Ok(())
+}
+
+fn new_accumulator() -> StddevAccumulator {
+    StddevAccumulator::try_new(StatsType::Sample).expect("sample statistics are supported")
+}
+
+fn stddev_result(accumulator: &mut StddevAccumulator) -> datafusion_common::Result<ScalarValue> {
+    match accumulator.evaluate()? {
+        ScalarValue::Float64(None) => Ok(ScalarValue::Null),
+        result => Ok(result),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use arrow_array::record_batch;
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_stddev_column() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::oneshot();
+        let mut err = Channel::bounded(10);
+        let block = tokio::spawn(stddev_column(
+            0.into(),
+            Running::Never,
+            in_.rx,
+            out.tx,
+            Channel::bounded(1).tx,
+            err.tx,
+        ));
+
+        in_.tx.send(sample_data().slice(0, 2)).await?;
+        in_.tx.send(sample_data().slice(2, 3)).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(block);
+
+        assert_eq!(
+            out.rx.recv_all().await?,
+            vec![ScalarValue::from(2.5f64.sqrt())]
+        );
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stddev_column_windows() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::bounded(10);
+        let mut err = Channel::bounded(10);
+        let block = tokio::spawn(stddev_column_windows(1.into(), in_.rx, out.tx, err.tx));
+
+        in_.tx.send(vec![sample_data()]).await?;
+        in_.tx.send(vec![sample_data().slice(0, 2)]).await?; // a single value
+        in_.tx.close();
+
+        let _ = tokio::join!(block);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(
+            outputs,
+            vec![ScalarValue::from(0.5f64.sqrt()), ScalarValue::Null]
+        );
+
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    fn sample_data() -> RecordBatch {
+        record_batch!(
+            ("a", Int32, [1, 2, 3, 4, 5]),
+            ("b", Float64, [Some(4.0), None, Some(5.0), None, None]),
+            ("c", Utf8, ["alpha", "beta", "gamma", "", ""])
+        )
+        .unwrap()
+    }
+}
@@ -0,0 +1,166 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::inputs_partition::InputsPartition;
+use alloc::sync::Arc;
+use arrow_array::RecordBatch;
+use arrow_schema::SchemaRef;
+use async_flow::{Inputs, Outputs, Port, Result, System};
+use datafusion::{catalog::streaming::StreamingTable, execution::SendableRecordBatchStream};
+use flows_derive::block;
+use flows_error::BlockError;
+use futures::StreamExt;
+
+/// Returns a DataFusion table of the given schema that streams the batches
+/// received on the given input port, e.g., as sent by a running system.
+///
+/// The table can be registered with any `SessionContext` and queried like
+/// any other table, with its scan ending once the port closes. Batches are
+/// conformed to the table's schema by column name, and a batch that lacks a
+/// column, or whose column has a different datatype, fails the query. The
+/// port can only be scanned once.
+pub fn inputs_table(
+    schema: SchemaRef,
+    inputs: Inputs<RecordBatch>,
+) -> datafusion_common::Result<StreamingTable> {
+    let partition = InputsPartition::new(schema.clone(), inputs, None);
+    StreamingTable::try_new(schema, alloc::vec![Arc::new(partition)])
+}
+
+/// Returns an output port, to connect in place of a block's output port,
+/// along with a DataFusion table of the given schema that streams the
+/// batches sent on it.
+///
+/// The port buffers at most `capacity` batches ahead of the table's scan.
+/// See `inputs_table` for how the table streams the batches.
+pub fn outputs_table(
+    schema: SchemaRef,
+    capacity: usize,
+) -> datafusion_common::Result<(Outputs<RecordBatch>, StreamingTable)> {
+    let (outputs, inputs) = System::bounded(capacity).into_inner();
+    Ok((outputs, inputs_table(schema, inputs)?))
+}
+
+/// A block that outputs the batches of a DataFusion record batch stream,
+/// e.g., as executed for a `DataFrame` or physical plan.
+///
+/// Empty batches are skipped. An error in the stream, which ends it, is
+/// reported on the `errors` port. The block exits without reading the rest
+/// of the stream once the `outputs` port is found closed.
+#[block]
+pub async fn read_batch_stream(
+    mut stream: SendableRecordBatchStream,
+    outputs: Outputs<RecordBatch>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    while let Some(result) = stream.next().await {
+        match result {
+            Ok(_) if outputs.is_closed() => break,
+            Ok(batch) if batch.num_rows() > 0 => outputs.send(batch).await?,
+            Err(error) => {
+                if errors.is_connected() {
+                    errors
+                        .send(BlockError::from_error("read_batch_stream", error))
+                        .await?;
+                }
+                break;
+            },
+            _ => continue,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec, vec::Vec};
+    use arrow_array::{cast::AsArray, record_batch, types::Int64Type};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+    use datafusion::prelude::SessionContext;
+
+    #[tokio::test]
+    async fn test_outputs_table() -> Result<(), Box<dyn Error>> {
+        let batch = record_batch!(("n", Int64, [3, 1, 4, 1, 5]))?;
+        let (outputs, table) = outputs_table(batch.schema(), 10)?;
+
+        outputs.send(batch.clone()).await?;
+        outputs.send(batch.slice(4, 1)).await?;
+        drop(outputs);
+
+        let context = SessionContext::new();
+        context.register_table("live", Arc::new(table))?;
+        let results = context
+            .sql("SELECT sum(n) AS total FROM live")
+            .await?
+            .collect()
+            .await?;
+        assert_eq!(
+            results[0].column(0).as_primitive::<Int64Type>().values(),
+            &[19]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_batch_stream() -> Result<(), Box<dyn Error>> {
+        let batch = record_batch!(("n", Int64, [3, 1, 4, 1, 5]))?;
+        let context = SessionContext::new();
+        context.register_batch("input", batch)?;
+        let stream = context
+            .sql("SELECT n * 2 AS m FROM input WHERE n > 1")
+            .await?
+            .execute_stream()
+            .await?;
+
+        let mut out = Channel::bounded(10);
+        let mut err = Channel::bounded(10);
+        let reader = tokio::spawn(read_batch_stream(stream, out.tx, err.tx));
+
+        let _ = tokio::join!(reader);
+
+        let values: Vec<i64> = out
+            .rx
+            .recv_all()
+            .await?
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column(0)
+                    .as_primitive::<Int64Type>()
+                    .values()
+                    .to_vec()
+            })
+            .collect();
+        assert_eq!(values, [6, 8, 10]);
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_batch_stream_closed() -> Result<(), Box<dyn Error>> {
+        let batch = record_batch!(("n", Int64, [3, 1, 4]))?;
+        let (outputs, table) = outputs_table(batch.schema(), 10)?;
+        let context = SessionContext::new();
+        context.register_table("live", Arc::new(table))?;
+        let stream = context
+            .sql("SELECT n FROM live")
+            .await?
+            .execute_stream()
+            .await?;
+
+        let mut out = Channel::bounded(10);
+        out.tx.close();
+        let reader = tokio::spawn(read_batch_stream(stream, out.tx, Channel::bounded(1).tx));
+
+        outputs.send(batch).await?; // the stream itself never ends
+        let _ = tokio::join!(reader);
+
+        assert!(out.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+}
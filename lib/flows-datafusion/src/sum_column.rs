@@ -1,37 +1,52 @@
 // This is free and unencumbered software released into the public domain.
 
-use arrow_array::{ArrayRef, RecordBatch};
-use async_flow::{Inputs, Output, Port, Result};
-use datafusion_common::ScalarValue;
+use super::{
+    ColumnRef, Running,
+    running::{Event, Ticker},
+};
+use alloc::{format, vec::Vec};
+use arrow_array::{ArrayRef, RecordBatch, types::DecimalType};
+use async_flow::{Inputs, Output, Outputs, Port, Result};
+use datafusion_common::{DataFusionError, ScalarValue};
 use flows_derive::block;
+use flows_error::BlockError;
 
 /// A block that outputs the sum of the values in a given column.
 ///
-/// Panics in case the specified column index is out of bounds.
-/// Outputs `ScalarValue::Null` in case the specified column has a non-numeric
-/// datatype.
+/// Batches that lack the specified column, or whose column has a non-numeric
+/// datatype, are reported on the `errors` port. Decimal sums are widened as
+/// by [`sum_array`], and batches that would overflow them are reported too.
+/// Outputs `ScalarValue::Null` in case there were no such values to sum.
+/// Running sums are output on the `running` port as specified by `every`.
 #[block]
 pub async fn sum_column(
-    column: usize,
+    column: ColumnRef,
+    every: Running,
     mut inputs: Inputs<RecordBatch>,
     output: Output<ScalarValue>,
+    running: Outputs<ScalarValue>,
+    errors: Outputs<BlockError>,
 ) -> Result {
     let mut result: ScalarValue = ScalarValue::Null;
+    let mut ticker = Ticker::start(every, "sum_column", &errors).await?;
 
-    while let Some(input) = inputs.recv().await? {
-        if input.num_rows() == 0 {
-            continue; // skip empty batches
-        }
-
-        let column_array = input.column(column);
-        let Some(column_sum) = sum_array(column_array) else {
-            continue; // skip unsupported datatypes
+    while let Some(event) = ticker.recv(&mut inputs).await? {
+        let input = match event {
+            Event::Input(input) => input,
+            Event::Tick => {
+                if running.is_connected() {
+                    running.send(result.clone()).await?;
+                }
+                continue;
+            },
         };
 
-        result = if result.is_null() {
-            column_sum
-        } else {
-            result.add(column_sum).unwrap()
+        if let Err(error) = sum_batch(&mut result, &input, &column)
+            && errors.is_connected()
+        {
+            errors
+                .send(BlockError::from_error("sum_column", error))
+                .await?;
         }
     }
 
@@ -42,11 +57,81 @@ pub async fn sum_column(
     Ok(())
 }
 
-pub fn sum_array(array: &ArrayRef) -> Option<ScalarValue> {
+/// A block that outputs the sum of the values in a given column for each
+/// input window of batches.
+///
+/// Batches that lack the specified column, or whose column has a non-numeric
+/// datatype, are reported on the `errors` port.
+/// Outputs `ScalarValue::Null` in case there were no such values to sum.
+#[block]
+pub async fn sum_column_windows(
+    column: ColumnRef,
+    mut inputs: Inputs<Vec<RecordBatch>>,
+    outputs: Outputs<ScalarValue>,
+    errors: Outputs<BlockError>,
+) -> Result {
+    while let Some(window) = inputs.recv().await? {
+        let mut result: ScalarValue = ScalarValue::Null;
+
+        for input in &window {
+            if let Err(error) = sum_batch(&mut result, input, &column)
+                && errors.is_connected()
+            {
+                errors
+                    .send(BlockError::from_error("sum_column_windows", error))
+                    .await?;
+            }
+        }
+
+        if !outputs.is_closed() {
+            outputs.send(result).await?;
+        }
+    }
+
+    Ok(())
+}
+
+fn sum_batch(
+    result: &mut ScalarValue,
+    input: &RecordBatch,
+    column: &ColumnRef,
+) -> datafusion_common::Result<()> {
+    if input.num_rows() == 0 {
+        return Ok(()); // skip empty batches
+    }
+
+    let column_array = column.resolve(input)?;
+    let column_sum = sum_array(column_array)?;
+    if column_sum.is_null() {
+        return Ok(()); // skip null-only batches
+    }
+
+    *result = if result.is_null() {
+        column_sum
+    } else if column_sum.data_type().is_decimal() {
+        // keeps to the widened precision of the first sum, or fails:
+        let total = result
+            .add_checked(column_sum)?
+            .cast_to(&result.data_type())?;
+        validate_decimal(&total)?;
+        total
+    } else {
+        result.add(column_sum)?
+    };
+    Ok(())
+}
+
+/// Returns the sum of the values of the given numeric array, or an error in
+/// case the array has a non-numeric datatype.
+///
+/// Decimal sums are widened by 10 digits of precision, up to the maximum
+/// precision of their datatype, as by DataFusion's `sum`, and are an error
+/// in case they overflow that.
+pub fn sum_array(array: &ArrayRef) -> datafusion_common::Result<ScalarValue> {
     use arrow_arith::aggregate::sum;
     use arrow_array::{cast::AsArray, types::*};
     use arrow_schema::DataType::*;
-    Some(match array.data_type() {
+    Ok(match array.data_type() {
         Int8 => ScalarValue::from(sum(array.as_primitive::<Int8Type>())),
         Int16 => ScalarValue::from(sum(array.as_primitive::<Int16Type>())),
         Int32 => ScalarValue::from(sum(array.as_primitive::<Int32Type>())),
@@ -58,11 +143,57 @@ pub fn sum_array(array: &ArrayRef) -> Option<ScalarValue> {
         Float16 => ScalarValue::from(sum(array.as_primitive::<Float16Type>())),
         Float32 => ScalarValue::from(sum(array.as_primitive::<Float32Type>())),
         Float64 => ScalarValue::from(sum(array.as_primitive::<Float64Type>())),
-        // TODO: Decimal32, Decimal64, Decimal128, Decimal256
-        _ => return None,
+        Decimal32(precision, scale) => sum_decimal::<Decimal32Type>(array, *precision, *scale)?,
+        Decimal64(precision, scale) => sum_decimal::<Decimal64Type>(array, *precision, *scale)?,
+        Decimal128(precision, scale) => sum_decimal::<Decimal128Type>(array, *precision, *scale)?,
+        Decimal256(precision, scale) => sum_decimal::<Decimal256Type>(array, *precision, *scale)?,
+        data_type => {
+            return Err(DataFusionError::Execution(format!(
+                "can't sum values of type {data_type}"
+            )));
+        },
     })
 }
 
+fn sum_decimal<T: DecimalType>(
+    array: &ArrayRef,
+    precision: u8,
+    scale: i8,
+) -> datafusion_common::Result<ScalarValue> {
+    use arrow_arith::aggregate::sum_checked;
+    use arrow_array::cast::AsArray;
+    let precision = precision.saturating_add(10).min(T::MAX_PRECISION);
+    let sum = sum_checked(array.as_primitive::<T>())?;
+    if let Some(sum) = sum {
+        T::validate_decimal_precision(sum, precision, scale)?;
+    }
+    ScalarValue::new_primitive::<T>(sum, &T::TYPE_CONSTRUCTOR(precision, scale))
+}
+
+/// Returns an error in case the given decimal value exceeds the precision of
+/// its datatype.
+fn validate_decimal(value: &ScalarValue) -> datafusion_common::Result<()> {
+    use arrow_array::{cast::AsArray, types::*};
+    use arrow_schema::DataType::*;
+    let array = value.to_array()?;
+    match value.data_type() {
+        Decimal32(precision, _) => array
+            .as_primitive::<Decimal32Type>()
+            .validate_decimal_precision(precision)?,
+        Decimal64(precision, _) => array
+            .as_primitive::<Decimal64Type>()
+            .validate_decimal_precision(precision)?,
+        Decimal128(precision, _) => array
+            .as_primitive::<Decimal128Type>()
+            .validate_decimal_precision(precision)?,
+        Decimal256(precision, _) => array
+            .as_primitive::<Decimal256Type>()
+            .validate_decimal_precision(precision)?,
+        _ => {},
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -75,7 +206,15 @@ mod tests {
     async fn test_sum_column_i32() -> Result<(), Box<dyn Error>> {
         let mut in_ = Channel::bounded(10);
         let mut out = Channel::oneshot();
-        let summer = tokio::spawn(sum_column(0, in_.rx, out.tx));
+        let mut err = Channel::bounded(10);
+        let summer = tokio::spawn(sum_column(
+            0.into(),
+            Running::Never,
+            in_.rx,
+            out.tx,
+            Channel::bounded(1).tx,
+            err.tx,
+        ));
 
         in_.tx.send(sample_data()).await?;
         in_.tx.send(sample_data()).await?;
@@ -87,6 +226,226 @@ mod tests {
         assert_eq!(outputs.len(), 1);
         assert_eq!(outputs[0], ScalarValue::from(30i32));
 
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sum_column_running() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let (mut out, mut run) = (Channel::oneshot(), Channel::bounded(10));
+        let mut err = Channel::bounded(10);
+        let summer = tokio::spawn(sum_column(
+            0.into(),
+            Running::Batches(2),
+            in_.rx,
+            out.tx,
+            run.tx,
+            err.tx,
+        ));
+
+        for _ in 0..5 {
+            in_.tx.send(sample_data()).await?;
+        }
+        in_.tx.close();
+
+        let _ = tokio::join!(summer);
+
+        assert_eq!(
+            run.rx.recv_all().await?,
+            vec![ScalarValue::from(30i32), ScalarValue::from(60i32)]
+        );
+        assert_eq!(out.rx.recv_all().await?, vec![ScalarValue::from(75i32)]);
+
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sum_column_running_zero() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let (mut out, mut run) = (Channel::oneshot(), Channel::bounded(10));
+        let mut err = Channel::bounded(10);
+        let summer = tokio::spawn(sum_column(
+            0.into(),
+            Running::Batches(0),
+            in_.rx,
+            out.tx,
+            run.tx,
+            err.tx,
+        ));
+
+        in_.tx.send(sample_data()).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(summer);
+
+        assert!(run.rx.recv_all().await?.is_empty());
+        assert_eq!(out.rx.recv_all().await?, vec![ScalarValue::from(15i32)]);
+        assert_eq!(err.rx.recv_all().await?.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sum_column_windows() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::bounded(10);
+        let mut err = Channel::bounded(10);
+        let summer = tokio::spawn(sum_column_windows(0.into(), in_.rx, out.tx, err.tx));
+
+        in_.tx.send(vec![sample_data(), sample_data()]).await?;
+        in_.tx.send(vec![sample_data()]).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(summer);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(
+            outputs,
+            vec![ScalarValue::from(30i32), ScalarValue::from(15i32)]
+        );
+
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sum_column_out_of_bounds() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::oneshot();
+        let mut err = Channel::bounded(10);
+        let summer = tokio::spawn(sum_column(
+            3.into(),
+            Running::Never,
+            in_.rx,
+            out.tx,
+            Channel::bounded(1).tx,
+            err.tx,
+        ));
+
+        in_.tx.send(sample_data()).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(summer);
+
+        assert_eq!(out.rx.recv_all().await?, vec![ScalarValue::Null]);
+
+        let errors = err.rx.recv_all().await?;
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].block(), "sum_column");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sum_column_by_name() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::oneshot();
+        let mut err = Channel::bounded(10);
+        let summer = tokio::spawn(sum_column(
+            "b".into(),
+            Running::Never,
+            in_.rx,
+            out.tx,
+            Channel::bounded(1).tx,
+            err.tx,
+        ));
+
+        in_.tx.send(sample_data()).await?;
+        in_.tx.send(sample_data().project(&[1, 0])?).await?; // reordered
+        in_.tx.send(sample_data().project(&[0])?).await?; // missing
+        in_.tx.close();
+
+        let _ = tokio::join!(summer);
+
+        assert_eq!(out.rx.recv_all().await?, vec![ScalarValue::from(18.0)]);
+
+        let errors = err.rx.recv_all().await?;
+        assert_eq!(errors.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sum_column_decimal() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::oneshot();
+        let mut err = Channel::bounded(10);
+        let summer = tokio::spawn(sum_column(
+            0.into(),
+            Running::Never,
+            in_.rx,
+            out.tx,
+            Channel::bounded(1).tx,
+            err.tx,
+        ));
+
+        let amounts = arrow_array::Decimal128Array::from(vec![Some(150), None, Some(275)])
+            .with_precision_and_scale(10, 2)?;
+        in_.tx
+            .send(RecordBatch::try_from_iter([(
+                "amount",
+                alloc::sync::Arc::new(amounts) as ArrayRef,
+            )])?)
+            .await?;
+        in_.tx.send(sample_data().project(&[2])?).await?; // non-numeric
+        in_.tx.close();
+
+        let _ = tokio::join!(summer);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs, vec![ScalarValue::Decimal128(Some(425), 20, 2)]);
+
+        let errors = err.rx.recv_all().await?;
+        assert_eq!(errors.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sum_column_decimal_overflow() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::oneshot();
+        let mut err = Channel::bounded(10);
+        let summer = tokio::spawn(sum_column(
+            0.into(),
+            Running::Never,
+            in_.rx,
+            out.tx,
+            Channel::bounded(1).tx,
+            err.tx,
+        ));
+
+        for amounts in [
+            vec![999_999_990],
+            vec![999_999_999, 1], // overflows the batch sum
+            vec![5],
+            vec![10], // overflows the total sum
+        ] {
+            let amounts =
+                arrow_array::Decimal32Array::from(amounts).with_precision_and_scale(9, 0)?;
+            in_.tx
+                .send(RecordBatch::try_from_iter([(
+                    "amount",
+                    alloc::sync::Arc::new(amounts) as ArrayRef,
+                )])?)
+                .await?;
+        }
+        in_.tx.close();
+
+        let _ = tokio::join!(summer);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(
+            outputs,
+            vec![ScalarValue::Decimal32(Some(999_999_995), 9, 0)]
+        );
+        assert_eq!(err.rx.recv_all().await?.len(), 2);
+
         Ok(())
     }
 
@@ -26,12 +26,12 @@ pub fn block(attr: TokenStream, input_fn: ItemFn) -> TokenStream {
     let options = if attr.is_empty() {
         BlockOptions::default()
     } else {
-        match NestedMeta::parse_meta_list(attr.into()) {
+        match NestedMeta::parse_meta_list(attr) {
             Ok(meta_list) => match BlockOptions::from_list(&meta_list) {
                 Ok(args) => args,
-                Err(e) => return TokenStream::from(e.write_errors()),
+                Err(e) => return e.write_errors(),
             },
-            Err(e) => return TokenStream::from(e.to_compile_error()),
+            Err(e) => return e.to_compile_error(),
         }
     };
 
@@ -47,10 +47,7 @@ pub fn block(attr: TokenStream, input_fn: ItemFn) -> TokenStream {
         });
 
     // Generate the struct name: snake_case->PascalCase + "Block":
-    let struct_name = Ident::new(
-        &format!("{}Block", trait_name.to_string()),
-        Span::call_site(),
-    );
+    let struct_name = Ident::new(&format!("{}Block", trait_name), Span::call_site());
 
     // Extract generics and where clause from the function:
     let generics = &input_fn.sig.generics;
@@ -73,6 +70,21 @@ pub fn block(attr: TokenStream, input_fn: ItemFn) -> TokenStream {
     let input_ids: Vec<_> = params.iter().filter_map(input_id).collect();
     let output_ids: Vec<_> = params.iter().filter_map(output_id).collect();
 
+    // Locate the conventional `errors` port, if the block has one:
+    let error_id = match params.iter().find(|param| param.name == "errors") {
+        None => quote! { None },
+        Some(param) => match &param.typ {
+            ParamType::Output(_, -1) => quote! { Some(self.errors.id()) },
+            _ => {
+                return syn::Error::new_spanned(
+                    &input_fn.sig,
+                    "the `errors` parameter must be an `Outputs<T>` port",
+                )
+                .to_compile_error();
+            },
+        },
+    };
+
     // Process function parameters into trait method parameters:
     // let trait_params: Vec<_> = inputs.iter().filter_map(fn_param_to_new_param).collect();
 
@@ -94,6 +106,11 @@ pub fn block(attr: TokenStream, input_fn: ItemFn) -> TokenStream {
             #fn_vis fn new(#(#new_params),*) -> Self {
                 Self { #(#new_args),* }
             }
+
+            /// Returns the ID of the block's `errors` port, if it has one.
+            #fn_vis fn error_port(&self) -> Option<::async_flow::model::OutputPortId> {
+                #error_id
+            }
         }
 
         #[automatically_derived]
@@ -112,11 +129,15 @@ pub fn block(attr: TokenStream, input_fn: ItemFn) -> TokenStream {
         #where_clause
         {
             fn inputs(&self) -> ::alloc::vec::Vec<::async_flow::model::InputPortId> {
-                [#(#input_ids),*].into()
+                let mut ids = ::alloc::vec::Vec::new();
+                #(ids.extend(#input_ids);)*
+                ids
             }
 
             fn outputs(&self) -> ::alloc::vec::Vec<::async_flow::model::OutputPortId> {
-                [#(#output_ids),*].into()
+                let mut ids = ::alloc::vec::Vec::new();
+                #(ids.extend(#output_ids);)*
+                ids
             }
         }
     };
@@ -147,7 +168,11 @@ fn extract_param(param: &FnArg) -> Option<Param> {
 fn input_id(param: &Param) -> Option<TokenStream> {
     let field_name = param.name();
     match &param.typ {
-        ParamType::Input(_, _) => Some(quote! { self.#field_name.id() }),
+        ParamType::Input(_, _) => Some(quote! { [self.#field_name.id()] }),
+        ParamType::Array(inner) => match inner.as_ref() {
+            ParamType::Input(_, _) => Some(quote! { self.#field_name.iter().map(|p| p.id()) }),
+            _ => None,
+        },
         _ => None,
     }
 }
@@ -155,7 +180,11 @@ fn input_id(param: &Param) -> Option<TokenStream> {
 fn output_id(param: &Param) -> Option<TokenStream> {
     let field_name = param.name();
     match &param.typ {
-        ParamType::Output(_, _) => Some(quote! { self.#field_name.id() }),
+        ParamType::Output(_, _) => Some(quote! { [self.#field_name.id()] }),
+        ParamType::Array(inner) => match inner.as_ref() {
+            ParamType::Output(_, _) => Some(quote! { self.#field_name.iter().map(|p| p.id()) }),
+            _ => None,
+        },
         _ => None,
     }
 }
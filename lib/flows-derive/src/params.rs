@@ -1,10 +1,13 @@
 // This is free and unencumbered software released into the public domain.
 
 use super::r#type::Typed;
-use alloc::string::{String, ToString};
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+};
 use proc_macro2::{Span, TokenStream};
 use quote::{ToTokens, quote};
-use syn::{FnArg, Ident, Pat, PatType, Path, Type, TypePath};
+use syn::{FnArg, GenericArgument, Ident, Pat, PatType, Path, Type, TypePath};
 use syn_match::path_match;
 
 #[derive(Clone, Debug)]
@@ -49,7 +52,7 @@ impl TryFrom<&FnArg> for Param {
     fn try_from(input: &FnArg) -> Result<Self, Self::Error> {
         match input {
             FnArg::Typed(typed) => typed.try_into(),
-            _ => return Err(()), // skip `self` parameters
+            _ => Err(()), // skip `self` parameters
         }
     }
 }
@@ -75,13 +78,18 @@ impl TryFrom<&PatType> for Param {
 pub enum ParamType {
     Input(Path, isize),
     Output(Path, isize),
+    Array(Box<ParamType>),
     Other(Typed),
 }
 
 impl ParamType {
     pub fn is_port(&self) -> bool {
         use ParamType::*;
-        matches!(self, Input(_, _) | Output(_, _))
+        match self {
+            Input(_, _) | Output(_, _) => true,
+            Array(inner) => inner.is_port(),
+            Other(_) => false,
+        }
     }
 
     pub fn owned(&self) -> Self {
@@ -101,6 +109,16 @@ impl From<&Type> for ParamType {
                 async_flow?::Inputs<$_t> => ParamType::Input(path.clone(), -1),
                 async_flow?::Output<$_t> => ParamType::Output(path.clone(), 1),
                 async_flow?::Outputs<$_t> => ParamType::Output(path.clone(), -1),
+                alloc?::vec?::Vec<$t> => match t {
+                    // `Vec<Inputs<T>>` and `Vec<Outputs<T>>` are port arrays:
+                    GenericArgument::Type(t) => match ParamType::from(t) {
+                        inner @ (ParamType::Input(_, _) | ParamType::Output(_, _)) => {
+                            ParamType::Array(Box::new(inner))
+                        },
+                        _ => ParamType::Other(input.into()),
+                    },
+                    _ => ParamType::Other(input.into()),
+                },
                 _ => ParamType::Other(input.into()),
             ),
             _ => ParamType::Other(input.into()),
@@ -125,6 +143,9 @@ impl ToTokens for ParamType {
                     _ => unreachable!(),
                 )
             },
+            ParamType::Array(inner) => {
+                quote! { ::alloc::vec::Vec<#inner> }
+            },
             ParamType::Other(type_) => {
                 quote! { #type_ }
             },
@@ -0,0 +1,84 @@
+// This is free and unencumbered software released into the public domain.
+
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+};
+use core::error::Error as StdError;
+use thiserror::Error;
+
+/// An error that occurred while a block was processing an input message.
+///
+/// By convention, blocks report such per-message failures on an optional
+/// `errors: Outputs<BlockError>` port instead of panicking or mixing them
+/// into their data outputs, and then carry on with the next message.
+/// Failures are dropped in case the `errors` port is not connected.
+#[derive(Debug, Error)]
+#[error("{block}: {message}")]
+pub struct BlockError {
+    block: &'static str,
+    message: String,
+    #[source]
+    source: Option<Box<dyn StdError + Send + Sync>>,
+}
+
+impl BlockError {
+    /// Constructs an error for the given block with the given message.
+    pub fn new(block: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            block,
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    /// Constructs an error for the given block from an underlying error.
+    pub fn from_error(
+        block: &'static str,
+        error: impl Into<Box<dyn StdError + Send + Sync>>,
+    ) -> Self {
+        let source = error.into();
+        Self {
+            block,
+            message: source.to_string(),
+            source: Some(source),
+        }
+    }
+
+    /// Attaches an underlying error as the source of this error.
+    pub fn with_source(mut self, source: impl Into<Box<dyn StdError + Send + Sync>>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Returns the name of the block that reported this error.
+    pub fn block(&self) -> &'static str {
+        self.block
+    }
+
+    /// Returns the message describing this error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+
+    #[test]
+    fn test_block_error() {
+        let error = BlockError::new("project_columns", "column index 3 is out of bounds");
+        assert_eq!(
+            format!("{error}"),
+            "project_columns: column index 3 is out of bounds"
+        );
+        assert!(error.source().is_none());
+
+        let error = BlockError::from_error("decode_string", "expected value".to_string());
+        assert_eq!(error.block(), "decode_string");
+        assert_eq!(error.message(), "expected value");
+        assert!(error.source().is_some());
+    }
+}
@@ -0,0 +1,13 @@
+// This is free and unencumbered software released into the public domain.
+
+#![no_std]
+#![forbid(unsafe_code)]
+//#![allow(unused)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+extern crate std;
+
+mod block_error;
+pub use block_error::*;
@@ -0,0 +1,117 @@
+// This is free and unencumbered software released into the public domain.
+
+use async_flow::{Inputs, Outputs, Result};
+use core::future::Future;
+use flows_derive::block;
+use futures_util::{StreamExt, stream::FuturesOrdered};
+use tokio::select;
+
+/// A block that outputs only the input messages satisfying a predicate.
+#[block]
+pub async fn filter<T, F>(mut predicate: F, mut inputs: Inputs<T>, outputs: Outputs<T>) -> Result
+where
+    F: FnMut(&T) -> bool,
+{
+    while let Some(input) = inputs.recv().await? {
+        if predicate(&input) {
+            outputs.send(input).await?;
+        }
+    }
+    Ok(())
+}
+
+/// A block that outputs only the input messages satisfying an async
+/// predicate, running up to `concurrency` invocations at a time.
+///
+/// Outputs are sent in the same order as their inputs were received.
+///
+/// Panics in case the specified concurrency is zero.
+#[block]
+pub async fn filter_async<T, F, Fut>(
+    concurrency: usize,
+    mut predicate: F,
+    mut inputs: Inputs<T>,
+    outputs: Outputs<T>,
+) -> Result
+where
+    F: FnMut(&T) -> Fut,
+    Fut: Future<Output = bool>,
+{
+    assert!(concurrency > 0);
+    let mut running = FuturesOrdered::new();
+    let mut open = true;
+
+    while open || !running.is_empty() {
+        select! {
+            input = inputs.recv(), if open && running.len() < concurrency => match input? {
+                Some(input) => {
+                    let satisfied = predicate(&input);
+                    running.push_back(async move { satisfied.await.then_some(input) });
+                },
+                None => open = false,
+            },
+            Some(output) = running.next(), if !running.is_empty() => {
+                if let Some(output) = output {
+                    outputs.send(output).await?;
+                }
+            },
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_filter() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::bounded(10);
+        let filterer = tokio::spawn(filter(|n: &i32| n % 2 == 0, in_.rx, out.tx));
+
+        for input in 1..=5 {
+            in_.tx.send(input).await?;
+        }
+        in_.tx.close();
+
+        let _ = tokio::join!(filterer);
+
+        assert_eq!(out.rx.recv_all().await?, vec![2, 4]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_filter_async() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::bounded(10);
+        let filterer = tokio::spawn(filter_async(
+            2,
+            |n: &i32| {
+                let even = n % 2 == 0;
+                async move {
+                    tokio::task::yield_now().await;
+                    even
+                }
+            },
+            in_.rx,
+            out.tx,
+        ));
+
+        for input in 1..=5 {
+            in_.tx.send(input).await?;
+        }
+        in_.tx.close();
+
+        let _ = tokio::join!(filterer);
+
+        assert_eq!(out.rx.recv_all().await?, vec![2, 4]);
+
+        Ok(())
+    }
+}
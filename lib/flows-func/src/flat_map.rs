@@ -0,0 +1,115 @@
+// This is free and unencumbered software released into the public domain.
+
+use async_flow::{Inputs, Outputs, Result};
+use core::future::Future;
+use flows_derive::block;
+use futures_util::{StreamExt, stream::FuturesOrdered};
+use tokio::select;
+
+/// A block that applies a function to each input message, outputting each
+/// of the items it returns.
+#[block]
+pub async fn flat_map<T, U, I, F>(mut f: F, mut inputs: Inputs<T>, outputs: Outputs<U>) -> Result
+where
+    I: IntoIterator<Item = U>,
+    F: FnMut(T) -> I,
+{
+    while let Some(input) = inputs.recv().await? {
+        for output in f(input) {
+            outputs.send(output).await?;
+        }
+    }
+    Ok(())
+}
+
+/// A block that applies an async function to each input message, outputting
+/// each of the items it returns, running up to `concurrency` invocations at a
+/// time.
+///
+/// Outputs are sent in the same order as their inputs were received.
+///
+/// Panics in case the specified concurrency is zero.
+#[block]
+pub async fn flat_map_async<T, U, I, F, Fut>(
+    concurrency: usize,
+    mut f: F,
+    mut inputs: Inputs<T>,
+    outputs: Outputs<U>,
+) -> Result
+where
+    I: IntoIterator<Item = U>,
+    F: FnMut(T) -> Fut,
+    Fut: Future<Output = I>,
+{
+    assert!(concurrency > 0);
+    let mut running = FuturesOrdered::new();
+    let mut open = true;
+
+    while open || !running.is_empty() {
+        select! {
+            input = inputs.recv(), if open && running.len() < concurrency => match input? {
+                Some(input) => running.push_back(f(input)),
+                None => open = false,
+            },
+            Some(items) = running.next(), if !running.is_empty() => {
+                for output in items {
+                    outputs.send(output).await?;
+                }
+            },
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_flat_map() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::bounded(10);
+        let mapper = tokio::spawn(flat_map(|n: usize| vec![n; n], in_.rx, out.tx));
+
+        for input in 1..=3 {
+            in_.tx.send(input).await?;
+        }
+        in_.tx.close();
+
+        let _ = tokio::join!(mapper);
+
+        assert_eq!(out.rx.recv_all().await?, vec![1, 2, 2, 3, 3, 3]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_flat_map_async() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::bounded(10);
+        let mapper = tokio::spawn(flat_map_async(
+            2,
+            |n: usize| async move {
+                tokio::task::yield_now().await;
+                vec![n; n]
+            },
+            in_.rx,
+            out.tx,
+        ));
+
+        for input in 1..=3 {
+            in_.tx.send(input).await?;
+        }
+        in_.tx.close();
+
+        let _ = tokio::join!(mapper);
+
+        assert_eq!(out.rx.recv_all().await?, vec![1, 2, 2, 3, 3, 3]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+// This is free and unencumbered software released into the public domain.
+
+use async_flow::{Inputs, Outputs, Result};
+use core::future::Future;
+use flows_derive::block;
+use futures_util::{StreamExt, stream::FuturesOrdered};
+use tokio::select;
+
+/// A block that calls a function with a reference to each input message
+/// before passing the message on unchanged.
+#[block]
+pub async fn inspect<T, F>(mut f: F, mut inputs: Inputs<T>, outputs: Outputs<T>) -> Result
+where
+    F: FnMut(&T),
+{
+    while let Some(input) = inputs.recv().await? {
+        f(&input);
+        outputs.send(input).await?;
+    }
+    Ok(())
+}
+
+/// A block that calls an async function with a reference to each input
+/// message before passing the message on unchanged, running up to
+/// `concurrency` invocations at a time.
+///
+/// Outputs are sent in the same order as their inputs were received.
+///
+/// Panics in case the specified concurrency is zero.
+#[block]
+pub async fn inspect_async<T, F, Fut>(
+    concurrency: usize,
+    mut f: F,
+    mut inputs: Inputs<T>,
+    outputs: Outputs<T>,
+) -> Result
+where
+    F: FnMut(&T) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    assert!(concurrency > 0);
+    let mut running = FuturesOrdered::new();
+    let mut open = true;
+
+    while open || !running.is_empty() {
+        select! {
+            input = inputs.recv(), if open && running.len() < concurrency => match input? {
+                Some(input) => {
+                    let inspected = f(&input);
+                    running.push_back(async move {
+                        inspected.await;
+                        input
+                    });
+                },
+                None => open = false,
+            },
+            Some(output) = running.next(), if !running.is_empty() => {
+                outputs.send(output).await?;
+            },
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use async_flow::{Channel, InputPort};
+    use core::{
+        error::Error,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    #[tokio::test]
+    async fn test_inspect() -> Result<(), Box<dyn Error>> {
+        static SEEN: AtomicUsize = AtomicUsize::new(0);
+
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::bounded(10);
+        let inspector = tokio::spawn(inspect(
+            |_: &i32| {
+                SEEN.fetch_add(1, Ordering::Relaxed);
+            },
+            in_.rx,
+            out.tx,
+        ));
+
+        for input in 1..=3 {
+            in_.tx.send(input).await?;
+        }
+        in_.tx.close();
+
+        let _ = tokio::join!(inspector);
+
+        assert_eq!(out.rx.recv_all().await?, vec![1, 2, 3]);
+        assert_eq!(SEEN.load(Ordering::Relaxed), 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_inspect_async() -> Result<(), Box<dyn Error>> {
+        static SEEN: AtomicUsize = AtomicUsize::new(0);
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::bounded(10);
+        let inspector = tokio::spawn(inspect_async(
+            2,
+            |_: &i32| async {
+                tokio::task::yield_now().await;
+                SEEN.fetch_add(1, Ordering::Relaxed);
+            },
+            in_.rx,
+            out.tx,
+        ));
+
+        for input in 1..=3 {
+            in_.tx.send(input).await?;
+        }
+        in_.tx.close();
+
+        let _ = tokio::join!(inspector);
+
+        assert_eq!(out.rx.recv_all().await?, vec![1, 2, 3]);
+        assert_eq!(SEEN.load(Ordering::Relaxed), 3);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+// This is free and unencumbered software released into the public domain.
+
+#![no_std]
+#![forbid(unsafe_code)]
+//#![allow(unused)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+extern crate std;
+
+mod filter;
+pub use filter::*;
+
+mod filter_map;
+pub use filter_map::*;
+
+mod flat_map;
+pub use flat_map::*;
+
+mod inspect;
+pub use inspect::*;
+
+mod map;
+pub use map::*;
+
+mod scan;
+pub use scan::*;
+
+mod try_map;
+pub use try_map::*;
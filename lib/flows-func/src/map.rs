@@ -0,0 +1,108 @@
+// This is free and unencumbered software released into the public domain.
+
+use async_flow::{Inputs, Outputs, Result};
+use core::future::Future;
+use flows_derive::block;
+use futures_util::{StreamExt, stream::FuturesOrdered};
+use tokio::select;
+
+/// A block that outputs the result of applying a function to each input
+/// message.
+#[block]
+pub async fn map<T, U, F>(mut f: F, mut inputs: Inputs<T>, outputs: Outputs<U>) -> Result
+where
+    F: FnMut(T) -> U,
+{
+    while let Some(input) = inputs.recv().await? {
+        outputs.send(f(input)).await?;
+    }
+    Ok(())
+}
+
+/// A block that outputs the result of applying an async function to each
+/// input message, running up to `concurrency` invocations at a time.
+///
+/// Outputs are sent in the same order as their inputs were received.
+///
+/// Panics in case the specified concurrency is zero.
+#[block]
+pub async fn map_async<T, U, F, Fut>(
+    concurrency: usize,
+    mut f: F,
+    mut inputs: Inputs<T>,
+    outputs: Outputs<U>,
+) -> Result
+where
+    F: FnMut(T) -> Fut,
+    Fut: Future<Output = U>,
+{
+    assert!(concurrency > 0);
+    let mut running = FuturesOrdered::new();
+    let mut open = true;
+
+    while open || !running.is_empty() {
+        select! {
+            input = inputs.recv(), if open && running.len() < concurrency => match input? {
+                Some(input) => running.push_back(f(input)),
+                None => open = false,
+            },
+            Some(output) = running.next(), if !running.is_empty() => {
+                outputs.send(output).await?;
+            },
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_map() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::bounded(10);
+        let mapper = tokio::spawn(map(|n: i32| n * 2, in_.rx, out.tx));
+
+        for input in 1..=3 {
+            in_.tx.send(input).await?;
+        }
+        in_.tx.close();
+
+        let _ = tokio::join!(mapper);
+
+        assert_eq!(out.rx.recv_all().await?, vec![2, 4, 6]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_map_async() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::bounded(10);
+        let mapper = tokio::spawn(map_async(
+            2,
+            |n: i32| async move {
+                tokio::task::yield_now().await;
+                n * 2
+            },
+            in_.rx,
+            out.tx,
+        ));
+
+        for input in 1..=5 {
+            in_.tx.send(input).await?;
+        }
+        in_.tx.close();
+
+        let _ = tokio::join!(mapper);
+
+        assert_eq!(out.rx.recv_all().await?, vec![2, 4, 6, 8, 10]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+// This is free and unencumbered software released into the public domain.
+
+use async_flow::{Inputs, Outputs, Result};
+use core::future::Future;
+use flows_derive::block;
+use futures_util::{StreamExt, stream::FuturesOrdered};
+use tokio::select;
+
+/// A block that threads mutable state through a function applied to each
+/// input message, outputting the function's results.
+///
+/// Like [`Iterator::scan`], the block exits as soon as the function returns
+/// `None`.
+#[block]
+pub async fn scan<T, S, U, F>(
+    mut state: S,
+    mut f: F,
+    mut inputs: Inputs<T>,
+    outputs: Outputs<U>,
+) -> Result
+where
+    F: FnMut(&mut S, T) -> Option<U>,
+{
+    while let Some(input) = inputs.recv().await? {
+        let Some(output) = f(&mut state, input) else {
+            break;
+        };
+        outputs.send(output).await?;
+    }
+    Ok(())
+}
+
+/// A block that threads mutable state through a function applied to each
+/// input message, outputting the results of the async computations it
+/// returns, running up to `concurrency` of them at a time.
+///
+/// As with `StreamExt::scan` from the `futures` crate, the state is updated
+/// when the function is called, while the returned future can't borrow it.
+/// Outputs are sent in the same order as their inputs were received, and the
+/// block exits as soon as an output is `None`, dropping later computations.
+///
+/// Panics in case the specified concurrency is zero.
+#[block]
+pub async fn scan_async<T, S, U, F, Fut>(
+    concurrency: usize,
+    mut state: S,
+    mut f: F,
+    mut inputs: Inputs<T>,
+    outputs: Outputs<U>,
+) -> Result
+where
+    F: FnMut(&mut S, T) -> Fut,
+    Fut: Future<Output = Option<U>>,
+{
+    assert!(concurrency > 0);
+    let mut running = FuturesOrdered::new();
+    let mut open = true;
+
+    while open || !running.is_empty() {
+        select! {
+            input = inputs.recv(), if open && running.len() < concurrency => match input? {
+                Some(input) => running.push_back(f(&mut state, input)),
+                None => open = false,
+            },
+            Some(output) = running.next(), if !running.is_empty() => {
+                let Some(output) = output else {
+                    break;
+                };
+                outputs.send(output).await?;
+            },
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_scan() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::bounded(10);
+        let scanner = tokio::spawn(scan(
+            0,
+            |sum: &mut i32, n: i32| {
+                *sum += n;
+                Some(*sum)
+            },
+            in_.rx,
+            out.tx,
+        ));
+
+        for input in 1..=4 {
+            in_.tx.send(input).await?;
+        }
+        in_.tx.close();
+
+        let _ = tokio::join!(scanner);
+
+        assert_eq!(out.rx.recv_all().await?, vec![1, 3, 6, 10]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_scan_async() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::bounded(10);
+        let scanner = tokio::spawn(scan_async(
+            2,
+            0,
+            |sum: &mut i32, n: i32| {
+                *sum += n;
+                let sum = *sum;
+                async move {
+                    tokio::task::yield_now().await;
+                    (sum < 10).then_some(sum)
+                }
+            },
+            in_.rx,
+            out.tx,
+        ));
+
+        for input in 1..=5 {
+            in_.tx.send(input).await?;
+        }
+        in_.tx.close();
+
+        let _ = tokio::join!(scanner);
+
+        assert_eq!(out.rx.recv_all().await?, vec![1, 3, 6]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+// This is free and unencumbered software released into the public domain.
+
+use async_flow::{Inputs, Outputs, Port, Result};
+use core::future::Future;
+use flows_derive::block;
+use futures_util::{StreamExt, stream::FuturesOrdered};
+use tokio::select;
+
+/// A block that applies a fallible function to each input message,
+/// outputting successful results on `outputs` and failures on `errors`.
+///
+/// Failures are dropped in case the `errors` port is not connected.
+#[block]
+pub async fn try_map<T, U, E, F>(
+    mut f: F,
+    mut inputs: Inputs<T>,
+    outputs: Outputs<U>,
+    errors: Outputs<E>,
+) -> Result
+where
+    E: Send,
+    F: FnMut(T) -> core::result::Result<U, E>,
+{
+    while let Some(input) = inputs.recv().await? {
+        match f(input) {
+            Ok(output) => outputs.send(output).await?,
+            Err(error) if errors.is_connected() => errors.send(error).await?,
+            Err(_) => continue,
+        }
+    }
+    Ok(())
+}
+
+/// A block that applies a fallible async function to each input message,
+/// outputting successful results on `outputs` and failures on `errors`,
+/// running up to `concurrency` invocations at a time.
+///
+/// Results are sent in the same order as their inputs were received.
+/// Failures are dropped in case the `errors` port is not connected.
+///
+/// Panics in case the specified concurrency is zero.
+#[block]
+pub async fn try_map_async<T, U, E, F, Fut>(
+    concurrency: usize,
+    mut f: F,
+    mut inputs: Inputs<T>,
+    outputs: Outputs<U>,
+    errors: Outputs<E>,
+) -> Result
+where
+    E: Send,
+    F: FnMut(T) -> Fut,
+    Fut: Future<Output = core::result::Result<U, E>>,
+{
+    assert!(concurrency > 0);
+    let mut running = FuturesOrdered::new();
+    let mut open = true;
+
+    while open || !running.is_empty() {
+        select! {
+            input = inputs.recv(), if open && running.len() < concurrency => match input? {
+                Some(input) => running.push_back(f(input)),
+                None => open = false,
+            },
+            Some(result) = running.next(), if !running.is_empty() => match result {
+                Ok(output) => outputs.send(output).await?,
+                Err(error) if errors.is_connected() => errors.send(error).await?,
+                Err(_) => continue,
+            },
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_try_map() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let (mut out, mut err) = (Channel::bounded(10), Channel::bounded(10));
+        let mapper = tokio::spawn(try_map(
+            |s: &str| s.parse::<i32>().map_err(|_| s),
+            in_.rx,
+            out.tx,
+            err.tx,
+        ));
+
+        for input in ["1", "two", "3"] {
+            in_.tx.send(input).await?;
+        }
+        in_.tx.close();
+
+        let _ = tokio::join!(mapper);
+
+        assert_eq!(out.rx.recv_all().await?, vec![1, 3]);
+        assert_eq!(err.rx.recv_all().await?, vec!["two"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_try_map_async() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let (mut out, mut err) = (Channel::bounded(10), Channel::bounded(10));
+        let mapper = tokio::spawn(try_map_async(
+            2,
+            |s: &str| async move { s.parse::<i32>().map_err(|_| s) },
+            in_.rx,
+            out.tx,
+            err.tx,
+        ));
+
+        for input in ["1", "two", "3"] {
+            in_.tx.send(input).await?;
+        }
+        in_.tx.close();
+
+        let _ = tokio::join!(mapper);
+
+        assert_eq!(out.rx.recv_all().await?, vec![1, 3]);
+        assert_eq!(err.rx.recv_all().await?, vec!["two"]);
+
+        Ok(())
+    }
+}
@@ -2,16 +2,20 @@
 
 use super::Result;
 use alloc::boxed::Box;
-use async_flow::{Inputs, Outputs};
+use async_flow::{Inputs, Outputs, Port};
 use core::error::Error as StdError;
 use flows_derive::block;
+use flows_error::BlockError;
 use hyper::body::{Body, Incoming};
 
 /// A block that outputs HTTP responses corresponding to input HTTP requests.
+///
+/// Requests that fail are reported on the `errors` port.
 #[block]
 pub async fn request<T>(
     mut requests: Inputs<http::Request<T>>,
-    responses: Outputs<Result<http::Response<Incoming>>>,
+    responses: Outputs<http::Response<Incoming>>,
+    errors: Outputs<BlockError>,
 ) -> Result<(), async_flow::Error>
 where
     T: Body + Send + 'static + Unpin,
@@ -19,8 +23,15 @@ where
     T::Error: Into<Box<dyn core::error::Error + Send + Sync>>,
 {
     while let Some(request) = requests.recv().await? {
-        let response = execute(request).await;
-        responses.send(response).await?;
+        match execute(request).await {
+            Ok(response) => responses.send(response).await?,
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("request", error))
+                    .await?
+            },
+            Err(_) => continue,
+        }
     }
     Ok(())
 }
@@ -107,10 +118,10 @@ where
     let http_client: Client<_, T> =
         Client::builder(TokioExecutor::new()).build(http_connector.build());
 
-    Ok(http_client
+    http_client
         .request(request)
         .await
-        .map_err(|_e| Error::Other(Box::new(_e)))?)
+        .map_err(|_e| Error::Other(Box::new(_e)))
 }
 
 #[cfg(any(not(feature = "std"), not(any(feature = "http1", feature = "http2"))))]
@@ -135,9 +146,9 @@ mod tests {
     #[tokio::test]
     async fn test_request() -> Result<(), Box<dyn Error>> {
         let mut in_ = Channel::bounded(1);
-        let mut out = Channel::bounded(1);
+        let (mut out, mut err) = (Channel::bounded(1), Channel::bounded(1));
 
-        let fetcher = tokio::spawn(request(in_.rx, out.tx));
+        let fetcher = tokio::spawn(request(in_.rx, out.tx, err.tx));
 
         #[cfg(all(feature = "http1", not(feature = "http2")))]
         let urls = ["http://httpbin.org/ip"];
@@ -167,9 +178,8 @@ mod tests {
         let _ = tokio::join!(fetcher);
 
         let outputs = out.rx.recv_all().await.unwrap();
-        #[cfg(feature = "std")]
-        std::eprintln!("{:?}", outputs); // DEBUG
-        assert_eq!(outputs.len(), 1);
+        let errors = err.rx.recv_all().await.unwrap();
+        assert_eq!(outputs.len() + errors.len(), 1);
 
         Ok(())
     }
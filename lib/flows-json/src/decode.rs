@@ -3,34 +3,82 @@
 use alloc::{string::String, vec::Vec};
 use async_flow::{Inputs, Outputs, Port};
 use flows_derive::block;
-use serde_json::{Result, Value};
+use flows_error::BlockError;
+use serde_json::Value;
 
 /// A block that decodes JSON value outputs from bytes inputs.
+///
+/// Inputs that fail to decode are reported on the `errors` port.
 #[block]
 pub async fn decode_bytes(
     mut inputs: Inputs<Vec<u8>>,
-    outputs: Outputs<Result<Value>>,
+    outputs: Outputs<Value>,
+    errors: Outputs<BlockError>,
 ) -> async_flow::Result {
     while let Some(input) = inputs.recv().await? {
-        let output: Result<Value> = serde_json::from_slice(&input);
-        if outputs.is_connected() {
-            outputs.send(output).await?;
+        match serde_json::from_slice(&input) {
+            Ok(output) if outputs.is_connected() => outputs.send(output).await?,
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("decode_bytes", error))
+                    .await?
+            },
+            _ => continue,
         }
     }
     Ok(())
 }
 
 /// A block that decodes JSON value outputs from from string inputs.
+///
+/// Inputs that fail to decode are reported on the `errors` port.
 #[block]
 pub async fn decode_string(
     mut inputs: Inputs<String>,
-    outputs: Outputs<Result<Value>>,
+    outputs: Outputs<Value>,
+    errors: Outputs<BlockError>,
 ) -> async_flow::Result {
     while let Some(input) = inputs.recv().await? {
-        let output: Result<Value> = serde_json::from_str(&input);
-        if outputs.is_connected() {
-            outputs.send(output).await?;
+        match serde_json::from_str(&input) {
+            Ok(output) if outputs.is_connected() => outputs.send(output).await?,
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("decode_string", error))
+                    .await?
+            },
+            _ => continue,
         }
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, string::ToString, vec};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_decode_string() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let (mut out, mut err) = (Channel::bounded(10), Channel::bounded(10));
+        let decoder = tokio::spawn(decode_string(in_.rx, out.tx, err.tx));
+
+        in_.tx.send("[1, 2]".to_string()).await?;
+        in_.tx.send("[1, 2".to_string()).await?;
+        in_.tx.send("null".to_string()).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(decoder);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs, vec![serde_json::json!([1, 2]), Value::Null]);
+
+        let errors = err.rx.recv_all().await?;
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].block(), "decode_string");
+
+        Ok(())
+    }
+}
@@ -3,34 +3,76 @@
 use alloc::{string::String, vec::Vec};
 use async_flow::{Inputs, Outputs, Port};
 use flows_derive::block;
-use serde_json::{Result, Value};
+use flows_error::BlockError;
+use serde_json::Value;
 
 /// A block that encodes JSON value inputs to bytes outputs.
+///
+/// Inputs that fail to encode are reported on the `errors` port.
 #[block]
 pub async fn encode_bytes(
     mut inputs: Inputs<Value>,
-    outputs: Outputs<Result<Vec<u8>>>,
+    outputs: Outputs<Vec<u8>>,
+    errors: Outputs<BlockError>,
 ) -> async_flow::Result {
     while let Some(input) = inputs.recv().await? {
-        let output = serde_json::to_vec(&input);
-        if outputs.is_connected() {
-            outputs.send(output).await?;
+        match serde_json::to_vec(&input) {
+            Ok(output) if outputs.is_connected() => outputs.send(output).await?,
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("encode_bytes", error))
+                    .await?
+            },
+            _ => continue,
         }
     }
     Ok(())
 }
 
 /// A block that encodes JSON value inputs to string outputs.
+///
+/// Inputs that fail to encode are reported on the `errors` port.
 #[block]
 pub async fn encode_string(
     mut inputs: Inputs<Value>,
-    outputs: Outputs<Result<String>>,
+    outputs: Outputs<String>,
+    errors: Outputs<BlockError>,
 ) -> async_flow::Result {
     while let Some(input) = inputs.recv().await? {
-        let output = serde_json::to_string(&input);
-        if outputs.is_connected() {
-            outputs.send(output).await?;
+        match serde_json::to_string(&input) {
+            Ok(output) if outputs.is_connected() => outputs.send(output).await?,
+            Err(error) if errors.is_connected() => {
+                errors
+                    .send(BlockError::from_error("encode_string", error))
+                    .await?
+            },
+            _ => continue,
         }
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_encode_string() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let (mut out, mut err) = (Channel::bounded(10), Channel::bounded(10));
+        let encoder = tokio::spawn(encode_string(in_.rx, out.tx, err.tx));
+
+        in_.tx.send(serde_json::json!({"a": [1, 2]})).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(encoder);
+
+        assert_eq!(out.rx.recv_all().await?, vec![r#"{"a":[1,2]}"#]);
+        assert!(err.rx.recv_all().await?.is_empty());
+
+        Ok(())
+    }
+}
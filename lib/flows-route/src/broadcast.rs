@@ -0,0 +1,56 @@
+// This is free and unencumbered software released into the public domain.
+
+use alloc::vec::Vec;
+use async_flow::{Inputs, Outputs, Port, Result};
+use flows_derive::block;
+
+/// A block that replicates each input message to all of its outputs.
+///
+/// Each message is delivered to every connected output before the next input
+/// is received, so the slowest consumer applies back-pressure to the producer.
+/// Outputs that become disconnected are skipped. The block exits once its
+/// input closes or none of its outputs remain connected.
+#[block]
+pub async fn broadcast<T>(mut inputs: Inputs<T>, outputs: Vec<Outputs<T>>) -> Result
+where
+    T: Clone + Send,
+{
+    while let Some(input) = inputs.recv().await? {
+        let mut delivered = false;
+        for output in outputs.iter().filter(|output| output.is_connected()) {
+            output.send(input.clone()).await?;
+            delivered = true;
+        }
+        if !delivered {
+            break; // no more consumers
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_broadcast() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(1);
+        let (mut out1, mut out2) = (Channel::bounded(10), Channel::bounded(10));
+        let broadcaster = tokio::spawn(broadcast(in_.rx, vec![out1.tx, out2.tx]));
+
+        for input in 1..=3 {
+            in_.tx.send(input).await?;
+        }
+        in_.tx.close();
+
+        let _ = tokio::join!(broadcaster);
+
+        assert_eq!(out1.rx.recv_all().await?, vec![1, 2, 3]);
+        assert_eq!(out2.rx.recv_all().await?, vec![1, 2, 3]);
+
+        Ok(())
+    }
+}
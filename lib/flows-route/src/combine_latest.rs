@@ -0,0 +1,89 @@
+// This is free and unencumbered software released into the public domain.
+
+use async_flow::{Inputs, Outputs, Result};
+use flows_derive::block;
+use tokio::select;
+
+/// A block that outputs the latest values of its two inputs whenever either
+/// of them receives a message.
+///
+/// Nothing is emitted until both inputs have received at least one message.
+/// When one input closes, its last value is retained and combined with every
+/// subsequent message on the other input. The block exits once both inputs
+/// have closed, or as soon as one of them closes without ever having received
+/// a message.
+#[block]
+pub async fn combine_latest<A, B>(
+    mut lhs: Inputs<A>,
+    mut rhs: Inputs<B>,
+    pairs: Outputs<(A, B)>,
+) -> Result
+where
+    A: Clone,
+    B: Clone,
+{
+    let (mut lhs_open, mut rhs_open) = (true, true);
+    let (mut a, mut b): (Option<A>, Option<B>) = (None, None);
+
+    while lhs_open || rhs_open {
+        select! {
+            input = lhs.recv(), if lhs_open => match input? {
+                Some(input) => a = Some(input),
+                None => {
+                    lhs_open = false;
+                    if a.is_none() {
+                        break;
+                    }
+                    continue;
+                },
+            },
+            input = rhs.recv(), if rhs_open => match input? {
+                Some(input) => b = Some(input),
+                None => {
+                    rhs_open = false;
+                    if b.is_none() {
+                        break;
+                    }
+                    continue;
+                },
+            },
+        }
+
+        if let (Some(a), Some(b)) = (&a, &b) {
+            pairs.send((a.clone(), b.clone())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_combine_latest() -> Result<(), Box<dyn Error>> {
+        let (mut lhs, mut rhs) = (Channel::bounded(10), Channel::bounded(10));
+        let mut out = Channel::bounded(10);
+        let combiner = tokio::spawn(combine_latest(lhs.rx, rhs.rx, out.tx));
+
+        // Whichever input is received first, the first pair is the same:
+        lhs.tx.send(1).await?;
+        rhs.tx.send("a").await?;
+        assert_eq!(out.rx.recv().await?, Some((1, "a")));
+
+        lhs.tx.close();
+        rhs.tx.send("b").await?;
+        rhs.tx.close();
+
+        let _ = tokio::join!(combiner);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs, vec![(1, "b")]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+// This is free and unencumbered software released into the public domain.
+
+#![no_std]
+#![forbid(unsafe_code)]
+//#![allow(unused)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+extern crate std;
+
+mod broadcast;
+pub use broadcast::*;
+
+mod combine_latest;
+pub use combine_latest::*;
+
+mod merge;
+pub use merge::*;
+
+mod partition_by_hash;
+pub use partition_by_hash::*;
+
+mod round_robin;
+pub use round_robin::*;
+
+mod zip;
+pub use zip::*;
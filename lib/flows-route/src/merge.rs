@@ -0,0 +1,84 @@
+// This is free and unencumbered software released into the public domain.
+
+use alloc::vec::Vec;
+use async_flow::{Inputs, Outputs, Port, PortEvent, Result};
+use core::{future::poll_fn, task::Poll};
+use flows_derive::block;
+
+/// A block that interleaves the messages from all of its inputs into a single
+/// output.
+///
+/// Inputs are polled in rotating order, starting after the input that
+/// produced the previous message, so that a busy input cannot starve the
+/// others. Inputs that close are dropped from the rotation, and the block
+/// exits once all of them have closed.
+#[block]
+pub async fn merge<T>(inputs: Vec<Inputs<T>>, outputs: Outputs<T>) -> Result
+where
+    T: Send,
+{
+    let mut inputs: Vec<Inputs<T>> = inputs
+        .into_iter()
+        .filter(|input| !input.is_unconnected() && !input.is_closed())
+        .collect();
+    let mut next = 0;
+
+    while !inputs.is_empty() {
+        let (index, event) = poll_fn(|cx| {
+            let len = inputs.len();
+            for offset in 0..len {
+                let index = (next + offset) % len;
+                if let Poll::Ready(event) = inputs[index].as_mut().poll_recv(cx) {
+                    return Poll::Ready((index, event));
+                }
+            }
+            Poll::Pending
+        })
+        .await;
+
+        match event {
+            Some(PortEvent::Message(message)) => {
+                outputs.send(message).await?;
+                next = index + 1;
+            },
+            Some(PortEvent::Connect) => next = index + 1,
+            Some(PortEvent::Disconnect) | None => {
+                inputs.remove(index);
+                next = index;
+            },
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_merge() -> Result<(), Box<dyn Error>> {
+        let (mut in1, mut in2) = (Channel::bounded(10), Channel::bounded(10));
+        let mut out = Channel::bounded(10);
+        let merger = tokio::spawn(merge(vec![in1.rx, in2.rx], out.tx));
+
+        for input in [1, 3, 5] {
+            in1.tx.send(input).await?;
+        }
+        in1.tx.close();
+        for input in [2, 4] {
+            in2.tx.send(input).await?;
+        }
+        in2.tx.close();
+
+        let _ = tokio::join!(merger);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs, vec![1, 2, 3, 4, 5]);
+
+        Ok(())
+    }
+}
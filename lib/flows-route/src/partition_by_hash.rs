@@ -0,0 +1,101 @@
+// This is free and unencumbered software released into the public domain.
+
+use alloc::vec::Vec;
+use async_flow::{Inputs, Outputs, Port, Result};
+use core::hash::{Hash, Hasher};
+use flows_derive::block;
+
+/// A block that distributes input messages across its outputs by the hash of
+/// a key extracted from each message.
+///
+/// Messages with equal keys always go to the same output, and the assignment
+/// is stable across runs. Messages whose output is not connected are dropped.
+/// Sending waits on the selected output, so a slow partition applies
+/// back-pressure to the producer.
+#[block]
+pub async fn partition_by_hash<T, K, F>(
+    key: F,
+    mut inputs: Inputs<T>,
+    outputs: Vec<Outputs<T>>,
+) -> Result
+where
+    T: Send,
+    K: Hash,
+    F: Fn(&T) -> K,
+{
+    if outputs.is_empty() {
+        return Ok(());
+    }
+
+    while let Some(input) = inputs.recv().await? {
+        let mut hasher = Fnv1aHasher::default();
+        key(&input).hash(&mut hasher);
+        let index = (hasher.finish() % outputs.len() as u64) as usize;
+
+        let output = &outputs[index];
+        if output.is_connected() {
+            output.send(input).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// A 64-bit FNV-1a hasher, which unlike the standard library's default hasher
+/// is deterministic and available without `std`.
+struct Fnv1aHasher(u64);
+
+impl Default for Fnv1aHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1aHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_partition_by_hash() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(1);
+        let (mut out1, mut out2) = (Channel::bounded(10), Channel::bounded(10));
+        let partitioner = tokio::spawn(partition_by_hash(
+            |n: &i32| n % 2,
+            in_.rx,
+            vec![out1.tx, out2.tx],
+        ));
+
+        for input in 1..=6 {
+            in_.tx.send(input).await?;
+        }
+        in_.tx.close();
+
+        let _ = tokio::join!(partitioner);
+
+        let (out1, out2) = (out1.rx.recv_all().await?, out2.rx.recv_all().await?);
+        assert_eq!(out1.len() + out2.len(), 6);
+        for outputs in [out1, out2] {
+            if let Some(first) = outputs.first() {
+                assert!(outputs.iter().all(|n| n % 2 == first % 2));
+            }
+        }
+
+        Ok(())
+    }
+}
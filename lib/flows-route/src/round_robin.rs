@@ -0,0 +1,60 @@
+// This is free and unencumbered software released into the public domain.
+
+use alloc::vec::Vec;
+use async_flow::{Inputs, Outputs, Port, Result};
+use flows_derive::block;
+
+/// A block that distributes input messages across its outputs in turn.
+///
+/// Each message goes to exactly one output, cycling through the connected
+/// outputs in order. Sending waits on the selected output, so a slow consumer
+/// applies back-pressure even if the other outputs have spare capacity.
+/// The block exits once its input closes or none of its outputs remain
+/// connected.
+#[block]
+pub async fn round_robin<T>(mut inputs: Inputs<T>, outputs: Vec<Outputs<T>>) -> Result
+where
+    T: Send,
+{
+    let mut next = 0;
+
+    while let Some(input) = inputs.recv().await? {
+        let Some(index) = (0..outputs.len())
+            .map(|offset| (next + offset) % outputs.len())
+            .find(|&index| outputs[index].is_connected())
+        else {
+            break; // no more consumers
+        };
+        outputs[index].send(input).await?;
+        next = index + 1;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_round_robin() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(1);
+        let (mut out1, mut out2) = (Channel::bounded(10), Channel::bounded(10));
+        let distributor = tokio::spawn(round_robin(in_.rx, vec![out1.tx, out2.tx]));
+
+        for input in 1..=5 {
+            in_.tx.send(input).await?;
+        }
+        in_.tx.close();
+
+        let _ = tokio::join!(distributor);
+
+        assert_eq!(out1.rx.recv_all().await?, vec![1, 3, 5]);
+        assert_eq!(out2.rx.recv_all().await?, vec![2, 4]);
+
+        Ok(())
+    }
+}
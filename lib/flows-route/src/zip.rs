@@ -0,0 +1,85 @@
+// This is free and unencumbered software released into the public domain.
+
+use async_flow::{Inputs, Outputs, Result};
+use flows_derive::block;
+use tokio::select;
+
+/// A block that pairs up the messages from its two inputs in order.
+///
+/// Waits for one message from each input before emitting a pair. The block
+/// exits as soon as an input is found closed while awaiting its next message,
+/// even if the other input is idle; a message already received from the
+/// other input is then dropped, and any messages still pending on it are left
+/// unconsumed.
+#[block]
+pub async fn zip<A, B>(mut lhs: Inputs<A>, mut rhs: Inputs<B>, pairs: Outputs<(A, B)>) -> Result {
+    let (mut a, mut b): (Option<A>, Option<B>) = (None, None);
+    loop {
+        select! {
+            input = lhs.recv(), if a.is_none() => match input? {
+                Some(input) => a = Some(input),
+                None => break,
+            },
+            input = rhs.recv(), if b.is_none() => match input? {
+                Some(input) => b = Some(input),
+                None => break,
+            },
+        }
+
+        match (a.take(), b.take()) {
+            (Some(lhs), Some(rhs)) => pairs.send((lhs, rhs)).await?,
+            pending => (a, b) = pending,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_zip() -> Result<(), Box<dyn Error>> {
+        let (mut lhs, mut rhs) = (Channel::bounded(10), Channel::bounded(10));
+        let mut out = Channel::bounded(10);
+        let zipper = tokio::spawn(zip(lhs.rx, rhs.rx, out.tx));
+
+        for input in [1, 2, 3] {
+            lhs.tx.send(input).await?;
+        }
+        lhs.tx.close();
+        for input in ["a", "b"] {
+            rhs.tx.send(input).await?;
+        }
+        rhs.tx.close();
+
+        let _ = tokio::join!(zipper);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs, vec![(1, "a"), (2, "b")]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_zip_closed_while_idle() -> Result<(), Box<dyn Error>> {
+        let (mut lhs, rhs) = (Channel::bounded(10), Channel::bounded(10));
+        let mut out = Channel::bounded(10);
+        let zipper = tokio::spawn(zip(lhs.rx, rhs.rx, out.tx));
+
+        lhs.tx.send(1).await?;
+        rhs.tx.send("a").await?;
+        lhs.tx.close();
+
+        // Exits even though `rhs` is still open:
+        let _ = tokio::join!(zipper);
+
+        assert_eq!(out.rx.recv_all().await?, vec![(1, "a")]);
+        drop(rhs.tx);
+
+        Ok(())
+    }
+}
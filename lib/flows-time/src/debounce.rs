@@ -0,0 +1,81 @@
+// This is free and unencumbered software released into the public domain.
+
+use async_flow::{Inputs, Outputs, Result};
+use core::time::Duration;
+use flows_derive::block;
+use tokio::{
+    select,
+    time::{Instant, sleep_until},
+};
+
+/// A block that outputs an input message only once no further messages have
+/// been received for the given duration.
+///
+/// Each message received during the quiet period replaces the pending one and
+/// restarts the period. A message still pending when the input closes is
+/// output immediately.
+#[block]
+pub async fn debounce<T>(duration: Duration, mut inputs: Inputs<T>, outputs: Outputs<T>) -> Result {
+    let mut pending: Option<(Instant, T)> = None;
+
+    loop {
+        let deadline = pending.as_ref().map(|(deadline, _)| *deadline);
+        select! {
+            input = inputs.recv() => match input? {
+                Some(input) => pending = Some((Instant::now() + duration, input)),
+                None => break,
+            },
+            _ = sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                if let Some((_, output)) = pending.take() {
+                    outputs.send(output).await?;
+                }
+            },
+        }
+    }
+
+    if let Some((_, output)) = pending {
+        outputs.send(output).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec::Vec};
+    use async_flow::Channel;
+    use core::error::Error;
+    use tokio::time::sleep;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_debounce() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::bounded(10);
+        let debouncer = tokio::spawn(debounce(Duration::from_secs(5), in_.rx, out.tx));
+
+        let start = Instant::now();
+        let collector = tokio::spawn(async move {
+            let mut elapsed = Vec::new();
+            while let Some(output) = out.rx.recv().await? {
+                elapsed.push((output, start.elapsed().as_secs()));
+            }
+            Ok::<_, async_flow::Error>(elapsed)
+        });
+
+        for input in [1, 2, 3] {
+            in_.tx.send(input).await?;
+            sleep(Duration::from_secs(1)).await;
+        }
+        sleep(Duration::from_secs(10)).await;
+        in_.tx.send(4).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(debouncer);
+        let elapsed = collector.await??;
+
+        assert_eq!(elapsed, [(3, 7), (4, 13)]);
+
+        Ok(())
+    }
+}
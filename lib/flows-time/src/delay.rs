@@ -0,0 +1,70 @@
+// This is free and unencumbered software released into the public domain.
+
+use alloc::collections::VecDeque;
+use async_flow::{Inputs, Outputs, Result};
+use core::time::Duration;
+use flows_derive::block;
+use tokio::{
+    select,
+    time::{Instant, sleep_until},
+};
+
+/// A block that delays each input message by a fixed duration.
+///
+/// Each message is output `duration` after it was received, preserving the
+/// spacing between messages. Messages still pending when the input closes
+/// are output once their delay has elapsed.
+#[block]
+pub async fn delay<T>(duration: Duration, mut inputs: Inputs<T>, outputs: Outputs<T>) -> Result {
+    let mut pending: VecDeque<(Instant, T)> = VecDeque::new();
+    let mut open = true;
+
+    while open || !pending.is_empty() {
+        let deadline = pending.front().map(|(deadline, _)| *deadline);
+        select! {
+            input = inputs.recv(), if open => match input? {
+                Some(input) => pending.push_back((Instant::now() + duration, input)),
+                None => open = false,
+            },
+            _ = sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                if let Some((_, output)) = pending.pop_front() {
+                    outputs.send(output).await?;
+                }
+            },
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec::Vec};
+    use async_flow::Channel;
+    use core::error::Error;
+    use tokio::time::sleep;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_delay() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::bounded(10);
+        let delayer = tokio::spawn(delay(Duration::from_secs(5), in_.rx, out.tx));
+
+        let start = Instant::now();
+        in_.tx.send(1).await?;
+        sleep(Duration::from_secs(2)).await;
+        in_.tx.send(2).await?;
+        in_.tx.close();
+
+        let mut elapsed = Vec::new();
+        while let Some(output) = out.rx.recv().await? {
+            elapsed.push((output, start.elapsed().as_secs()));
+        }
+        let _ = tokio::join!(delayer);
+
+        assert_eq!(elapsed, [(1, 5), (2, 7)]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+// This is free and unencumbered software released into the public domain.
+
+#![no_std]
+#![forbid(unsafe_code)]
+//#![allow(unused)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+extern crate std;
+
+mod debounce;
+pub use debounce::*;
+
+mod delay;
+pub use delay::*;
+
+mod rate_limit;
+pub use rate_limit::*;
+
+mod throttle_latest;
+pub use throttle_latest::*;
+
+mod timeout_with_default;
+pub use timeout_with_default::*;
@@ -0,0 +1,78 @@
+// This is free and unencumbered software released into the public domain.
+
+use async_flow::{Inputs, Outputs, Result};
+use core::time::Duration;
+use flows_derive::block;
+use tokio::time::{Instant, sleep_until};
+
+/// A block that limits the rate of input messages using a token bucket.
+///
+/// The bucket holds up to `burst` tokens and gains one token every
+/// `interval`, starting out full. Each message consumes a token, waiting for
+/// one to become available if the bucket is empty, so that sustained
+/// throughput is at most one message per `interval`.
+///
+/// Panics in case the specified interval or burst is zero.
+#[block]
+pub async fn rate_limit<T>(
+    interval: Duration,
+    burst: usize,
+    mut inputs: Inputs<T>,
+    outputs: Outputs<T>,
+) -> Result {
+    assert!(!interval.is_zero() && burst > 0);
+    let mut tokens = burst;
+    let mut refilled = Instant::now();
+
+    while let Some(input) = inputs.recv().await? {
+        if tokens == 0 {
+            sleep_until(refilled + interval).await;
+        }
+
+        let now = Instant::now();
+        let earned = (now.duration_since(refilled).as_nanos() / interval.as_nanos()) as usize;
+        if tokens + earned >= burst {
+            tokens = burst;
+            refilled = now;
+        } else {
+            tokens += earned;
+            refilled += interval * earned as u32;
+        }
+
+        tokens -= 1;
+        outputs.send(input).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec::Vec};
+    use async_flow::Channel;
+    use core::error::Error;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limit() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::bounded(10);
+        let limiter = tokio::spawn(rate_limit(Duration::from_secs(1), 2, in_.rx, out.tx));
+
+        let start = Instant::now();
+        for input in 1..=4 {
+            in_.tx.send(input).await?;
+        }
+        in_.tx.close();
+
+        let mut elapsed = Vec::new();
+        while let Some(output) = out.rx.recv().await? {
+            elapsed.push((output, start.elapsed().as_secs()));
+        }
+        let _ = tokio::join!(limiter);
+
+        assert_eq!(elapsed, [(1, 0), (2, 0), (3, 1), (4, 2)]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+// This is free and unencumbered software released into the public domain.
+
+use async_flow::{Inputs, Outputs, Result};
+use core::time::Duration;
+use flows_derive::block;
+use tokio::{
+    select,
+    time::{Instant, sleep_until},
+};
+
+/// A block that outputs at most one input message per interval, favoring the
+/// latest message.
+///
+/// A message received while no interval is running is output immediately and
+/// starts a new interval. Messages received during an interval replace one
+/// another, and the latest of them is output once the interval ends, starting
+/// the next one. A message still pending when the input closes is output at
+/// the end of the current interval.
+///
+/// Panics in case the specified interval is zero.
+#[block]
+pub async fn throttle_latest<T>(
+    interval: Duration,
+    mut inputs: Inputs<T>,
+    outputs: Outputs<T>,
+) -> Result {
+    assert!(!interval.is_zero());
+    let mut interval_end: Option<Instant> = None;
+    let mut pending: Option<T> = None;
+    let mut open = true;
+
+    while open || pending.is_some() {
+        select! {
+            input = inputs.recv(), if open => match input? {
+                Some(input) if interval_end.is_none() => {
+                    interval_end = Some(Instant::now() + interval);
+                    outputs.send(input).await?;
+                },
+                Some(input) => pending = Some(input),
+                None => open = false,
+            },
+            _ = sleep_until(interval_end.unwrap_or_else(Instant::now)), if interval_end.is_some() => {
+                interval_end = None;
+                if let Some(output) = pending.take() {
+                    interval_end = Some(Instant::now() + interval);
+                    outputs.send(output).await?;
+                }
+            },
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec::Vec};
+    use async_flow::Channel;
+    use core::error::Error;
+    use tokio::time::sleep;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_throttle_latest() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::bounded(10);
+        let throttler = tokio::spawn(throttle_latest(Duration::from_secs(5), in_.rx, out.tx));
+
+        let start = Instant::now();
+        let collector = tokio::spawn(async move {
+            let mut elapsed = Vec::new();
+            while let Some(output) = out.rx.recv().await? {
+                elapsed.push((output, start.elapsed().as_secs()));
+            }
+            Ok::<_, async_flow::Error>(elapsed)
+        });
+
+        for input in 1..=4 {
+            in_.tx.send(input).await?;
+            sleep(Duration::from_secs(1)).await;
+        }
+        sleep(Duration::from_secs(10)).await;
+        in_.tx.send(5).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(throttler);
+        let elapsed = collector.await??;
+
+        assert_eq!(elapsed, [(1, 0), (4, 5), (5, 14)]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+// This is free and unencumbered software released into the public domain.
+
+use async_flow::{Inputs, Outputs, Result};
+use core::time::Duration;
+use flows_derive::block;
+use tokio::time::timeout;
+
+/// A block that forwards input messages, outputting a default value whenever
+/// no message has been received for the given duration.
+///
+/// The timer restarts after every message and after every default value, so
+/// a silent input yields the default once per `duration` until it closes.
+#[block]
+pub async fn timeout_with_default<T>(
+    duration: Duration,
+    default: T,
+    mut inputs: Inputs<T>,
+    outputs: Outputs<T>,
+) -> Result
+where
+    T: Clone,
+{
+    loop {
+        match timeout(duration, inputs.recv()).await {
+            Ok(input) => match input? {
+                Some(input) => outputs.send(input).await?,
+                None => break,
+            },
+            Err(_) => outputs.send(default.clone()).await?,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec::Vec};
+    use async_flow::Channel;
+    use core::error::Error;
+    use tokio::time::{Instant, sleep};
+
+    #[tokio::test(start_paused = true)]
+    async fn test_timeout_with_default() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::bounded(10);
+        let timer = tokio::spawn(timeout_with_default(
+            Duration::from_secs(5),
+            0,
+            in_.rx,
+            out.tx,
+        ));
+
+        let start = Instant::now();
+        let collector = tokio::spawn(async move {
+            let mut elapsed = Vec::new();
+            while let Some(output) = out.rx.recv().await? {
+                elapsed.push((output, start.elapsed().as_secs()));
+            }
+            Ok::<_, async_flow::Error>(elapsed)
+        });
+
+        in_.tx.send(1).await?;
+        sleep(Duration::from_secs(12)).await;
+        in_.tx.send(2).await?;
+        in_.tx.close();
+
+        let _ = tokio::join!(timer);
+        let elapsed = collector.await??;
+
+        assert_eq!(elapsed, [(1, 0), (0, 5), (0, 10), (2, 12)]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+// This is free and unencumbered software released into the public domain.
+
+use alloc::vec::Vec;
+use async_flow::{Inputs, Outputs, Port, Result};
+use core::mem::take;
+use flows_derive::block;
+
+/// A block that groups input messages into windows of a fixed count.
+///
+/// Outputs a window each time `size` messages have been received, and a
+/// final partial window, if any, once the input closes.
+///
+/// Panics in case the specified size is zero.
+#[block]
+pub async fn count_window<T>(size: usize, mut inputs: Inputs<T>, windows: Outputs<Vec<T>>) -> Result
+where
+    T: Send,
+{
+    assert!(size > 0);
+    let mut window = Vec::with_capacity(size);
+
+    while let Some(input) = inputs.recv().await? {
+        window.push(input);
+        if window.len() < size {
+            continue; // window not yet full
+        }
+
+        let window = take(&mut window);
+        if !windows.is_closed() {
+            windows.send(window).await?;
+        }
+    }
+
+    if !window.is_empty() && !windows.is_closed() {
+        windows.send(window).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_count_window() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let mut out = Channel::bounded(10);
+        let windower = tokio::spawn(count_window(2, in_.rx, out.tx));
+
+        for input in 1..=5 {
+            in_.tx.send(input).await?;
+        }
+        in_.tx.close();
+
+        let _ = tokio::join!(windower);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs, vec![vec![1, 2], vec![3, 4], vec![5]]);
+
+        Ok(())
+    }
+}
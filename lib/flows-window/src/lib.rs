@@ -0,0 +1,24 @@
+// This is free and unencumbered software released into the public domain.
+
+#![no_std]
+#![forbid(unsafe_code)]
+//#![allow(unused)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+extern crate std;
+
+mod count_window;
+pub use count_window::*;
+
+mod session_window;
+pub use session_window::*;
+
+mod sliding_window;
+pub use sliding_window::*;
+
+mod tumbling_window;
+pub use tumbling_window::*;
+
+mod watermark;
@@ -0,0 +1,132 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::watermark::Watermark;
+use alloc::vec::Vec;
+use async_flow::{Inputs, Outputs, Port, Result};
+use core::time::Duration;
+use flows_derive::block;
+
+/// A block that groups input messages into sessions of activity separated by
+/// an event-time inactivity gap.
+///
+/// A message joins every open session whose events lie within `gap` of its
+/// own event time, merging those sessions if it bridges more than one. A
+/// session is output once the watermark, which trails the latest event time
+/// seen by `lateness`, passes its last event time plus `gap`. Messages that
+/// would start a session that has already ended are sent to `late`. Any
+/// remaining sessions are output in order once the input closes.
+///
+/// Panics in case the specified gap is zero.
+#[block]
+pub async fn session_window<T, F>(
+    gap: Duration,
+    lateness: Duration,
+    timestamp: F,
+    mut inputs: Inputs<T>,
+    windows: Outputs<Vec<T>>,
+    late: Outputs<T>,
+) -> Result
+where
+    T: Send,
+    F: Fn(&T) -> Duration,
+{
+    assert!(!gap.is_zero());
+    let mut watermark = Watermark::new(lateness);
+    let mut sessions: Vec<Session<T>> = Vec::new(); // ordered by start time
+
+    while let Some(input) = inputs.recv().await? {
+        let time = timestamp(&input);
+
+        let mut session = Session {
+            start: time,
+            end: time,
+            inputs: Vec::new(),
+        };
+        let mut index = 0;
+        while index < sessions.len() {
+            let other = &sessions[index];
+            if time + gap > other.start && time < other.end + gap {
+                let other = sessions.remove(index);
+                session.start = session.start.min(other.start);
+                session.end = session.end.max(other.end);
+                session.inputs.extend(other.inputs);
+            } else {
+                index += 1;
+            }
+        }
+
+        if session.inputs.is_empty() && watermark.has_passed(time + gap) {
+            if late.is_connected() {
+                late.send(input).await?;
+            }
+            continue;
+        }
+
+        session.inputs.push(input);
+        let index = sessions.partition_point(|other| other.start < session.start);
+        sessions.insert(index, session);
+        watermark.observe(time);
+
+        let mut index = 0;
+        while index < sessions.len() {
+            if !watermark.has_passed(sessions[index].end + gap) {
+                index += 1;
+                continue; // session still open
+            }
+            let window = sessions.remove(index).inputs;
+            if !windows.is_closed() {
+                windows.send(window).await?;
+            }
+        }
+    }
+
+    for session in sessions {
+        if !windows.is_closed() {
+            windows.send(session.inputs).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug)]
+struct Session<T> {
+    start: Duration,
+    end: Duration,
+    inputs: Vec<T>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_session_window() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let (mut out, mut late) = (Channel::bounded(10), Channel::bounded(10));
+        let windower = tokio::spawn(session_window(
+            Duration::from_secs(5),
+            Duration::ZERO,
+            |secs: &u64| Duration::from_secs(*secs),
+            in_.rx,
+            out.tx,
+            late.tx,
+        ));
+
+        for input in [1, 3, 20, 10, 6, 2, 40] {
+            in_.tx.send(input).await?;
+        }
+        in_.tx.close();
+
+        let _ = tokio::join!(windower);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs, vec![vec![1, 3], vec![20], vec![40]]);
+        assert_eq!(late.rx.recv_all().await?, vec![10, 6, 2]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::watermark::{Watermark, align};
+use alloc::{collections::BTreeMap, vec::Vec};
+use async_flow::{Inputs, Outputs, Port, Result};
+use core::time::Duration;
+use flows_derive::block;
+
+/// A block that groups input messages into overlapping windows of a fixed
+/// event-time interval, starting a new window every `slide`.
+///
+/// Each message is copied into every window that contains its event time.
+/// Windows are output, and late messages handled, the same way as for
+/// [`tumbling_window`](crate::tumbling_window): a message is late only if all
+/// of the windows containing it have already been output.
+///
+/// Panics in case the specified size or slide is zero.
+#[block]
+pub async fn sliding_window<T, F>(
+    size: Duration,
+    slide: Duration,
+    lateness: Duration,
+    timestamp: F,
+    mut inputs: Inputs<T>,
+    windows: Outputs<Vec<T>>,
+    late: Outputs<T>,
+) -> Result
+where
+    T: Clone + Send,
+    F: Fn(&T) -> Duration,
+{
+    assert!(!size.is_zero() && !slide.is_zero());
+    let mut watermark = Watermark::new(lateness);
+    let mut pending: BTreeMap<Duration, Vec<T>> = BTreeMap::new();
+
+    while let Some(input) = inputs.recv().await? {
+        let time = timestamp(&input);
+        let last_start = align(time, slide);
+
+        if watermark.has_passed(last_start + size) {
+            if late.is_connected() {
+                late.send(input).await?;
+            }
+            continue;
+        }
+
+        let mut start = last_start;
+        loop {
+            if !watermark.has_passed(start + size) {
+                pending.entry(start).or_default().push(input.clone());
+            }
+            match start.checked_sub(slide) {
+                Some(prev) if prev + size > time => start = prev,
+                _ => break,
+            }
+        }
+        watermark.observe(time);
+
+        while let Some(entry) = pending.first_entry() {
+            if !watermark.has_passed(*entry.key() + size) {
+                break; // window still open
+            }
+            let window = entry.remove();
+            if !windows.is_closed() {
+                windows.send(window).await?;
+            }
+        }
+    }
+
+    for (_, window) in pending {
+        if !windows.is_closed() {
+            windows.send(window).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_sliding_window() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let (mut out, mut late) = (Channel::bounded(10), Channel::bounded(10));
+        let windower = tokio::spawn(sliding_window(
+            Duration::from_secs(10),
+            Duration::from_secs(5),
+            Duration::ZERO,
+            |secs: &u64| Duration::from_secs(*secs),
+            in_.rx,
+            out.tx,
+            late.tx,
+        ));
+
+        for input in [1, 7, 12, 2] {
+            in_.tx.send(input).await?;
+        }
+        in_.tx.close();
+
+        let _ = tokio::join!(windower);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs, vec![vec![1, 7], vec![7, 12], vec![12]]);
+        assert_eq!(late.rx.recv_all().await?, vec![2]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::watermark::{Watermark, align};
+use alloc::{collections::BTreeMap, vec::Vec};
+use async_flow::{Inputs, Outputs, Port, Result};
+use core::time::Duration;
+use flows_derive::block;
+
+/// A block that groups input messages into consecutive, non-overlapping
+/// windows of a fixed event-time interval.
+///
+/// The `timestamp` function extracts each message's event time, and windows
+/// are aligned to multiples of `size`. A window is output once the watermark,
+/// which trails the latest event time seen by `lateness`, passes its end.
+/// Messages that arrive for an already-output window are sent to `late`.
+/// Any remaining windows are output in order once the input closes.
+///
+/// Panics in case the specified size is zero.
+#[block]
+pub async fn tumbling_window<T, F>(
+    size: Duration,
+    lateness: Duration,
+    timestamp: F,
+    mut inputs: Inputs<T>,
+    windows: Outputs<Vec<T>>,
+    late: Outputs<T>,
+) -> Result
+where
+    T: Send,
+    F: Fn(&T) -> Duration,
+{
+    assert!(!size.is_zero());
+    let mut watermark = Watermark::new(lateness);
+    let mut pending: BTreeMap<Duration, Vec<T>> = BTreeMap::new();
+
+    while let Some(input) = inputs.recv().await? {
+        let time = timestamp(&input);
+        let start = align(time, size);
+
+        if watermark.has_passed(start + size) {
+            if late.is_connected() {
+                late.send(input).await?;
+            }
+            continue;
+        }
+
+        pending.entry(start).or_default().push(input);
+        watermark.observe(time);
+
+        while let Some(entry) = pending.first_entry() {
+            if !watermark.has_passed(*entry.key() + size) {
+                break; // window still open
+            }
+            let window = entry.remove();
+            if !windows.is_closed() {
+                windows.send(window).await?;
+            }
+        }
+    }
+
+    for (_, window) in pending {
+        if !windows.is_closed() {
+            windows.send(window).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, vec};
+    use async_flow::{Channel, InputPort};
+    use core::error::Error;
+
+    #[tokio::test]
+    async fn test_tumbling_window() -> Result<(), Box<dyn Error>> {
+        let mut in_ = Channel::bounded(10);
+        let (mut out, mut late) = (Channel::bounded(10), Channel::bounded(10));
+        let windower = tokio::spawn(tumbling_window(
+            Duration::from_secs(10),
+            Duration::from_secs(2),
+            |secs: &u64| Duration::from_secs(*secs),
+            in_.rx,
+            out.tx,
+            late.tx,
+        ));
+
+        for input in [1, 5, 11, 9, 13, 3, 25] {
+            in_.tx.send(input).await?;
+        }
+        in_.tx.close();
+
+        let _ = tokio::join!(windower);
+
+        let outputs = out.rx.recv_all().await?;
+        assert_eq!(outputs, vec![vec![1, 5, 9], vec![11, 13], vec![25]]);
+        assert_eq!(late.rx.recv_all().await?, vec![3]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+// This is free and unencumbered software released into the public domain.
+
+use core::time::Duration;
+
+/// An event-time watermark that trails the latest observed timestamp by a
+/// fixed allowed lateness.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Watermark {
+    lateness: Duration,
+    latest: Option<Duration>,
+}
+
+impl Watermark {
+    pub(crate) fn new(lateness: Duration) -> Self {
+        Self {
+            lateness,
+            latest: None,
+        }
+    }
+
+    /// Advances the watermark, if the given timestamp is the latest so far.
+    pub(crate) fn observe(&mut self, timestamp: Duration) {
+        if self.latest.is_none_or(|latest| timestamp > latest) {
+            self.latest = Some(timestamp);
+        }
+    }
+
+    /// Checks whether a window ending at the given time has been closed.
+    pub(crate) fn has_passed(&self, end: Duration) -> bool {
+        self.latest
+            .is_some_and(|latest| end <= latest.saturating_sub(self.lateness))
+    }
+}
+
+/// Rounds the given timestamp down to a multiple of the given interval.
+pub(crate) fn align(timestamp: Duration, interval: Duration) -> Duration {
+    let nanos = timestamp.as_nanos() - timestamp.as_nanos() % interval.as_nanos();
+    Duration::from_nanos(nanos as u64)
+}
@@ -26,6 +26,12 @@ pub use flows_derive as derive;
 #[cfg(feature = "dns")]
 pub use flows_dns as dns;
 
+#[cfg(feature = "error")]
+pub use flows_error as error;
+
+#[cfg(feature = "func")]
+pub use flows_func as func;
+
 #[cfg(feature = "hash")]
 pub use flows_hash as hash;
 
@@ -53,12 +59,21 @@ pub use flows_pubsub as pubsub;
 #[cfg(feature = "rand")]
 pub use flows_rand as rand;
 
+#[cfg(feature = "route")]
+pub use flows_route as route;
+
 #[cfg(feature = "text")]
 pub use flows_text as text;
 
+#[cfg(feature = "time")]
+pub use flows_time as time;
+
 #[cfg(feature = "video")]
 pub use flows_video as video;
 
+#[cfg(feature = "window")]
+pub use flows_window as window;
+
 #[doc = include_str!("../../../README.md")]
 #[cfg(doctest)]
 pub struct ReadmeDoctests;